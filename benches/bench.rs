@@ -99,6 +99,8 @@ make_benches! {
         bench_remove_small => gen_remove(VEC_SIZE as _),
         bench_extend => gen_extend(SPILLED_SIZE as _),
         bench_extend_small => gen_extend(VEC_SIZE as _),
+        bench_extend_copied => gen_extend_copied(SPILLED_SIZE as _),
+        bench_extend_copied_small => gen_extend_copied(VEC_SIZE as _),
         bench_from_iter => gen_from_iter(SPILLED_SIZE as _),
         bench_from_iter_small => gen_from_iter(VEC_SIZE as _),
         bench_from_slice => gen_from_slice(SPILLED_SIZE as _),
@@ -121,6 +123,8 @@ make_benches! {
         bench_remove_vec_small => gen_remove(VEC_SIZE as _),
         bench_extend_vec => gen_extend(SPILLED_SIZE as _),
         bench_extend_vec_small => gen_extend(VEC_SIZE as _),
+        bench_extend_copied_vec => gen_extend_copied(SPILLED_SIZE as _),
+        bench_extend_copied_vec_small => gen_extend_copied(VEC_SIZE as _),
         bench_from_iter_vec => gen_from_iter(SPILLED_SIZE as _),
         bench_from_iter_vec_small => gen_from_iter(VEC_SIZE as _),
         bench_from_slice_vec => gen_from_slice(SPILLED_SIZE as _),
@@ -190,20 +194,23 @@ fn gen_extend<V: Vector<u64>>(n: u64, b: &mut Bencher) {
     });
 }
 
-fn gen_from_iter<V: Vector<u64>>(n: u64, b: &mut Bencher) {
+fn gen_extend_copied<V: Vector<u64>>(n: u64, b: &mut Bencher) {
     let v: Vec<u64> = (0..n).collect();
     b.iter(|| {
-        let vec = V::from(&v);
+        let mut vec = V::new();
+        vec.extend(v.iter().copied());
         vec
     });
 }
 
+fn gen_from_iter<V: Vector<u64>>(n: u64, b: &mut Bencher) {
+    let v: Vec<u64> = (0..n).collect();
+    b.iter(|| V::from(&v));
+}
+
 fn gen_from_slice<V: Vector<u64>>(n: u64, b: &mut Bencher) {
     let v: Vec<u64> = (0..n).collect();
-    b.iter(|| {
-        let vec = V::from_elems(&v);
-        vec
-    });
+    b.iter(|| V::from_elems(&v));
 }
 
 fn gen_extend_from_slice<V: Vector<u64>>(n: u64, b: &mut Bencher) {
@@ -232,10 +239,7 @@ fn gen_pushpop<V: Vector<u64>>(b: &mut Bencher) {
 }
 
 fn gen_from_elem<V: Vector<u64>>(n: usize, b: &mut Bencher) {
-    b.iter(|| {
-        let vec = V::from_elem(42, n);
-        vec
-    });
+    b.iter(|| V::from_elem(42, n));
 }
 
 #[bench]
@@ -269,6 +273,7 @@ fn bench_insert_from_slice(b: &mut Bencher) {
 }
 
 #[bench]
+#[allow(clippy::needless_late_init)]
 fn bench_macro_from_list(b: &mut Bencher) {
     b.iter(|| {
         #[cfg(feature = "const_generics")]
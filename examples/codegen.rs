@@ -0,0 +1,69 @@
+//! Functions whose optimized LLVM IR is checked by `scripts/test-codegen.sh`.
+//!
+//! None of them should write to the inline buffer beyond the elements they store: creating an
+//! empty `SmallVec` with a large inline capacity must not zero or copy that capacity. Filling a
+//! byte vector with copies of one value must be a single `memset`. Getting the elements or the
+//! length of a vector must not branch on whether it's spilled.
+
+use smallvec::SmallVec;
+
+#[cfg(feature = "const_generics")]
+type Big = SmallVec<u8, 1024>;
+#[cfg(not(feature = "const_generics"))]
+type Big = SmallVec<[u8; 1024]>;
+
+#[cfg(feature = "const_generics")]
+type Huge = SmallVec<u8, 8192>;
+#[cfg(not(feature = "const_generics"))]
+type Huge = SmallVec<[u8; 8192]>;
+
+#[no_mangle]
+pub fn codegen_new() -> Big {
+    SmallVec::new()
+}
+
+#[cfg(feature = "alloc")]
+#[no_mangle]
+pub fn codegen_new_boxed() -> Box<Big> {
+    SmallVec::new_boxed()
+}
+
+#[no_mangle]
+pub fn codegen_with_capacity(n: usize) -> Big {
+    SmallVec::with_capacity(n)
+}
+
+#[no_mangle]
+pub fn codegen_from_iter(n: u8) -> Big {
+    (0..n).collect()
+}
+
+#[no_mangle]
+pub fn codegen_from_slice(slice: &[u8]) -> Big {
+    SmallVec::from_slice(slice)
+}
+
+#[no_mangle]
+pub fn codegen_shrink_to_fit(v: &mut Big) {
+    v.shrink_to_fit()
+}
+
+#[no_mangle]
+pub fn codegen_from_elem(n: usize) -> Huge {
+    SmallVec::from_elem(0, n)
+}
+
+#[no_mangle]
+pub fn codegen_resize(v: &mut Big, n: usize) {
+    v.resize(n, 7)
+}
+
+#[no_mangle]
+pub fn codegen_as_slice(v: &Big) -> &[u8] {
+    v
+}
+
+#[no_mangle]
+pub fn codegen_len(v: &Big) -> usize {
+    v.len()
+}
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+
+set -e
+
+# Builds for a target with a 16-bit `usize`, where literals or capacity math that assume a wider
+# `usize` fail to compile.
+
+rustup component add rust-src
+
+for target in msp430-none-elf; do
+    /bin/echo -e "\e[0;33m***** Checking target '${target}' *****\e[0m\n"
+    cargo check --no-default-features --target "${target}" -Z build-std=core,alloc
+done
@@ -0,0 +1,54 @@
+#!/usr/bin/env bash
+
+set -e
+
+# Checks the optimized IR of `examples/codegen.rs` for writes that touch the whole inline buffer
+
+cargo rustc --release --example codegen -- --emit=llvm-ir
+IR=$(ls -t ../target/release/examples/codegen-*.ll | head -n 1)
+
+body() {
+    awk "/^define .*@$1\\(/,/^}/" "$IR"
+}
+
+check_absent() {
+    local function=$1
+    local pattern=$2
+    if [ -z "$(body "$function")" ]; then
+        /bin/echo -e "\e[0;31m***** $function not found in $IR *****\e[0m\n"
+        exit 1
+    fi
+    if body "$function" | grep -qE "$pattern"; then
+        /bin/echo -e "\e[0;31m***** $function matches '$pattern' *****\e[0m\n"
+        body "$function" | grep -E "$pattern"
+        exit 1
+    fi
+}
+
+check_present() {
+    local function=$1
+    local pattern=$2
+    if ! body "$function" | grep -qE "$pattern"; then
+        /bin/echo -e "\e[0;31m***** $function doesn't match '$pattern' *****\e[0m\n"
+        exit 1
+    fi
+}
+
+for function in codegen_new codegen_new_boxed codegen_with_capacity codegen_from_iter codegen_from_slice codegen_shrink_to_fit; do
+    check_absent $function "llvm\\.memset"
+done
+
+for function in codegen_new codegen_new_boxed codegen_with_capacity; do
+    check_absent $function "llvm\\.memcpy"
+done
+
+for function in codegen_from_elem codegen_resize; do
+    check_present $function "llvm\\.memset"
+done
+
+# The capacity tells whether the vector is spilled, which must be a select, not a branch
+for function in codegen_as_slice codegen_len; do
+    check_absent $function "^ *br "
+done
+
+/bin/echo -e "\e[0;32m***** codegen checks passed *****\e[0m\n"
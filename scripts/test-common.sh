@@ -1,10 +1,36 @@
 STABLE_FEATURES=(
+    allocator-api2
+    arbitrary
+    bincode2
+    bytemuck
+    bytes
+    defmt
+    derive
+    embedded-io
+    nom
+    proptest
+    quickcheck
+    rayon
+    rkyv
     serde
     std
+    u32_len
+    union
+    varint
+    wasm
 )
 
 test_with_feature() {
     local feature=$1
     /bin/echo -e "\e[0;33m***** Testing with feature '${feature}' *****\e[0m\n"
-    CARGO_INCREMENTAL=0 cargo test --features "${feature}" --no-default-features --verbose
-}
\ No newline at end of file
+    CARGO_INCREMENTAL=0 cargo test --features "${feature}" --verbose
+}
+
+# FIXME: Remove CARGO_INCREMENTAL. See https://github.com/rust-lang/rust/issues/61338
+# Doctests use the array form, so only the library tests run with constant generics.
+
+test_lib_with_feature() {
+    local feature=$1
+    /bin/echo -e "\e[0;33m***** Testing with feature '${feature}' *****\e[0m\n"
+    CARGO_INCREMENTAL=0 cargo test --features "${feature}" --lib --verbose
+}
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+
+set -e
+
+# Aliasing and provenance checks of the unsafe code
+
+rustup component add miri
+cargo miri setup
+
+for features in "alloc" "std"; do
+    /bin/echo -e "\e[0;33m***** Testing under Miri with features '${features}' *****\e[0m\n"
+    cargo miri test --lib --no-default-features --features "${features}" tests::aliasing
+done
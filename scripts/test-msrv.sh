@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+
+set -e
+
+# The minimum supported Rust version covers the crate with its own features. The optional
+# integrations are left out, since their dependencies set their own minimum versions.
+
+/bin/echo -e "\e[0;33m***** Testing with default features *****\e[0m\n"
+cargo test --verbose
+
+/bin/echo -e "\e[0;33m***** Testing without features *****\e[0m\n"
+cargo test --no-default-features --lib --verbose
+
+for feature in std u32_len union varint; do
+    /bin/echo -e "\e[0;33m***** Testing with feature '${feature}' *****\e[0m\n"
+    cargo test --features "${feature}" --lib --verbose
+done
+
+/bin/echo -e "\e[0;33m***** Testing with feature 'const_generics' *****\e[0m\n"
+cargo test --features const_generics --lib --verbose
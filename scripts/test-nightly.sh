@@ -6,9 +6,10 @@ set -e
 # All nightly features individually
 
 NIGHTLY_FEATURES=(
+    allocator_api
+    extend_one
     may_dangle
     specialization
-    union
 )
 
 for feature in "${NIGHTLY_FEATURES[@]}"; do
@@ -17,19 +18,6 @@ done
 
 # All nightly features individually with constant generics
 
-# FIXME: Remove CARGO_INCREMENTAL. See https://github.com/rust-lang/rust/issues/61338
-# FIXME: Remove --lib once const_generics is default. Doctests are not using constant generics
-
-test_lib_with_feature() {
-    local feature=$1
-    /bin/echo -e "\e[0;33m***** Testing with feature '${feature}' *****\e[0m\n"
-    CARGO_INCREMENTAL=0 cargo test --features "${feature}" --lib --no-default-features --verbose
-}
-
-for feature in "${STABLE_FEATURES[@]}"; do
-    test_lib_with_feature "const_generics, $feature"
-done
-
 for feature in "${NIGHTLY_FEATURES[@]}"; do
     test_lib_with_feature "const_generics, $feature"
 done
@@ -37,7 +25,7 @@ done
 # All features
 
 /bin/echo -e "\e[0;33m***** Testing all features *****\e[0m\n"
-CARGO_INCREMENTAL=0 cargo test --all-features --lib --no-default-features --verbose
+CARGO_INCREMENTAL=0 cargo test --all-features --lib --verbose
 
 # Run bench
 
@@ -3,10 +3,15 @@
 set -e
 . ./test-common.sh --source-only
 
-# No features
+# Default features
+
+/bin/echo -e "\e[0;33m***** Testing with default features *****\e[0m\n"
+cargo test --verbose
+
+# Only `core`, without the default `alloc` feature
 
 /bin/echo -e "\e[0;33m***** Testing without features *****\e[0m\n"
-cargo test --no-default-features --verbose
+cargo test --no-default-features --lib --verbose
 
 # All stable features individually
 
@@ -17,3 +22,17 @@ done
 # All stable features at once
 
 test_with_feature $(IFS=, ; echo "${STABLE_FEATURES[*]}")
+
+# Constant generics, alone and with each stable feature
+
+test_lib_with_feature const_generics
+test_lib_with_feature const_new
+
+# The doc examples written for the constant generics form
+
+/bin/echo -e "\e[0;33m***** Testing the constant generics doc examples *****\e[0m\n"
+cargo test --features const_generics --doc --verbose -- src/lib.rs extend_from_array
+
+for feature in "${STABLE_FEATURES[@]}"; do
+    test_lib_with_feature "const_generics, $feature"
+done
@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+
+set -e
+
+# The conversions of the `wasm` feature call into JS, so their tests only run on a wasm target,
+# in Node, through the test runner matching the `wasm-bindgen` version in use.
+
+rustup target add wasm32-unknown-unknown
+cargo install wasm-bindgen-cli --version "$(cargo pkgid wasm-bindgen | cut -d '@' -f 2)"
+
+for features in "wasm" "wasm, const_generics"; do
+    /bin/echo -e "\e[0;33m***** Testing with features '${features}' on wasm32 *****\e[0m\n"
+    CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+        cargo test --target wasm32-unknown-unknown --features "${features}" --lib --verbose test_wasm
+done
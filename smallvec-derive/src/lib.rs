@@ -0,0 +1,233 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Derive macros for `smallvec`. Use them through the `derive` feature of `smallvec` rather than
+//! depending on this crate directly.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Fields};
+
+/// Generates a "struct of `SmallVec`s" container for a struct with named fields.
+///
+/// For a `struct Particle` annotated with `#[soa(inline = N)]`, this generates:
+///
+/// - `ParticleSoA`, which stores each field in its own `SmallVec` holding up to `N` values
+///   inline, with `new`, `len`, `is_empty`, `push`, `pop`, `get`, `get_mut`, `swap_remove`,
+///   `truncate`, `clear` and `iter`, plus a `field()`/`field_mut()` pair of slice accessors for
+///   every field;
+/// - `ParticleSoARef<'a>` and `ParticleSoAMut<'a>`, holding references to the fields of one
+///   element.
+///
+/// Field names must not clash with the names of the container's methods.
+#[proc_macro_derive(SmallVecSoA, attributes(soa))]
+pub fn derive_small_vec_soa(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    soa(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn soa(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`SmallVecSoA` doesn't support generic structs",
+        ));
+    }
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) if !fields.named.is_empty() => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`SmallVecSoA` needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`SmallVecSoA` can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut inline: Option<Expr> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("soa"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("inline") {
+                inline = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `inline = <size>`"))
+            }
+        })?;
+    }
+    let inline = inline.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "`SmallVecSoA` needs the inline capacity, as in `#[soa(inline = 8)]`",
+        )
+    })?;
+
+    let soa = format_ident!("{}SoA", name);
+    let soa_ref = format_ident!("{}SoARef", name);
+    let soa_mut = format_ident!("{}SoAMut", name);
+    let idents: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let idents_mut: Vec<_> = idents.iter().map(|f| format_ident!("{}_mut", f)).collect();
+    let tys: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let vises: Vec<_> = fields.iter().map(|f| &f.vis).collect();
+    let first = idents[0];
+
+    let soa_doc = format!("A struct of `SmallVec`s holding `{}` values.", name);
+    let ref_doc = format!(
+        "References to the fields of a `{}` stored in a `{}`.",
+        name, soa
+    );
+    let mut_doc = format!(
+        "Mutable references to the fields of a `{}` stored in a `{}`.",
+        name, soa
+    );
+
+    Ok(quote! {
+        #[doc = #soa_doc]
+        #vis struct #soa {
+            #( #idents: ::smallvec::__SoaVec<#tys, { #inline }>, )*
+        }
+
+        #[doc = #ref_doc]
+        #[allow(dead_code)]
+        #[derive(Clone, Copy)]
+        #vis struct #soa_ref<'a> {
+            #( #vises #idents: &'a #tys, )*
+        }
+
+        #[doc = #mut_doc]
+        #[allow(dead_code)]
+        #vis struct #soa_mut<'a> {
+            #( #vises #idents: &'a mut #tys, )*
+        }
+
+        #[allow(dead_code)]
+        impl #soa {
+            /// Construct an empty container
+            #[inline]
+            #vis fn new() -> Self {
+                #soa {
+                    #( #idents: ::smallvec::SmallVec::new(), )*
+                }
+            }
+
+            /// The number of elements in the container
+            #[inline]
+            #vis fn len(&self) -> usize {
+                self.#first.len()
+            }
+
+            /// Returns `true` if the container is empty
+            #[inline]
+            #vis fn is_empty(&self) -> bool {
+                self.#first.is_empty()
+            }
+
+            /// Append an element to the container.
+            #vis fn push(&mut self, value: #name) {
+                let #name { #( #idents, )* } = value;
+                #( self.#idents.push(#idents); )*
+            }
+
+            /// Remove the last element and return it, or `None` if the container is empty.
+            #vis fn pop(&mut self) -> ::core::option::Option<#name> {
+                if self.is_empty() {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(#name {
+                    #( #idents: self.#idents.pop().unwrap(), )*
+                })
+            }
+
+            /// Returns references to the fields of the element at `index`, if any.
+            #[inline]
+            #vis fn get(&self, index: usize) -> ::core::option::Option<#soa_ref<'_>> {
+                if index >= self.len() {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(#soa_ref {
+                    #( #idents: &self.#idents[index], )*
+                })
+            }
+
+            /// Returns mutable references to the fields of the element at `index`, if any.
+            #[inline]
+            #vis fn get_mut(&mut self, index: usize) -> ::core::option::Option<#soa_mut<'_>> {
+                if index >= self.len() {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(#soa_mut {
+                    #( #idents: &mut self.#idents[index], )*
+                })
+            }
+
+            /// Remove the element at `index` and return it, replacing it with the last element.
+            ///
+            /// Panics if `index` is out of bounds.
+            #[track_caller]
+            #vis fn swap_remove(&mut self, index: usize) -> #name {
+                #name {
+                    #( #idents: self.#idents.swap_remove(index), )*
+                }
+            }
+
+            /// Shorten the container, keeping the first `len` elements.
+            #vis fn truncate(&mut self, len: usize) {
+                #( self.#idents.truncate(len); )*
+            }
+
+            /// Remove all the elements.
+            #vis fn clear(&mut self) {
+                #( self.#idents.clear(); )*
+            }
+
+            /// An iterator over references to the fields of each element.
+            #vis fn iter(&self) -> impl ::core::iter::Iterator<Item = #soa_ref<'_>> + '_ {
+                (0..self.len()).map(move |index| #soa_ref {
+                    #( #idents: &self.#idents[index], )*
+                })
+            }
+
+            #(
+                /// The values of this field, for all the elements.
+                #[inline]
+                #vises fn #idents(&self) -> &[#tys] {
+                    &self.#idents
+                }
+
+                /// The values of this field, for all the elements.
+                #[inline]
+                #vises fn #idents_mut(&mut self) -> &mut [#tys] {
+                    &mut self.#idents
+                }
+            )*
+        }
+
+        impl ::core::default::Default for #soa {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    })
+}
@@ -0,0 +1,86 @@
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+use crate::utils::allocate;
+use crate::CollectionAllocErr;
+#[cfg(feature = "allocator_api")]
+pub use alloc::alloc::{Allocator, Global};
+#[cfg(all(
+    feature = "alloc",
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+use alloc::vec::Vec;
+#[cfg(all(feature = "allocator-api2", not(feature = "allocator_api")))]
+pub use allocator_api2::alloc::{Allocator, Global};
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use {crate::utils::layout_array, core::ptr::NonNull};
+
+/// The allocator of the spilled storage when vectors can't be given one.
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Global;
+
+/// Management of the heap buffer of a spilled vector, sized in elements.
+pub trait RawAlloc {
+    /// Allocates a buffer for `capacity` elements.
+    fn allocate_array<T>(&self, capacity: usize) -> Result<*mut T, CollectionAllocErr>;
+
+    /// Frees a buffer previously obtained from this allocator.
+    unsafe fn deallocate_array<T>(&self, ptr: *mut T, capacity: usize);
+}
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl RawAlloc for Global {
+    #[inline]
+    fn allocate_array<T>(&self, capacity: usize) -> Result<*mut T, CollectionAllocErr> {
+        allocate(capacity)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    unsafe fn deallocate_array<T>(&self, ptr: *mut T, capacity: usize) {
+        let _vec: Vec<T> = Vec::from_raw_parts(ptr, 0, capacity);
+        // Let it drop.
+    }
+
+    // Without a heap, only empty buffers are ever allocated.
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    unsafe fn deallocate_array<T>(&self, _ptr: *mut T, _capacity: usize) {}
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+impl<A: Allocator> RawAlloc for A {
+    fn allocate_array<T>(&self, capacity: usize) -> Result<*mut T, CollectionAllocErr> {
+        let layout = layout_array::<T>(capacity)?;
+        if layout.size() == 0 {
+            return Ok(NonNull::dangling().as_ptr());
+        }
+        match self.allocate(layout) {
+            Ok(ptr) => Ok(ptr.cast().as_ptr()),
+            Err(_) => Err(CollectionAllocErr::AllocErr { layout }),
+        }
+    }
+
+    unsafe fn deallocate_array<T>(&self, ptr: *mut T, capacity: usize) {
+        // The layout was already computed successfully when the buffer was allocated.
+        if let Ok(layout) = layout_array::<T>(capacity) {
+            if layout.size() != 0 {
+                self.deallocate(NonNull::new_unchecked(ptr as *mut u8), layout);
+            }
+        }
+    }
+}
+
+/// Frees a heap buffer when dropped, so that it is released even if dropping its elements
+/// panics.
+pub struct DeallocOnDrop<'a, A: RawAlloc, T> {
+    pub alloc: &'a A,
+    pub ptr: *mut T,
+    pub capacity: usize,
+}
+
+impl<'a, A: RawAlloc, T> Drop for DeallocOnDrop<'a, A, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.alloc.deallocate_array(self.ptr, self.capacity) }
+    }
+}
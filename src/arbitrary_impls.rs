@@ -0,0 +1,253 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{
+    SmallBinaryHeap, SmallBitVec, SmallMap, SmallOrdMap, SmallOrdSet, SmallSet, SmallString,
+    SmallVec, SmallVec1, SmallVecDeque,
+};
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+
+// The collections are generated like `Vec`: elements are read for as long as the input says to
+// continue, so no fixed number of bytes is needed, and `size_hint` is `(0, None)`.
+
+macro_rules! impl_for_items {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallVec<$($decl_generics)*>
+where
+    $item: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallVec1<$($decl_generics)*>
+where
+    $item: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut vec = SmallVec1::new(u.arbitrary()?);
+        for item in u.arbitrary_iter()? {
+            vec.push(item?);
+        }
+        Ok(vec)
+    }
+
+    fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<Self> {
+        let mut vec = SmallVec1::new(u.arbitrary()?);
+        for item in u.arbitrary_take_rest_iter()? {
+            vec.push(item?);
+        }
+        Ok(vec)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(<$item as Arbitrary<'a>>::size_hint(depth), (0, None))
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallVecDeque<$($decl_generics)*>
+where
+    $item: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallBinaryHeap<$($decl_generics)*>
+where
+    $item: Arbitrary<'a> + Ord,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallSet<$($decl_generics)*>
+where
+    $item: Arbitrary<'a> + Eq,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallOrdSet<$($decl_generics)*>
+where
+    $item: Arbitrary<'a> + Ord,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+    }
+}
+
+macro_rules! impl_for_maps {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallMap<$($decl_generics)*>
+where
+    K: Arbitrary<'a> + Eq,
+    V: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallOrdMap<$($decl_generics)*>
+where
+    K: Arbitrary<'a> + Ord,
+    V: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+    }
+}
+
+macro_rules! impl_for_string {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        <&str>::arbitrary(u).map(Self::from)
+    }
+
+    #[inline]
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        <&str>::arbitrary_take_rest(u).map(Self::from)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&str>::size_hint(depth)
+    }
+}
+
+    }
+}
+
+macro_rules! impl_for_bits {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<'a, $($impl_generics)*> Arbitrary<'a> for SmallBitVec<$($decl_generics)*> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl_for_items!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+impl_for_items!([A: Array], [A], A::Item);
+
+#[cfg(feature = "const_generics")]
+impl_for_maps!([K, V, const N: usize], [K, V, { N }]);
+#[cfg(not(feature = "const_generics"))]
+impl_for_maps!([K, V, A: Array<Item = (K, V)>], [K, V, A]);
+
+#[cfg(feature = "const_generics")]
+impl_for_string!([const N: usize], [{ N }]);
+#[cfg(not(feature = "const_generics"))]
+impl_for_string!([A: Array<Item = u8>], [A]);
+
+#[cfg(feature = "const_generics")]
+impl_for_bits!([const WORDS: usize], [{ WORDS }]);
+#[cfg(not(feature = "const_generics"))]
+impl_for_bits!([A: Array<Item = u64>], [A]);
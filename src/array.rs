@@ -1,4 +1,13 @@
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use crate::allocator::Allocator;
+use crate::SmallVec;
+use core::{convert::TryFrom, iter::FromIterator};
+
 /// Types that can be used as the backing store for a SmallVec
+///
+/// # Safety
+///
+/// `as_ptr`/`as_mut_ptr` must point to `size()` contiguous `Item`s owned by the array.
 pub unsafe trait Array {
     /// The type of the array's elements.
     type Item;
@@ -10,6 +19,8 @@ pub unsafe trait Array {
     fn size() -> usize;
 }
 
+// `Extend<&T>`, `FromIterator<&T>` and `TryFrom<SmallVec>` are implemented for each array size,
+// see `small_vec.rs`.
 macro_rules! impl_array(
     ($($size:expr),+) => {
         $(
@@ -19,12 +30,71 @@ macro_rules! impl_array(
                 fn as_ptr(&self) -> *const T { self.as_ref().as_ptr() }
                 fn size() -> usize { $size }
             }
+
+            #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+            impl<'a, T: Copy + 'a, Alloc: Allocator> Extend<&'a T> for SmallVec<[T; $size], Alloc> {
+                #[inline]
+                fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iterable: I) {
+                    self.extend(iterable.into_iter().copied())
+                }
+
+                #[cfg(feature = "extend_one")]
+                #[inline]
+                fn extend_one(&mut self, &item: &'a T) {
+                    self.push(item);
+                }
+
+                #[cfg(feature = "extend_one")]
+                #[inline]
+                fn extend_reserve(&mut self, additional: usize) {
+                    self.reserve(additional);
+                }
+            }
+
+            #[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+            impl<'a, T: Copy + 'a> Extend<&'a T> for SmallVec<[T; $size]> {
+                #[inline]
+                fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iterable: I) {
+                    self.extend(iterable.into_iter().copied())
+                }
+
+                #[cfg(feature = "extend_one")]
+                #[inline]
+                fn extend_one(&mut self, &item: &'a T) {
+                    self.push(item);
+                }
+
+                #[cfg(feature = "extend_one")]
+                #[inline]
+                fn extend_reserve(&mut self, additional: usize) {
+                    self.reserve(additional);
+                }
+            }
+
+            impl<'a, T: Copy + 'a> FromIterator<&'a T> for SmallVec<[T; $size]> {
+                #[inline]
+                fn from_iter<I: IntoIterator<Item = &'a T>>(iterable: I) -> Self {
+                    iterable.into_iter().copied().collect()
+                }
+            }
+
+            impl<T, A: Array<Item = T>> TryFrom<SmallVec<A>> for [T; $size] {
+                type Error = SmallVec<A>;
+
+                #[inline]
+                fn try_from(vec: SmallVec<A>) -> Result<Self, SmallVec<A>> {
+                    vec.try_into_array()
+                }
+            }
         )+
     }
 );
 
 impl_array!(
     0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 20, 24, 32, 36, 0x40, 0x80, 0x100,
-    0x200, 0x400, 0x800, 0x1000, 0x2000, 0x4000, 0x8000, 0x10000, 0x20000, 0x40000, 0x80000,
-    0x100_000
+    0x200, 0x400, 0x800, 0x1000, 0x2000, 0x4000, 0x8000
 );
+
+// These lengths don't fit in a 16-bit `usize`.
+#[cfg(not(target_pointer_width = "16"))]
+impl_array!(0x10000, 0x20000, 0x40000, 0x80000, 0x100_000);
@@ -0,0 +1,77 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use bincode2::{
+    de::{BorrowDecode, BorrowDecoder, Decode, Decoder},
+    enc::{Encode, Encoder},
+    error::{DecodeError, EncodeError},
+};
+use core::{convert::TryFrom, mem};
+
+// Vectors are encoded like a `Vec<T>`, as a `u64` length followed by the elements, so the two
+// can be decoded from each other's encoding.
+
+fn decode_len<D: Decoder>(decoder: &mut D) -> Result<usize, DecodeError> {
+    let len = u64::decode(decoder)?;
+    usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<$($impl_generics)*> Encode for SmallVec<$($decl_generics)*>
+where
+    $item: Encode,
+{
+    #[inline]
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self[..].encode(encoder)
+    }
+}
+
+impl<Context, $($impl_generics)*> Decode<Context> for SmallVec<$($decl_generics)*>
+where
+    $item: Decode<Context>,
+{
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let len = decode_len(decoder)?;
+        decoder.claim_container_read::<$item>(len)?;
+        let mut vec = SmallVec::with_capacity(len);
+        for _ in 0..len {
+            // Each element claims its own size again while being decoded.
+            decoder.unclaim_bytes_read(mem::size_of::<$item>());
+            vec.push(<$item>::decode(decoder)?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<'de, Context, $($impl_generics)*> BorrowDecode<'de, Context> for SmallVec<$($decl_generics)*>
+where
+    $item: BorrowDecode<'de, Context>,
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        let len = decode_len(decoder)?;
+        decoder.claim_container_read::<$item>(len)?;
+        let mut vec = SmallVec::with_capacity(len);
+        for _ in 0..len {
+            decoder.unclaim_bytes_read(mem::size_of::<$item>());
+            vec.push(<$item>::borrow_decode(decoder)?);
+        }
+        Ok(vec)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
@@ -0,0 +1,175 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use bytemuck::{cast_slice, cast_slice_mut, NoUninit, Pod, PodCastError, Zeroable};
+use core::mem;
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<$($impl_generics)*> SmallVec<$($decl_generics)*>
+where
+    $item: NoUninit,
+{
+    /// Returns the elements as their bytes.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u16; 2]> = SmallVec::from_slice(&[1, 0x0203]);
+    /// assert_eq!(v.as_bytes(), &[1, 0, 3, 2][..]);
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        cast_slice(self)
+    }
+}
+
+impl<$($impl_generics)*> SmallVec<$($decl_generics)*>
+where
+    $item: Pod,
+{
+    /// Returns the elements as their mutable bytes.
+    #[inline]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        cast_slice_mut(self)
+    }
+
+    /// Build a vector out of a copy of `bytes`, which don't need to be aligned for the element
+    /// type.
+    ///
+    /// Fails with `PodCastError::OutputSliceWouldHaveSlop` if the length of `bytes` isn't a
+    /// multiple of the size of an element.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v = SmallVec::<[[f32; 2]; 4]>::try_from_bytes(&[0; 16]).unwrap();
+    /// assert_eq!(&v[..], &[[0.0; 2]; 2]);
+    /// assert!(SmallVec::<[f32; 4]>::try_from_bytes(&[0; 3]).is_err());
+    /// ```
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, PodCastError> {
+        let size = mem::size_of::<$item>();
+        if size == 0 {
+            return if bytes.is_empty() {
+                Ok(SmallVec::new())
+            } else {
+                Err(PodCastError::SizeMismatch)
+            };
+        }
+        if bytes.len() % size != 0 {
+            return Err(PodCastError::OutputSliceWouldHaveSlop);
+        }
+        let mut vec = SmallVec::from_elem(<$item as Zeroable>::zeroed(), bytes.len() / size);
+        vec.as_bytes_mut().copy_from_slice(bytes);
+        Ok(vec)
+    }
+}
+
+    }
+}
+
+macro_rules! create_cast_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($from_generics:tt)*],
+    [$($to_generics:tt)*],
+    $from_item:ty,
+    $to_item:ty
+) => {
+
+/// Convert a vector of `Pod` elements into a vector of another `Pod` type with the same bytes.
+///
+/// The heap buffer of a spilled vector is reused when both types have the same alignment, its
+/// size is a multiple of the new element size, and the new capacity is larger than the inline
+/// capacity of the new vector. Otherwise the bytes are copied into a new vector, inline if they
+/// fit.
+///
+/// Fails and gives the vector back if its bytes can't be split evenly into new elements.
+///
+/// ```rust
+/// use smallvec::{try_cast_smallvec, SmallVec};
+///
+/// let vertices: SmallVec<[[f32; 4]; 8]> = SmallVec::from_elem([1.0; 4], 16);
+/// let ptr = vertices.as_ptr() as *const f32;
+/// let floats: SmallVec<[f32; 4]> = try_cast_smallvec(vertices).unwrap();
+/// assert_eq!(floats.len(), 64);
+/// assert_eq!(floats.as_ptr(), ptr);
+/// ```
+pub fn try_cast_smallvec<$($impl_generics)*>(
+    vec: SmallVec<$($from_generics)*>,
+) -> Result<SmallVec<$($to_generics)*>, (PodCastError, SmallVec<$($from_generics)*>)>
+where
+    $from_item: Pod,
+    $to_item: Pod,
+{
+    let (from_size, to_size) = (mem::size_of::<$from_item>(), mem::size_of::<$to_item>());
+    if from_size == 0 || to_size == 0 {
+        return if from_size == to_size {
+            Ok(SmallVec::from_elem(<$to_item as Zeroable>::zeroed(), vec.len()))
+        } else {
+            Err((PodCastError::SizeMismatch, vec))
+        };
+    }
+    let bytes = vec.len() * from_size;
+    if bytes % to_size != 0 {
+        return Err((PodCastError::OutputSliceWouldHaveSlop, vec));
+    }
+    let capacity_bytes = vec.capacity() * from_size;
+    let reusable = vec.spilled()
+        && mem::align_of::<$from_item>() == mem::align_of::<$to_item>()
+        && capacity_bytes % to_size == 0
+        && capacity_bytes / to_size > SmallVec::<$($to_generics)*>::new().inline_size();
+    if reusable {
+        let (ptr, _, _) = vec.into_raw_parts();
+        // Same alignment and allocation size, so the buffer can be deallocated as the new type.
+        unsafe {
+            Ok(SmallVec::from_raw_parts(
+                ptr as *mut $to_item,
+                bytes / to_size,
+                capacity_bytes / to_size,
+            ))
+        }
+    } else {
+        Ok(SmallVec::try_from_bytes(vec.as_bytes()).unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+/// Like [`try_cast_smallvec`](fn.try_cast_smallvec.html), but panics if the conversion fails.
+#[inline]
+#[track_caller]
+pub fn cast_smallvec<$($impl_generics)*>(
+    vec: SmallVec<$($from_generics)*>,
+) -> SmallVec<$($to_generics)*>
+where
+    $from_item: Pod,
+    $to_item: Pod,
+{
+    match try_cast_smallvec(vec) {
+        Ok(vec) => vec,
+        Err((error, _)) => panic!("cast_smallvec: {:?}", error),
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
+
+#[cfg(feature = "const_generics")]
+create_cast_with_parts!(
+    [T, U, const N: usize, const M: usize],
+    [T, { N }],
+    [U, { M }],
+    T,
+    U
+);
+#[cfg(not(feature = "const_generics"))]
+create_cast_with_parts!([A: Array, B: Array], [A], [B], A::Item, B::Item);
@@ -0,0 +1,47 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use bytes::{Bytes, BytesMut};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<$($impl_generics)*> From<SmallVec<$($decl_generics)*>> for Bytes {
+    /// Hand the heap buffer of a spilled vector over without copying. Inline bytes are copied
+    /// into a new allocation.
+    ///
+    /// ```rust
+    /// use bytes::Bytes;
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u8; 2]> = SmallVec::from_slice(b"abc");
+    /// let ptr = v.as_ptr();
+    /// let bytes = Bytes::from(v);
+    /// assert_eq!(bytes.as_ptr(), ptr);
+    /// assert_eq!(&bytes[..], b"abc");
+    /// ```
+    #[inline]
+    fn from(vec: SmallVec<$($decl_generics)*>) -> Self {
+        Bytes::from(vec.into_vec())
+    }
+}
+
+impl<$($impl_generics)*> From<SmallVec<$($decl_generics)*>> for BytesMut {
+    /// Hand the heap buffer of a spilled vector over without copying. Inline bytes are copied
+    /// into a new allocation.
+    #[inline]
+    fn from(vec: SmallVec<$($decl_generics)*>) -> Self {
+        BytesMut::from(Bytes::from(vec))
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);
@@ -0,0 +1,195 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::ptr;
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A cursor over a `SmallVec` that can insert and remove elements while walking it.
+///
+/// While the cursor is alive the vector's storage is split in two runs separated by a gap of
+/// spare capacity: the elements before the cursor stay at the start of the buffer and the
+/// current element and everything after it sit at the end of the gap. Moving, inserting and
+/// removing only touch the elements next to the gap, and the runs are joined again with a single
+/// move when the cursor is dropped.
+///
+/// Returned from [`SmallVec::cursor_mut`][1].
+///
+/// [1]: struct.SmallVec.html#method.cursor_mut
+pub struct CursorMut<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    pub(crate) vec: &'a mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+    // Number of elements before the cursor, stored at `0..head`.
+    pub(crate) head: usize,
+    // The current element and the ones after it are stored at `back..back + tail`.
+    pub(crate) back: usize,
+    pub(crate) tail: usize,
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*>
+    CursorMut<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    /// Returns the index of the current element, or the length of the vector if the cursor is
+    /// past the end.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.head
+    }
+
+    /// Returns the number of elements in the vector, including those inserted through the
+    /// cursor.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.head + self.tail
+    }
+
+    /// Returns `true` if the vector is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a mutable reference to the current element, or `None` if the cursor is past the
+    /// end.
+    #[inline]
+    pub fn current(&mut self) -> Option<&mut $array_item> {
+        if self.tail == 0 {
+            return None;
+        }
+        unsafe { Some(&mut *self.vec.triple_mut().0.add(self.back)) }
+    }
+
+    /// Returns a mutable reference to the element before the current one, or `None` if the
+    /// cursor is at the start.
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<&mut $array_item> {
+        if self.head == 0 {
+            return None;
+        }
+        unsafe { Some(&mut *self.vec.triple_mut().0.add(self.head - 1)) }
+    }
+
+    /// Moves the cursor to the next element. Returns `false` (and doesn't move) if the cursor
+    /// was already past the end.
+    #[inline]
+    pub fn move_next(&mut self) -> bool {
+        if self.tail == 0 {
+            return false;
+        }
+        unsafe {
+            let ptr = self.vec.triple_mut().0;
+            if self.head != self.back {
+                ptr::copy_nonoverlapping(ptr.add(self.back), ptr.add(self.head), 1);
+            }
+        }
+        self.head += 1;
+        self.back += 1;
+        self.tail -= 1;
+        true
+    }
+
+    /// Moves the cursor to the previous element. Returns `false` (and doesn't move) if the
+    /// cursor was already at the start.
+    #[inline]
+    pub fn move_prev(&mut self) -> bool {
+        if self.head == 0 {
+            return false;
+        }
+        self.head -= 1;
+        self.back -= 1;
+        self.tail += 1;
+        unsafe {
+            let ptr = self.vec.triple_mut().0;
+            if self.head != self.back {
+                ptr::copy_nonoverlapping(ptr.add(self.head), ptr.add(self.back), 1);
+            }
+        }
+        true
+    }
+
+    /// Removes the current element and returns it. The cursor then points to the element that
+    /// followed it. Returns `None` if the cursor is past the end.
+    #[inline]
+    pub fn remove_current(&mut self) -> Option<$array_item> {
+        if self.tail == 0 {
+            return None;
+        }
+        unsafe {
+            let item = ptr::read(self.vec.triple_mut().0.add(self.back));
+            self.back += 1;
+            self.tail -= 1;
+            Some(item)
+        }
+    }
+
+    /// Inserts an element before the current one. The cursor keeps pointing to the same
+    /// element.
+    pub fn insert_before(&mut self, value: $array_item) {
+        self.open_gap();
+        unsafe {
+            ptr::write(self.vec.triple_mut().0.add(self.head), value);
+        }
+        self.head += 1;
+    }
+
+    /// Inserts an element after the current one, or at the end of the vector if the cursor is
+    /// past the end. The cursor keeps pointing to the same element.
+    pub fn insert_after(&mut self, value: $array_item) {
+        if self.tail == 0 {
+            return self.insert_before(value);
+        }
+        self.open_gap();
+        unsafe {
+            let ptr = self.vec.triple_mut().0;
+            ptr::copy_nonoverlapping(ptr.add(self.back), ptr.add(self.back - 1), 1);
+            ptr::write(ptr.add(self.back), value);
+        }
+        self.back -= 1;
+        self.tail += 1;
+    }
+
+    /// Makes sure there is at least one free slot between the two runs.
+    fn open_gap(&mut self) {
+        if self.head != self.back {
+            return;
+        }
+        unsafe {
+            let len = self.len();
+            let (_, len_ptr, cap) = self.vec.triple_mut();
+            if len == cap {
+                // Both runs are contiguous, so the vector can be grown as usual.
+                len_ptr.set(len);
+                self.vec.reserve(1);
+                self.vec.triple_mut().1.set(0);
+            }
+            let (ptr, _, cap) = self.vec.triple_mut();
+            let back = cap - self.tail;
+            ptr::copy(ptr.add(self.back), ptr.add(back), self.tail);
+            self.back = back;
+        }
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
+    for CursorMut<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn drop(&mut self) {
+        unsafe {
+            let (ptr, len_ptr, _) = self.vec.triple_mut();
+            ptr::copy(ptr.add(self.back), ptr.add(self.head), self.tail);
+            len_ptr.set(self.head + self.tail);
+        }
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
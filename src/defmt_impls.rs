@@ -0,0 +1,29 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use defmt::{Format, Formatter};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<$($impl_generics)*> Format for SmallVec<$($decl_generics)*>
+where
+    $item: Format,
+{
+    #[inline]
+    fn format(&self, fmt: Formatter) {
+        Format::format(&**self, fmt)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
@@ -1,4 +1,5 @@
-use core::{ptr, slice};
+use crate::small_vec_data::NonMaxUsize;
+use core::{mem, ptr, slice};
 
 /// An iterator that removes the items from a `SmallVec` and yields them by value.
 ///
@@ -7,8 +8,72 @@ use core::{ptr, slice};
 /// [1]: struct.SmallVec.html#method.drain
 pub struct Drain<'a, T: 'a> {
     pub(crate) iter: slice::IterMut<'a, T>,
+    // The length of the vector, which only covers the elements before the drained range until
+    // the iterator is dropped.
+    pub(crate) len: &'a mut NonMaxUsize,
+    pub(crate) ptr: *mut T,
+    // The elements after the drained range, moved back next to the ones before it on drop.
+    pub(crate) tail_start: usize,
+    pub(crate) tail_len: usize,
 }
 
+impl<'a, T: 'a> Drain<'a, T> {
+    /// Returns the elements that haven't been yielded yet.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2, 3]);
+    /// let mut drain = v.drain(..);
+    /// drain.next();
+    /// assert_eq!(drain.as_slice(), &[2, 3]);
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// Stop draining and keep the elements that haven't been yielded yet in the vector, in
+    /// their place.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2, 3, 4, 5]);
+    /// let mut drain = v.drain(1..4);
+    /// assert_eq!(drain.next(), Some(2));
+    /// drain.keep_rest();
+    /// assert_eq!(&*v, &[1, 3, 4, 5]);
+    /// ```
+    pub fn keep_rest(self) {
+        let mut this = mem::ManuallyDrop::new(self);
+        let start = this.len.get();
+        let unyielded_len = this.iter.len();
+        // Zero-sized elements don't need to be moved.
+        if mem::size_of::<T>() != 0 {
+            unsafe {
+                let unyielded = this.iter.as_slice().as_ptr();
+                let start_ptr = this.ptr.add(start);
+                if !ptr::eq(unyielded, start_ptr) {
+                    ptr::copy(unyielded, start_ptr, unyielded_len);
+                }
+                if this.tail_start != start + unyielded_len {
+                    ptr::copy(
+                        this.ptr.add(this.tail_start),
+                        start_ptr.add(unyielded_len),
+                        this.tail_len,
+                    );
+                }
+            }
+        }
+        let len = start + unyielded_len + this.tail_len;
+        this.len.set(len);
+    }
+}
+
+unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+
 impl<'a, T: 'a> Iterator for Drain<'a, T> {
     type Item = T;
 
@@ -38,7 +103,29 @@ impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
 
 impl<'a, T: 'a> Drop for Drain<'a, T> {
     fn drop(&mut self) {
-        // Destroy the remaining elements.
-        for _ in self.by_ref() {}
+        // Moves the tail back even if dropping one of the remaining elements panics.
+        struct MoveTail<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<'r, 'a, T> Drop for MoveTail<'r, 'a, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = drain.len.get();
+                if drain.tail_len > 0 && drain.tail_start != start {
+                    unsafe {
+                        ptr::copy(
+                            drain.ptr.add(drain.tail_start),
+                            drain.ptr.add(start),
+                            drain.tail_len,
+                        );
+                    }
+                }
+                drain.len.set(start + drain.tail_len);
+            }
+        }
+
+        let remaining = mem::replace(&mut self.iter, <&mut [T]>::default().iter_mut()).into_slice();
+        let remaining = remaining as *mut [T];
+        let _guard = MoveTail(self);
+        unsafe { ptr::drop_in_place(remaining) };
     }
 }
@@ -0,0 +1,92 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{CollectionAllocErr, IntoIter, SmallVec};
+use core::{cmp, convert::Infallible};
+use embedded_io::{BufRead, Error, ErrorKind, ErrorType, Read, Write};
+
+impl Error for CollectionAllocErr {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::OutOfMemory
+    }
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<$($impl_generics)*> ErrorType for SmallVec<$($decl_generics)*> {
+    type Error = CollectionAllocErr;
+}
+
+impl<$($impl_generics)*> Write for SmallVec<$($decl_generics)*> {
+    /// Append all of `buf`, or nothing if the vector can't grow enough.
+    ///
+    /// ```rust
+    /// use embedded_io::Write;
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::new();
+    /// write!(v, "{}-{}", 1, 2).unwrap();
+    /// v.write_all(b"!").unwrap();
+    /// assert_eq!(&v[..], b"1-2!");
+    /// ```
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, CollectionAllocErr> {
+        self.try_extend_from_slice(buf)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), CollectionAllocErr> {
+        Ok(())
+    }
+}
+
+impl<$($impl_generics)*> ErrorType for IntoIter<$($decl_generics)*> {
+    type Error = Infallible;
+}
+
+impl<$($impl_generics)*> BufRead for IntoIter<$($decl_generics)*> {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Infallible> {
+        Ok(self.as_slice())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.current += cmp::min(amt, self.end - self.current);
+    }
+}
+
+impl<$($impl_generics)*> Read for IntoIter<$($decl_generics)*> {
+    /// Move the bytes that haven't been read yet out of the vector.
+    ///
+    /// ```rust
+    /// use embedded_io::Read;
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u8; 8]> = SmallVec::from_slice(b"hello");
+    /// let mut reader = v.into_iter();
+    /// let mut buf = [0; 4];
+    /// assert_eq!(reader.read(&mut buf).unwrap(), 4);
+    /// assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    /// assert_eq!(&buf[..1], b"o");
+    /// ```
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+        let n = Read::read(&mut self.as_slice(), buf)?;
+        self.current += n;
+        Ok(n)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);
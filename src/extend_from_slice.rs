@@ -1,3 +1,4 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 /// Trait to be implemented by a collection that can be extended from a slice
@@ -24,6 +25,7 @@ pub trait ExtendFromSlice<T> {
     fn extend_from_slice(&mut self, other: &[T]);
 }
 
+#[cfg(feature = "alloc")]
 impl<T: Clone> ExtendFromSlice<T> for Vec<T> {
     fn extend_from_slice(&mut self, other: &[T]) {
         Vec::extend_from_slice(self, other)
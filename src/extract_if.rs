@@ -0,0 +1,81 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{ptr, slice};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $array_item:ty
+) => {
+
+/// An iterator that removes the items of a `SmallVec` matching a predicate and yields them by
+/// value.
+///
+/// Returned from [`SmallVec::extract_if`][1].
+///
+/// [1]: struct.SmallVec.html#method.extract_if
+pub struct ExtractIf<'a, F: FnMut(&mut $array_item) -> bool, $($impl_generics)*> {
+    // The length of the vector is zero while the iterator is alive.
+    pub(crate) vec: &'a mut SmallVec<$($decl_generics)*>,
+    pub(crate) pred: F,
+    // The index of the next element to check.
+    pub(crate) idx: usize,
+    // The number of elements removed so far.
+    pub(crate) del: usize,
+    pub(crate) old_len: usize,
+}
+
+impl<'a, F: FnMut(&mut $array_item) -> bool, $($impl_generics)*> Iterator
+    for ExtractIf<'a, F, $($decl_generics)*>
+{
+    type Item = $array_item;
+
+    fn next(&mut self) -> Option<$array_item> {
+        unsafe {
+            let v = slice::from_raw_parts_mut(self.vec.triple_mut().0, self.old_len);
+            while self.idx < self.old_len {
+                let i = self.idx;
+                let extracted = (self.pred)(&mut v[i]);
+                // Update the index only now, so that a panicking predicate keeps the element.
+                self.idx += 1;
+                if extracted {
+                    self.del += 1;
+                    return Some(ptr::read(&v[i]));
+                } else if self.del > 0 {
+                    ptr::copy_nonoverlapping(&v[i], &mut v[i - self.del], 1);
+                }
+            }
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<'a, F: FnMut(&mut $array_item) -> bool, $($impl_generics)*> Drop
+    for ExtractIf<'a, F, $($decl_generics)*>
+{
+    fn drop(&mut self) {
+        // Keep the elements that weren't checked.
+        unsafe {
+            let (ptr, len_ptr, _) = self.vec.triple_mut();
+            if self.idx < self.old_len && self.del > 0 {
+                ptr::copy(ptr.add(self.idx), ptr.add(self.idx - self.del), self.old_len - self.idx);
+            }
+            len_ptr.set(self.old_len - self.del);
+        }
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
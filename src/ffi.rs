@@ -0,0 +1,60 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use std::ffi::{CStr, CString, NulError};
+#[cfg(unix)]
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<$($impl_generics)*> SmallVec<$($decl_generics)*> {
+    /// Copy the bytes into a new `CString`, failing if they contain an interior nul byte.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u8; 8]> = SmallVec::from_slice(b"abc");
+    /// assert_eq!(v.to_cstring().unwrap().as_bytes_with_nul(), b"abc\0");
+    /// ```
+    #[inline]
+    // `NulError` has been in `std::ffi` since Rust 1.0; clippy dates it by its `alloc::ffi` home.
+    #[allow(clippy::incompatible_msrv)]
+    pub fn to_cstring(&self) -> Result<CString, NulError> {
+        CString::new(&self[..])
+    }
+
+    /// View the bytes as an `OsStr`.
+    #[cfg(unix)]
+    #[inline]
+    pub fn as_os_str(&self) -> &OsStr {
+        OsStr::from_bytes(self)
+    }
+}
+
+impl<'a, $($impl_generics)*> From<&'a CStr> for SmallVec<$($decl_generics)*> {
+    /// Copy the bytes of a C string, without the trailing nul byte.
+    #[inline]
+    fn from(s: &'a CStr) -> Self {
+        SmallVec::from_slice(s.to_bytes())
+    }
+}
+
+#[cfg(unix)]
+impl<'a, $($impl_generics)*> From<&'a OsStr> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn from(s: &'a OsStr) -> Self {
+        SmallVec::from_slice(s.as_bytes())
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);
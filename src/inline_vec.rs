@@ -0,0 +1,369 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{utils::insertion_index_out_of_bounds, CollectionAllocErr, Drain, IntoIter, SmallVec};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{
+    convert::TryFrom,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut, RangeBounds},
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A `SmallVec` that never spills onto the heap.
+///
+/// Operations that would need more than the inline capacity fail and hand the rejected values
+/// back instead of allocating, so the vector can be used where allocating isn't allowed, like
+/// real-time threads. The layout is the one of the underlying `SmallVec`, which is available
+/// through `as_smallvec`/`into_smallvec`.
+///
+/// ```rust
+/// use smallvec::InlineVec;
+/// use std::convert::TryFrom;
+///
+/// let mut v: InlineVec<[u8; 2]> = InlineVec::new();
+/// assert_eq!(v.push(1), Ok(()));
+/// assert_eq!(v.push(2), Ok(()));
+/// assert_eq!(v.push(3), Err(3));
+/// assert!(v.is_full());
+/// assert_eq!(&v[..], &[1, 2]);
+///
+/// // Conversions fail instead of spilling too.
+/// assert!(InlineVec::<[u8; 2]>::try_from(&[1, 2, 3][..]).is_err());
+/// assert!(InlineVec::<[u8; 2]>::try_from(vec![1, 2]).is_ok());
+/// ```
+pub struct InlineVec<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty vector
+    #[inline]
+    pub fn new() -> Self {
+        InlineVec {
+            vec: SmallVec::new(),
+        }
+    }
+
+    /// Wrap a `SmallVec`, moving its elements inline if it has spilled, or give it back as `Err`
+    /// if they don't fit.
+    #[inline]
+    pub fn from_smallvec(mut vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Result<Self, SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>> {
+        if vec.len() > vec.inline_size() {
+            return Err(vec);
+        }
+        vec.shrink_to_fit();
+        Ok(InlineVec { vec })
+    }
+
+    /// Returns a reference to the underlying `SmallVec`.
+    #[inline]
+    pub fn as_smallvec(&self) -> &SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        &self.vec
+    }
+
+    /// Unwrap the underlying `SmallVec`, which is never spilled.
+    #[inline]
+    pub fn into_smallvec(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.vec
+    }
+
+    /// The number of elements stored in the vector
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the vector is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// The maximum number of elements the vector can hold
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.inline_size()
+    }
+
+    /// The number of elements that can still be added
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Returns `true` if no more elements can be added
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Append an item to the vector, or give it back as `Err` if the vector is full.
+    #[inline]
+    pub fn push(&mut self, value: $array_item) -> Result<(), $array_item> {
+        self.vec.push_within_capacity(value)
+    }
+
+    /// Remove an item from the end of the vector and return it, or `None` if empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<$array_item> {
+        self.vec.pop()
+    }
+
+    /// Insert an element at position `index`, shifting all elements after it to the right, or
+    /// give it back as `Err` if the vector is full.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, element: $array_item) -> Result<(), $array_item> {
+        if index > self.len() {
+            insertion_index_out_of_bounds("insert", index, self.len());
+        }
+        if self.is_full() {
+            return Err(element);
+        }
+        self.vec.insert(index, element);
+        Ok(())
+    }
+
+    /// Remove and return the element at position `index`, shifting all elements after it to the
+    /// left.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> $array_item {
+        self.vec.remove(index)
+    }
+
+    /// Remove the element at position `index`, replacing it with the last element.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn swap_remove(&mut self, index: usize) -> $array_item {
+        self.vec.swap_remove(index)
+    }
+
+    /// Shorten the vector, keeping the first `len` elements and dropping the rest.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    /// Remove all elements from the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Retains only the elements specified by the predicate.
+    #[inline]
+    pub fn retain<F: FnMut(&mut $array_item) -> bool>(&mut self, f: F) {
+        self.vec.retain(f);
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector and yields the
+    /// removed items.
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater
+    /// than the length of the vector.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, $array_item> {
+        self.vec.drain(range)
+    }
+
+    /// Append clones of all the elements of `other`, or return
+    /// `CollectionAllocErr::CapacityOverflow` and leave the vector untouched if they don't fit.
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, other: &[$array_item]) -> Result<(), CollectionAllocErr>
+    where
+        $array_item: Clone,
+    {
+        if other.len() > self.remaining_capacity() {
+            return Err(CollectionAllocErr::CapacityOverflow);
+        }
+        self.vec.extend(other.iter().cloned());
+        Ok(())
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        InlineVec {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.vec.fmt(f)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Target = [$array_item];
+    #[inline]
+    fn deref(&self) -> &[$array_item] {
+        &self.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DerefMut
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [$array_item] {
+        &mut self.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq {}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn from(vec: InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        vec.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Hash
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'a, $array_item>;
+    type Item = &'a $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a mut InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::IterMut<'a, $array_item>;
+    type Item = &'a mut $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> TryFrom<&'a [$array_item]>
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    type Error = CollectionAllocErr;
+
+    /// Clone the elements of `slice` into a new vector, or fail with
+    /// `CollectionAllocErr::CapacityOverflow` if they don't fit.
+    #[inline]
+    fn try_from(slice: &'a [$array_item]) -> Result<Self, Self::Error> {
+        let mut vec = Self::new();
+        vec.try_extend_from_slice(slice)?;
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> TryFrom<Vec<$array_item>>
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Error = Vec<$array_item>;
+
+    /// Move the elements of `vec` into a new vector and free its buffer, or give it back as `Err`
+    /// if they don't fit.
+    #[inline]
+    fn try_from(vec: Vec<$array_item>) -> Result<Self, Self::Error> {
+        let mut inline = Self::new();
+        if vec.len() > inline.capacity() {
+            return Err(vec);
+        }
+        inline.vec.extend(vec);
+        Ok(inline)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> TryFrom<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for InlineVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Error = SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>;
+
+    #[inline]
+    fn try_from(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Result<Self, Self::Error> {
+        Self::from_smallvec(vec)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
@@ -0,0 +1,67 @@
+use crate::small_vec_data::NonMaxUsize;
+use core::ptr;
+
+/// A gap opened in a vector by `insert_many`, between the elements before the insertion point
+/// and the moved tail.
+///
+/// While the gap is filled, the length of the vector only covers the elements before it. When
+/// the `InsertGap` is dropped, even by a panicking iterator, the unfilled part of the gap is
+/// closed by moving the tail back and the length covers everything again.
+pub struct InsertGap<'a, T> {
+    len: &'a mut NonMaxUsize,
+    start: *mut T,
+    filled: usize,
+    size: usize,
+    tail: usize,
+}
+
+impl<'a, T> InsertGap<'a, T> {
+    /// Move the elements of the vector from `index` on `size` slots toward the back.
+    ///
+    /// `ptr` and `len` must belong to the same vector, with room for `size` more elements.
+    #[inline]
+    pub unsafe fn open(ptr: *mut T, len: &'a mut NonMaxUsize, index: usize, size: usize) -> Self {
+        let tail = len.get() - index;
+        let start = ptr.add(index);
+        ptr::copy(start, start.add(size), tail);
+        len.set(index);
+        InsertGap {
+            len,
+            start,
+            filled: 0,
+            size,
+            tail,
+        }
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.filled == self.size
+    }
+
+    /// Write the next item of the gap, which must not be full.
+    #[inline]
+    pub fn push(&mut self, item: T) {
+        debug_assert!(!self.is_full());
+        unsafe {
+            ptr::write(self.start.add(self.filled), item);
+        }
+        self.filled += 1;
+    }
+}
+
+impl<'a, T> Drop for InsertGap<'a, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            if self.filled < self.size {
+                ptr::copy(
+                    self.start.add(self.size),
+                    self.start.add(self.filled),
+                    self.tail,
+                );
+            }
+        }
+        self.len.set(self.len.get() + self.filled + self.tail);
+    }
+}
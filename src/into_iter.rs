@@ -1,12 +1,20 @@
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use crate::allocator::{Allocator, Global};
 #[cfg(not(feature = "const_generics"))]
 use crate::Array;
 use crate::SmallVec;
-use core::ptr;
+#[cfg(feature = "specialization")]
+use core::iter::TrustedLen;
+use core::{
+    fmt::{self, Debug},
+    ptr, slice,
+};
 
 macro_rules! create_with_parts {
 (
     <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
     <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    [$($alloc:ident = $alloc_default:ident)?],
     $array_item:ty
 ) => {
 
@@ -15,22 +23,81 @@ macro_rules! create_with_parts {
 /// Returned from [`SmallVec::into_iter`][1].
 ///
 /// [1]: struct.SmallVec.html#method.into_iter
-pub struct IntoIter<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
-    pub(crate) data: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+pub struct IntoIter<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator = $alloc_default)?> {
+    pub(crate) data: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>,
     pub(crate) current: usize,
     pub(crate) end: usize,
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?> {
+    /// Returns the remaining items of this iterator as a slice.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[char; 4]> = SmallVec::from_slice(&['a', 'b', 'c']);
+    /// let mut iter = v.into_iter();
+    /// assert_eq!(iter.as_slice(), &['a', 'b', 'c']);
+    /// let _ = iter.next().unwrap();
+    /// assert_eq!(iter.as_slice(), &['b', 'c']);
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[$array_item] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr().add(self.current), self.end - self.current) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [$array_item] {
+        unsafe {
+            slice::from_raw_parts_mut(self.data.as_mut_ptr().add(self.current), self.end - self.current)
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Clone
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Clone,
+    $($alloc: Clone,)?
+{
+    fn clone(&self) -> Self {
+        // The length of `data` is zero, so this only clones the allocator.
+        let mut data = self.data.clone();
+        data.extend(self.as_slice().iter().cloned());
+        data.into_iter()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Debug
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
     for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        SmallVec::new().into_iter()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Drop
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     fn drop(&mut self) {
         for _ in self {}
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Iterator
-    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Iterator
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     type Item = $array_item;
 
@@ -54,8 +121,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Iterator
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DoubleEndedIterator
-    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> DoubleEndedIterator
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn next_back(&mut self) -> Option<$array_item> {
@@ -70,13 +137,34 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DoubleEndedIte
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> ExactSizeIterator
-    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?> {}
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> ExactSizeIterator
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?> {}
+
+// `size_hint` is always exact.
+#[cfg(feature = "specialization")]
+unsafe impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> TrustedLen
+    for IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?> {}
 
     }
 }
 
-#[cfg(feature = "const_generics")]
-create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
-#[cfg(not(feature = "const_generics"))]
-create_with_parts!(<A: Array>, <A>, A::Item);
+#[cfg(all(
+    feature = "const_generics",
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, [Alloc = Global], T);
+#[cfg(all(
+    feature = "const_generics",
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, [], T);
+#[cfg(all(
+    not(feature = "const_generics"),
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+create_with_parts!(<A: Array>, <A>, [Alloc = Global], A::Item);
+#[cfg(all(
+    not(feature = "const_generics"),
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+create_with_parts!(<A: Array>, <A>, [], A::Item);
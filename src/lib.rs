@@ -8,51 +8,365 @@
 //! to the heap for larger allocations.  This can be a useful optimization for improving cache
 //! locality and reducing allocator traffic for workloads that fit within the inline buffer.
 //!
+//! ## Minimum supported Rust version
+//!
+//! `smallvec` requires Rust 1.62 or later. The features integrating other crates may need a
+//! newer compiler, as required by those crates, and the features documented as such need a
+//! nightly compiler.
+//!
 //! ## `alloc` support
 //!
 //! By default, `smallvec` uses the `alloc` crate, which means that it can be used on platforms
 //! that have `liballoc` but not `libstd`.
 //!
+//! Without the default `alloc` feature the crate only depends on `core`, for targets with no
+//! allocator at all. Vectors then never spill: growing past the inline capacity fails like an
+//! allocation failure, so the `try_*` methods return `CollectionAllocErr::AllocErr` and the
+//! others panic. The conversions to and from `Vec`, `Box` and `String`, and the types that need
+//! the heap (`SmallArena`, `SmallVecPool`, `Splice` and `VecGuard`), are not available. Features
+//! of integrations that need the heap enable `alloc`.
+//!
 //! ## `union` feature
 //!
-//! When the `union` feature is enabled `smallvec` will track its state (inline or spilled)
-//! without the use of an enum tag, reducing the size of the `smallvec` by one machine word.
-//! This means that there is potentially no space overhead compared to `Vec`.
-//! Note that `smallvec` can still be larger than `Vec` if the inline buffer is larger than two
-//! machine words.
+//! `smallvec` tracks its state (inline or spilled) with its capacity alone, without an enum tag,
+//! and the inline buffer shares its space with the heap pointer and length. This means that
+//! there is potentially no space overhead compared to `Vec`, and that getting the length or the
+//! elements doesn't branch on the state. Note that `smallvec` can still be larger than `Vec` if
+//! the inline buffer is larger than two machine words.
+//!
+//! This used to require the `union` feature, which is kept for compatibility and has no effect.
+//!
+//! ## `u32_len` feature
+//!
+//! The `u32_len` feature stores the length and the capacity of vectors in `u32`s instead of
+//! `usize`s, and packs the heap pointer beside them. On 64-bit targets this saves 8 bytes per
+//! vector whose inline buffer is up to 12 bytes long and 4-byte aligned, like `SmallVec<[u8; 8]>`,
+//! which takes 16 bytes instead of 24. In exchange vectors can't hold `u32::MAX` elements or more:
+//! growing past that limit fails like a capacity overflow.
+//!
+//! ## `allocator_api` and `allocator-api2` features
+//!
+//! The `allocator_api` feature adds an allocator parameter to `SmallVec`, defaulting to the
+//! global allocator, and the `new_in`/`with_capacity_in` constructors that take the allocator
+//! used for the spilled storage. This requires a nightly compiler.
+//!
+//! The `allocator-api2` feature does the same on stable compilers, with the `Allocator` trait of
+//! the `allocator-api2` crate. When both are enabled, the nightly `Allocator` trait is used.
+//!
+//! ```rust,ignore
+//! use allocator_api2::alloc::System;
+//! use smallvec::SmallVec;
+//!
+//! let mut v: SmallVec<[u8; 2], System> = SmallVec::new_in(System);
+//! v.extend_from_slice(b"abc");
+//! assert!(v.spilled());
+//! ```
+//!
+//! ## `const_generics` feature
+//!
+//! The `const_generics` feature switches `SmallVec` and the types built on it from an `Array`
+//! type parameter to an element type and a const inline capacity, `SmallVec<T, N>` instead of
+//! `SmallVec<[T; N]>`, so any capacity can be used without an `Array` implementation. Both forms
+//! work on the minimum supported Rust version, 1.62.
+//!
+//! ```rust
+//! # #[cfg(feature = "const_generics")] {
+//! use smallvec::SmallVec;
+//!
+//! let mut v: SmallVec<u8, 100> = SmallVec::new();
+//! v.extend_from_slice(&[1, 2, 3]);
+//! assert_eq!(v.inline_size(), 100);
+//! # }
+//! ```
+//!
+//! ## `const_new` feature
+//!
+//! The `const_new` feature, which implies `const_generics`, makes `SmallVec::new` a `const fn`
+//! and adds `SmallVec::from_const` and the `smallvec_inline!` macro, which build fully inline
+//! vectors in const contexts. This allows vectors in `static`s without lazy initialization.
+//!
+//! ```rust,ignore
+//! use smallvec::{smallvec_inline, SmallVec};
+//!
+//! static DEFAULTS: SmallVec<u8, 4> = smallvec_inline![1, 2, 3, 4];
+//! static EMPTY: SmallVec<u8, 4> = SmallVec::new();
+//! ```
+//!
+//! ## `specialization` feature
+//!
+//! The `specialization` feature, which requires a nightly compiler, uses specialization to speed
+//! up building vectors: `From<&[T]>` copies `Copy` elements in bulk, and `Extend` (and so
+//! `collect`) reserves once and writes without capacity checks for `TrustedLen` iterators, like
+//! `slice.iter().copied()` or `vec::IntoIter`, and moves the remaining items of a
+//! `smallvec::IntoIter` with a single copy. `from_elem`, `resize` and `smallvec![elem; n]` write
+//! `Copy` elements without a drop guard, and bytes with a single `memset`.
+//!
+//! ## `extend_one` feature
+//!
+//! The `extend_one` feature, which requires a nightly compiler, implements the
+//! `Extend::extend_one` and `Extend::extend_reserve` methods, so that code extending a
+//! `SmallVec` one item at a time through the `Extend` trait, like `Iterator::unzip`, reserves
+//! the capacity up front and pushes directly.
+//!
+//! ## `may_dangle` feature
+//!
+//! The `may_dangle` feature, which requires a nightly compiler, tells the drop checker that
+//! dropping a `SmallVec` only drops its elements, like `Vec` does. Vectors of references can then
+//! be dropped after the values they point to, as long as nothing reads through them:
+//!
+//! ```rust,ignore
+//! use smallvec::SmallVec;
+//!
+//! let mut refs: SmallVec<[&u8; 2]> = SmallVec::new();
+//! let x = 1;
+//! refs.push(&x);
+//! // `x` is dropped before `refs` here, which is rejected without `may_dangle`.
+//! ```
+//!
+//! ## `arbitrary` feature
+//!
+//! The `arbitrary` feature implements `arbitrary::Arbitrary` for `SmallVec` and the collections
+//! built on it, so fuzz targets can take them as input directly.
+//!
+//! ## `bincode2` feature
+//!
+//! The `bincode2` feature implements the `Encode`, `Decode` and `BorrowDecode` traits of
+//! `bincode` 2 for `SmallVec`, with the same encoding as `Vec`.
+//!
+//! ## `bytemuck` feature
 //!
-//! To use this feature add `features = ["union"]` in the `smallvec` section of Cargo.toml.
-//! Note that this feature requires a nightly compiler (for now).
+//! The `bytemuck` feature adds `as_bytes`, `as_bytes_mut` and `try_from_bytes` to vectors of
+//! `Pod` elements, and the [`cast_smallvec`](fn.cast_smallvec.html) and
+//! [`try_cast_smallvec`](fn.try_cast_smallvec.html) functions to convert between element types,
+//! reusing the heap buffer when the layouts allow it.
+//!
+//! ## `bytes` feature
+//!
+//! The `bytes` feature adds conversions from byte vectors into `bytes::Bytes` and
+//! `bytes::BytesMut`, which take over the heap buffer of a spilled vector without copying.
+//!
+//! ## `defmt` feature
+//!
+//! The `defmt` feature implements `defmt::Format` for `SmallVec`, which is logged like a slice of
+//! its elements.
+//!
+//! ## `embedded-io` feature
+//!
+//! The `embedded-io` feature implements the `Write` trait of `embedded-io` 0.6 for byte vectors,
+//! appending like `std::io::Write` does, and `Read` and `BufRead` for their `IntoIter`, which
+//! moves the bytes out. This gives `no_std` code the same growable buffer; without `alloc`,
+//! writing past the inline capacity fails with `CollectionAllocErr`.
+//!
+//! ## `nom` feature
+//!
+//! The `nom` feature implements the input traits of `nom` 7 for byte vectors, so that owned
+//! buffers can be parsed directly. Since an owned buffer can't lend parts of itself, the parsed
+//! and remaining inputs are copies; parse `&v[..]` instead when ownership isn't needed.
+//!
+//! ## `proptest` feature
+//!
+//! The `proptest` feature implements `proptest::arbitrary::Arbitrary` for `SmallVec`, and adds
+//! the [`proptest::smallvec`](proptest/fn.smallvec.html) strategy to generate vectors from an
+//! element strategy and a length range.
+//!
+//! ## `quickcheck` feature
+//!
+//! The `quickcheck` feature implements `quickcheck::Arbitrary` for `SmallVec`, generating and
+//! shrinking vectors like `Vec`s.
+//!
+//! ## `rayon` feature
+//!
+//! The `rayon` feature implements `IntoParallelIterator` for vectors and references to them, and
+//! `FromParallelIterator` and `ParallelExtend`, so `par_iter`, `collect` and `par_extend` work on
+//! `SmallVec`s.
+//!
+//! ## `rkyv` feature
+//!
+//! The `rkyv` feature implements the `Archive`, `Serialize` and `Deserialize` traits of `rkyv`
+//! 0.8 for `SmallVec`. Vectors are archived as an `ArchivedVec`, exactly like a `Vec`, so they
+//! can be accessed in place without deserializing them.
+//!
+//! ## `varint` feature
+//!
+//! The `varint` feature adds methods to append and decode LEB128 varints (plain or
+//! zigzag-encoded) in byte vectors.
+//!
+//! ## `wasm` feature
+//!
+//! The `wasm` feature adds conversions between byte vectors and the `Uint8Array` and `JsValue`
+//! types of `js-sys` and `wasm-bindgen`, which copy the bytes across the JS boundary without
+//! going through a `Vec<u8>`.
+//!
+//! ## `derive` feature
+//!
+//! The `derive` feature provides `#[derive(SmallVecSoA)]`, which turns a struct with named fields
+//! into a "struct of `SmallVec`s" container storing each field in its own vector:
+//!
+//! ```rust,ignore
+//! use smallvec::SmallVecSoA;
+//!
+//! #[derive(SmallVecSoA)]
+//! #[soa(inline = 8)]
+//! struct Particle {
+//!     position: f32,
+//!     mass: f32,
+//! }
+//!
+//! let mut particles = ParticleSoA::new();
+//! particles.push(Particle { position: 1.0, mass: 2.0 });
+//! assert_eq!(particles.mass(), &[2.0]);
+//! assert_eq!(*particles.get(0).unwrap().position, 1.0);
+//! ```
 
-#![cfg_attr(feature = "const_generics", feature(const_generics))]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+#![cfg_attr(feature = "extend_one", feature(extend_one))]
 #![cfg_attr(feature = "may_dangle", feature(dropck_eyepatch))]
-#![cfg_attr(feature = "specialization", feature(specialization))]
-#![cfg_attr(feature = "union", feature(untagged_unions))]
+#![cfg_attr(feature = "specialization", feature(specialization, trusted_len))]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(all(test, feature = "derive"))]
+extern crate self as smallvec;
 
+mod allocator;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
 #[cfg(not(feature = "const_generics"))]
 mod array;
+#[cfg(feature = "bincode2")]
+mod bincode_impls;
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impls;
+#[cfg(feature = "bytes")]
+mod bytes_impls;
+mod collection_alloc_err;
+mod cursor_mut;
+#[cfg(feature = "defmt")]
+mod defmt_impls;
 mod drain;
+#[cfg(feature = "embedded-io")]
+mod embedded_io_impls;
 mod extend_from_slice;
+mod extract_if;
+#[cfg(feature = "std")]
+mod ffi;
+mod inline_vec;
+mod insert_gap;
 mod into_iter;
 #[macro_use]
 mod macros;
+#[cfg(feature = "nom")]
+mod nom_impls;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
+#[cfg(feature = "rkyv")]
+mod rkyv_impls;
+#[cfg(feature = "std")]
+mod scratch;
+#[cfg(feature = "serde")]
+pub mod serde_bytes;
 mod set_len_on_drop;
+#[cfg(feature = "alloc")]
+mod small_arena;
+mod small_binary_heap;
+mod small_bit_vec;
+mod small_cow;
+#[cfg(feature = "std")]
+mod small_cursor;
+mod small_map;
+mod small_ord_map;
+mod small_ord_set;
+mod small_set;
+mod small_slot_map;
+mod small_string;
 mod small_vec;
+mod small_vec1;
 mod small_vec_data;
+mod small_vec_deque;
+#[cfg(feature = "alloc")]
+mod small_vec_pool;
 #[cfg(feature = "serde")]
 mod small_vec_visitor;
+mod sortable_float;
+#[cfg(feature = "specialization")]
+mod spec_extend;
 #[cfg(feature = "specialization")]
 mod spec_from;
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod splice;
+#[cfg(all(test, feature = "alloc"))]
+mod tests;
+#[cfg(all(test, not(feature = "alloc")))]
+#[path = "tests/no_alloc.rs"]
 mod tests;
+mod to_small_vec;
 mod utils;
+#[cfg(feature = "varint")]
+mod varint;
+#[cfg(feature = "alloc")]
+mod vec_guard;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 #[cfg(not(feature = "const_generics"))]
 pub use self::array::Array;
+#[cfg(feature = "bytemuck")]
+pub use self::bytemuck_impls::{cast_smallvec, try_cast_smallvec};
+#[cfg(feature = "std")]
+pub use self::scratch::with_scratch;
+#[cfg(feature = "std")]
+pub use self::small_cursor::SmallCursor;
+#[cfg(feature = "serde")]
+pub use self::small_vec_visitor::{AppendSeed, SmallVecVisitor};
+pub use self::{
+    collection_alloc_err::CollectionAllocErr,
+    cursor_mut::CursorMut,
+    drain::Drain,
+    extend_from_slice::ExtendFromSlice,
+    extract_if::ExtractIf,
+    inline_vec::InlineVec,
+    into_iter::IntoIter,
+    small_binary_heap::SmallBinaryHeap,
+    small_bit_vec::{SmallBitVec, SmallBitVecIter, SmallBitVecOnes},
+    small_cow::SmallCow,
+    small_map::{
+        SmallMap, SmallMapEntry, SmallMapIter, SmallMapIterMut, SmallMapOccupiedEntry,
+        SmallMapVacantEntry,
+    },
+    small_ord_map::SmallOrdMap,
+    small_ord_set::SmallOrdSet,
+    small_set::SmallSet,
+    small_slot_map::{Slot, SlotKey, SmallSlotMap, SmallSlotMapIter, SmallSlotMapIterMut},
+    small_string::SmallString,
+    small_vec::SmallVec,
+    small_vec1::SmallVec1,
+    small_vec_deque::{
+        SmallVecDeque, SmallVecDequeIntoIter, SmallVecDequeIter, SmallVecDequeIterMut,
+    },
+    sortable_float::SortableFloat,
+    to_small_vec::ToSmallVec,
+};
+#[cfg(feature = "alloc")]
 pub use self::{
-    drain::Drain, extend_from_slice::ExtendFromSlice, into_iter::IntoIter, small_vec::SmallVec,
+    small_arena::SmallArena,
+    small_vec_pool::{PooledSmallVec, SmallVecPool},
+    splice::Splice,
+    vec_guard::VecGuard,
 };
+#[cfg(feature = "derive")]
+pub use smallvec_derive::SmallVecSoA;
+
+/// The vector type used by the containers generated by `#[derive(SmallVecSoA)]`.
+#[cfg(all(feature = "derive", feature = "const_generics"))]
+#[doc(hidden)]
+pub type __SoaVec<T, const N: usize> = SmallVec<T, N>;
+/// The vector type used by the containers generated by `#[derive(SmallVecSoA)]`.
+#[cfg(all(feature = "derive", not(feature = "const_generics")))]
+#[doc(hidden)]
+pub type __SoaVec<T, const N: usize> = SmallVec<[T; N]>;
@@ -1,18 +1,3 @@
-/// `panic!()` in debug builds, optimization hint in release.
-#[cfg(not(feature = "union"))]
-macro_rules! debug_unreachable {
-    () => {
-        debug_unreachable!("entered unreachable code")
-    };
-    ($e:expr) => {
-        if cfg!(not(debug_assertions)) {
-            core::hint::unreachable_unchecked();
-        } else {
-            panic!($e);
-        }
-    };
-}
-
 /// Creates a [`SmallVec`] containing the arguments.
 ///
 /// `smallvec!` allows `SmallVec`s to be defined with the same syntax as array expressions.
@@ -40,6 +25,9 @@ macro_rules! debug_unreachable {
 /// which implement [`Clone`] and the number of elements doesn't have to be
 /// a constant.
 ///
+/// Neither form goes through a `Vec`: the elements are written straight into the inline buffer
+/// when they fit, or into a single heap allocation of the right size otherwise.
+///
 /// This will use `clone` to duplicate an expression, so one should be careful
 /// using this with types having a nonstandard `Clone` implementation. For
 /// example, `smallvec![Rc::new(1); 5]` will create a vector of five references
@@ -53,13 +41,62 @@ macro_rules! smallvec {
         $crate::SmallVec::from_elem($elem, $n)
     });
     ($($x:expr),*$(,)*) => ({
-        let count = 0usize $(+ smallvec!(@one $x))*;
-        let mut vec = $crate::SmallVec::new();
-        if count <= vec.inline_size() {
-            $(vec.push($x);)*
-            vec
-        } else {
-            $crate::SmallVec::from_vec(vec![$($x,)*])
-        }
+        let count = 0usize $(+ $crate::smallvec!(@one $x))*;
+        // Stays inline when the elements fit, and allocates once otherwise.
+        #[allow(unused_mut)]
+        let mut vec = $crate::SmallVec::with_capacity(count);
+        $(vec.push($x);)*
+        vec
+    });
+}
+
+/// Creates an inline [`SmallVec`] containing the arguments, in const contexts too.
+///
+/// The inline capacity of the vector is the number of elements, and the repeat form needs a
+/// constant length. Requires the `const_new` feature.
+///
+/// ```rust,ignore
+/// use smallvec::{smallvec_inline, SmallVec};
+///
+/// const PRIMES: SmallVec<u8, 4> = smallvec_inline![2, 3, 5, 7];
+/// static ZEROS: SmallVec<u8, 8> = smallvec_inline![0; 8];
+/// assert_eq!(PRIMES[..], [2, 3, 5, 7]);
+/// assert!(!ZEROS.spilled());
+/// ```
+#[cfg(feature = "const_new")]
+#[macro_export]
+macro_rules! smallvec_inline {
+    ($elem:expr; $n:expr) => ({
+        $crate::SmallVec::<_, { $n }>::from_const([$elem; $n])
     });
+    ($($x:expr),+ $(,)?) => ({
+        $crate::SmallVec::from_const([$($x,)+])
+    });
+}
+
+/// Fails compilation if a type takes more than a given number of bytes.
+///
+/// Meant for `SmallVec` types, whose size grows with their inline capacity, to catch a
+/// `SmallVec<[BigStruct; 64]>` that would blow up the stack at build time. The size checked is
+/// that of the whole value, so it includes the capacity next to the inline buffer.
+///
+/// ```
+/// use smallvec::{assert_inline_bytes, SmallVec};
+///
+/// assert_inline_bytes!(SmallVec<[u8; 64]>, 128);
+/// ```
+///
+/// ```compile_fail
+/// use smallvec::{assert_inline_bytes, SmallVec};
+///
+/// assert_inline_bytes!(SmallVec<[[u64; 32]; 64]>, 4096);
+/// ```
+#[macro_export]
+macro_rules! assert_inline_bytes {
+    ($ty:ty, $max_bytes:expr) => {
+        const _: () = assert!(
+            ::core::mem::size_of::<$ty>() <= $max_bytes,
+            "the vector type is larger than its byte budget"
+        );
+    };
 }
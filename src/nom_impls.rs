@@ -0,0 +1,213 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{IntoIter, SmallVec};
+use core::{
+    iter::Enumerate,
+    ops::{Range, RangeFrom, RangeFull, RangeTo},
+    str::FromStr,
+};
+use nom::{
+    error::{ErrorKind, ParseError},
+    AsBytes, Compare, CompareResult, Err, FindSubstring, FindToken, IResult, InputIter,
+    InputLength, InputTake, InputTakeAtPosition, Needed, ParseTo, Slice,
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+// Owned buffers can't lend sub-slices of themselves, so taking, splitting and slicing copy the
+// selected bytes into new vectors. Parse `&v[..]` instead when the input doesn't need to be owned.
+
+impl<$($impl_generics)*> AsBytes for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<'b, $($impl_generics)*> Compare<&'b [u8]> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn compare(&self, t: &'b [u8]) -> CompareResult {
+        (&self[..]).compare(t)
+    }
+
+    #[inline]
+    fn compare_no_case(&self, t: &'b [u8]) -> CompareResult {
+        (&self[..]).compare_no_case(t)
+    }
+}
+
+impl<'b, $($impl_generics)*> Compare<&'b str> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn compare(&self, t: &'b str) -> CompareResult {
+        (&self[..]).compare(t)
+    }
+
+    #[inline]
+    fn compare_no_case(&self, t: &'b str) -> CompareResult {
+        (&self[..]).compare_no_case(t)
+    }
+}
+
+impl<'b, $($impl_generics)*> FindSubstring<&'b [u8]> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn find_substring(&self, substr: &'b [u8]) -> Option<usize> {
+        (&self[..]).find_substring(substr)
+    }
+}
+
+impl<'b, $($impl_generics)*> FindSubstring<&'b str> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn find_substring(&self, substr: &'b str) -> Option<usize> {
+        (&self[..]).find_substring(substr)
+    }
+}
+
+impl<$($impl_generics)*> FindToken<u8> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn find_token(&self, token: u8) -> bool {
+        (&self[..]).find_token(token)
+    }
+}
+
+impl<$($impl_generics)*> InputIter for SmallVec<$($decl_generics)*> {
+    type Item = u8;
+    type Iter = Enumerate<Self::IterElem>;
+    type IterElem = IntoIter<$($decl_generics)*>;
+
+    #[inline]
+    fn iter_indices(&self) -> Self::Iter {
+        self.iter_elements().enumerate()
+    }
+
+    /// Iterates over a copy of the bytes.
+    #[inline]
+    fn iter_elements(&self) -> Self::IterElem {
+        self.clone().into_iter()
+    }
+
+    #[inline]
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.iter().position(|&b| predicate(b))
+    }
+
+    #[inline]
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        (&self[..]).slice_index(count)
+    }
+}
+
+impl<$($impl_generics)*> InputLength for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<$($impl_generics)*> InputTake for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn take(&self, count: usize) -> Self {
+        SmallVec::from_slice(&self[..count])
+    }
+
+    #[inline]
+    fn take_split(&self, count: usize) -> (Self, Self) {
+        let (prefix, suffix) = self.split_at(count);
+        (SmallVec::from_slice(suffix), SmallVec::from_slice(prefix))
+    }
+}
+
+impl<$($impl_generics)*> InputTakeAtPosition for SmallVec<$($decl_generics)*> {
+    type Item = u8;
+
+    fn split_at_position<P, E: ParseError<Self>>(&self, predicate: P) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.iter().position(|&b| predicate(b)) {
+            Some(i) => Ok(self.take_split(i)),
+            None => Err(Err::Incomplete(Needed::new(1))),
+        }
+    }
+
+    fn split_at_position1<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+        e: ErrorKind,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.iter().position(|&b| predicate(b)) {
+            Some(0) => Err(Err::Error(E::from_error_kind(self.clone(), e))),
+            Some(i) => Ok(self.take_split(i)),
+            None => Err(Err::Incomplete(Needed::new(1))),
+        }
+    }
+
+    fn split_at_position_complete<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.iter().position(|&b| predicate(b)) {
+            Some(i) => Ok(self.take_split(i)),
+            None => Ok(self.take_split(self.len())),
+        }
+    }
+
+    fn split_at_position1_complete<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+        e: ErrorKind,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.iter().position(|&b| predicate(b)) {
+            Some(0) => Err(Err::Error(E::from_error_kind(self.clone(), e))),
+            Some(i) => Ok(self.take_split(i)),
+            None if self.is_empty() => Err(Err::Error(E::from_error_kind(self.clone(), e))),
+            None => Ok(self.take_split(self.len())),
+        }
+    }
+}
+
+impl<R: FromStr, $($impl_generics)*> ParseTo<R> for SmallVec<$($decl_generics)*> {
+    #[inline]
+    fn parse_to(&self) -> Option<R> {
+        (&self[..]).parse_to()
+    }
+}
+
+impl_slice!([$($impl_generics)*], [$($decl_generics)*], Range<usize>);
+impl_slice!([$($impl_generics)*], [$($decl_generics)*], RangeFrom<usize>);
+impl_slice!([$($impl_generics)*], [$($decl_generics)*], RangeTo<usize>);
+impl_slice!([$($impl_generics)*], [$($decl_generics)*], RangeFull);
+
+    }
+}
+
+macro_rules! impl_slice {
+    ([$($impl_generics:tt)*], [$($decl_generics:tt)*], $range:ty) => {
+        impl<$($impl_generics)*> Slice<$range> for SmallVec<$($decl_generics)*> {
+            #[inline]
+            fn slice(&self, range: $range) -> Self {
+                SmallVec::from_slice(&self[range])
+            }
+        }
+    };
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);
@@ -0,0 +1,76 @@
+//! `proptest` strategies generating `SmallVec`s.
+//!
+//! The vectors shrink like `Vec`s, by removing elements and then shrinking the remaining ones.
+//! They are always collected into a fresh `SmallVec`, so a value that shrinks to fit the inline
+//! capacity is stored inline.
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use smallvec::SmallVec;
+//!
+//! proptest! {
+//!     fn sorted(mut v in any::<SmallVec<[u8; 4]>>()) {
+//!         v.sort();
+//!         prop_assert!(v.windows(2).all(|w| w[0] <= w[1]));
+//!     }
+//!
+//!     fn short(v in smallvec::proptest::smallvec::<_, [u16; 4]>(0..10u16, 0..=4)) {
+//!         prop_assert!(!v.spilled());
+//!     }
+//! }
+//! # sorted();
+//! # short();
+//! ```
+
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use proptest::{
+    arbitrary::{any_with, Arbitrary},
+    collection::{vec, SizeRange, VecStrategy},
+    strategy::{Map, Strategy},
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+/// Create a strategy generating vectors of elements from `element`, with a length in `size`.
+pub fn smallvec<S: Strategy<Value = $item>, $($impl_generics)*>(
+    element: S,
+    size: impl Into<SizeRange>,
+) -> Map<VecStrategy<S>, fn(Vec<$item>) -> SmallVec<$($decl_generics)*>>
+where
+    $item: Debug,
+{
+    vec(element, size).prop_map(collect as fn(_) -> _)
+}
+
+fn collect<$($impl_generics)*>(vec: Vec<$item>) -> SmallVec<$($decl_generics)*> {
+    vec.into_iter().collect()
+}
+
+impl<$($impl_generics)*> Arbitrary for SmallVec<$($decl_generics)*>
+where
+    $item: Arbitrary,
+{
+    type Parameters = (SizeRange, <$item as Arbitrary>::Parameters);
+    type Strategy = Map<VecStrategy<<$item as Arbitrary>::Strategy>, fn(Vec<$item>) -> Self>;
+
+    fn arbitrary_with((size, args): Self::Parameters) -> Self::Strategy {
+        smallvec(any_with::<$item>(args), size)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
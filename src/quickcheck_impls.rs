@@ -0,0 +1,36 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::{boxed::Box, vec::Vec};
+use quickcheck::{Arbitrary, Gen};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<$($impl_generics)*> Arbitrary for SmallVec<$($decl_generics)*>
+where
+    $item: Arbitrary,
+{
+    /// Generate a vector the way a `Vec` is generated, so its length is up to the size of `g`.
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<$item>::arbitrary(g).into_iter().collect()
+    }
+
+    /// Shrink like a `Vec`, by removing elements and then shrinking the remaining ones. The
+    /// candidates are collected into fresh vectors, so those that fit inline are stored inline.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.to_vec().shrink().map(|vec| vec.into_iter().collect()))
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array + 'static], [A], A::Item);
@@ -0,0 +1,90 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::vec::Vec;
+use rayon::{
+    iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator},
+    slice, vec,
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+impl<$($impl_generics)*> IntoParallelIterator for SmallVec<$($decl_generics)*>
+where
+    $item: Send,
+{
+    type Item = $item;
+    type Iter = vec::IntoIter<$item>;
+
+    /// Iterate over the elements in parallel. They are handed over as a `Vec`, which takes over
+    /// the heap buffer of a spilled vector and moves inline elements to a new allocation.
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        self.into_vec().into_par_iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoParallelIterator for &'a SmallVec<$($decl_generics)*>
+where
+    $item: Sync,
+{
+    type Item = &'a $item;
+    type Iter = slice::Iter<'a, $item>;
+
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        self[..].into_par_iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoParallelIterator for &'a mut SmallVec<$($decl_generics)*>
+where
+    $item: Send,
+{
+    type Item = &'a mut $item;
+    type Iter = slice::IterMut<'a, $item>;
+
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        self[..].into_par_iter()
+    }
+}
+
+impl<$($impl_generics)*> FromParallelIterator<$item> for SmallVec<$($decl_generics)*>
+where
+    $item: Send,
+{
+    fn from_par_iter<I: IntoParallelIterator<Item = $item>>(par_iter: I) -> Self {
+        let mut vec = SmallVec::new();
+        vec.par_extend(par_iter);
+        vec
+    }
+}
+
+impl<$($impl_generics)*> ParallelExtend<$item> for SmallVec<$($decl_generics)*>
+where
+    $item: Send,
+{
+    /// Collect the elements in parallel into a `Vec`, then move them to the end of the vector.
+    fn par_extend<I: IntoParallelIterator<Item = $item>>(&mut self, par_iter: I) {
+        let items: Vec<$item> = par_iter.into_par_iter().collect();
+        if self.is_empty() && items.len() > self.inline_size() {
+            *self = SmallVec::from_vec(items);
+        } else {
+            self.extend(items);
+        }
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
@@ -0,0 +1,78 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use rkyv::{
+    rancor::Fallible,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Serialize,
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty
+) => {
+
+// A vector is archived like a `Vec`, as a relative pointer to its elements and a length. The
+// inline capacity isn't part of the archive, so an archived `SmallVec` can be deserialized into a
+// `Vec` or a `SmallVec` of another size, and the other way around.
+
+impl<$($impl_generics)*> Archive for SmallVec<$($decl_generics)*>
+where
+    $item: Archive,
+{
+    type Archived = ArchivedVec<<$item as Archive>::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_slice(self, resolver, out);
+    }
+}
+
+impl<S, $($impl_generics)*> Serialize<S> for SmallVec<$($decl_generics)*>
+where
+    $item: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<<$item as Archive>::Archived>::serialize_from_slice(self, serializer)
+    }
+}
+
+impl<D, $($impl_generics)*> Deserialize<SmallVec<$($decl_generics)*>, D>
+    for ArchivedVec<<$item as Archive>::Archived>
+where
+    $item: Archive,
+    <$item as Archive>::Archived: Deserialize<$item, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<SmallVec<$($decl_generics)*>, D::Error> {
+        let mut vec = SmallVec::with_capacity(self.len());
+        for item in self.iter() {
+            vec.push(item.deserialize(deserializer)?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<U, $($impl_generics)*> PartialEq<SmallVec<$($decl_generics)*>> for ArchivedVec<U>
+where
+    U: PartialEq<$item>,
+{
+    #[inline]
+    fn eq(&self, other: &SmallVec<$($decl_generics)*>) -> bool {
+        self.as_slice() == &other[..]
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
@@ -0,0 +1,76 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use std::{any::Any, boxed::Box, cell::RefCell, thread_local, vec::Vec};
+
+thread_local! {
+    // One spare vector (and its capacity) per type used with `with_scratch`.
+    static SCRATCH: RefCell<Vec<(usize, Box<dyn Any>)>> = RefCell::new(Vec::new());
+}
+
+fn take_scratch<V: Any + Default>() -> V {
+    SCRATCH
+        .with(|scratch| {
+            let mut scratch = scratch.borrow_mut();
+            let index = scratch.iter().position(|(_, v)| v.is::<V>())?;
+            scratch.swap_remove(index).1.downcast::<V>().ok()
+        })
+        .map_or_else(V::default, |v| *v)
+}
+
+fn put_scratch<V: Any>(v: V, capacity: usize) {
+    // The thread-local may already be destroyed if this runs in another thread-local's
+    // destructor, in which case the vector is simply dropped.
+    let _ = SCRATCH.try_with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        // After nested calls, keep the largest of the vectors.
+        match scratch.iter_mut().find(|(_, v)| v.is::<V>()) {
+            Some(spare) if spare.0 < capacity => *spare = (capacity, Box::new(v)),
+            Some(_) => {}
+            None => scratch.push((capacity, Box::new(v))),
+        }
+    });
+}
+
+/// Run `f` with an empty thread-local scratch vector.
+///
+/// The vector is cleared when `f` returns and kept for the next call on the same thread, so a
+/// buffer that spilled keeps its heap allocation across calls. Nested calls get distinct
+/// vectors.
+#[cfg(feature = "const_generics")]
+pub fn with_scratch<T: 'static, const N: usize, R, F: FnOnce(&mut SmallVec<T, { N }>) -> R>(
+    f: F,
+) -> R {
+    let mut v: SmallVec<T, { N }> = take_scratch();
+    let result = f(&mut v);
+    v.clear();
+    let capacity = v.capacity();
+    put_scratch(v, capacity);
+    result
+}
+
+/// Run `f` with an empty thread-local scratch vector.
+///
+/// The vector is cleared when `f` returns and kept for the next call on the same thread, so a
+/// buffer that spilled keeps its heap allocation across calls. Nested calls get distinct
+/// vectors.
+///
+/// ```rust
+/// use smallvec::with_scratch;
+///
+/// let sum = with_scratch::<[u32; 8], _, _>(|buf| {
+///     buf.extend(1..=4);
+///     buf.iter().sum::<u32>()
+/// });
+/// assert_eq!(sum, 10);
+/// with_scratch::<[u32; 8], _, _>(|buf| assert!(buf.is_empty()));
+/// ```
+#[cfg(not(feature = "const_generics"))]
+pub fn with_scratch<A: Array + 'static, R, F: FnOnce(&mut SmallVec<A>) -> R>(f: F) -> R {
+    let mut v: SmallVec<A> = take_scratch();
+    let result = f(&mut v);
+    v.clear();
+    let capacity = v.capacity();
+    put_scratch(v, capacity);
+    result
+}
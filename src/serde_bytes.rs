@@ -0,0 +1,102 @@
+//! Serialize a `SmallVec` of bytes as a byte string rather than as a sequence of `u8`.
+//!
+//! Use it on struct fields with `#[serde(with = "smallvec::serde_bytes")]`. Binary formats
+//! like bincode or CBOR then write the bytes in one go, with no per-element overhead.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use smallvec::SmallVec;
+//!
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Packet {
+//!     #[serde(with = "smallvec::serde_bytes")]
+//!     payload: SmallVec<[u8; 16]>,
+//! }
+//!
+//! let packet = Packet { payload: SmallVec::from_slice(b"hello") };
+//! let encoded = bincode::serialize(&packet).unwrap();
+//! assert_eq!(bincode::deserialize::<Packet>(&encoded).unwrap(), packet);
+//! ```
+//!
+//! Deserializing also accepts strings and sequences of integers, for formats such as JSON that
+//! have no byte strings.
+
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{fmt, marker::PhantomData};
+use serde::{
+    de::{Deserializer, Error, SeqAccess, Visitor},
+    Serializer,
+};
+
+struct BytesVisitor<V> {
+    phantom: PhantomData<V>,
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    $vec:ty
+) => {
+
+/// Serialize `vec` with `serialize_bytes`.
+#[inline]
+pub fn serialize<$($impl_generics)*, S: Serializer>(
+    vec: &$vec,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(vec)
+}
+
+/// Deserialize a vector with `deserialize_byte_buf`.
+#[inline]
+pub fn deserialize<'de, $($impl_generics)*, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<$vec, D::Error> {
+    deserializer.deserialize_byte_buf(BytesVisitor {
+        phantom: PhantomData,
+    })
+}
+
+impl<'de, $($impl_generics)*> Visitor<'de> for BytesVisitor<$vec> {
+    type Value = $vec;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    #[inline]
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(SmallVec::from_slice(v))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E: Error>(self, v: alloc::vec::Vec<u8>) -> Result<Self::Value, E> {
+        // Takes over the buffer, unless it is small enough to move inline.
+        Ok(SmallVec::from_vec(v))
+    }
+
+    #[inline]
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(SmallVec::from_slice(v.as_bytes()))
+    }
+
+    fn visit_seq<B: SeqAccess<'de>>(self, mut seq: B) -> Result<Self::Value, B::Error> {
+        let mut values = SmallVec::with_capacity(
+            crate::small_vec_visitor::cautious_capacity::<u8>(seq.size_hint()),
+        );
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], SmallVec<u8, { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], SmallVec<A>);
@@ -1,16 +1,18 @@
+use crate::small_vec_data::NonMaxUsize;
+
 /// Set the length of the vec when the `SetLenOnDrop` value goes out of scope.
 ///
 /// Copied from https://github.com/rust-lang/rust/pull/36355
 pub struct SetLenOnDrop<'a> {
-    len: &'a mut usize,
+    len: &'a mut NonMaxUsize,
     local_len: usize,
 }
 
 impl<'a> SetLenOnDrop<'a> {
     #[inline]
-    pub fn new(len: &'a mut usize) -> Self {
+    pub fn new(len: &'a mut NonMaxUsize) -> Self {
         SetLenOnDrop {
-            local_len: *len,
+            local_len: len.get(),
             len,
         }
     }
@@ -29,6 +31,6 @@ impl<'a> SetLenOnDrop<'a> {
 impl<'a> Drop for SetLenOnDrop<'a> {
     #[inline]
     fn drop(&mut self) {
-        *self.len = self.local_len;
+        self.len.set(self.local_len);
     }
 }
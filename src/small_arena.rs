@@ -0,0 +1,206 @@
+use crate::utils::{infallible, layout_array};
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::CollectionAllocErr;
+use alloc::{
+    alloc::{alloc, dealloc},
+    vec::Vec,
+};
+use core::{
+    alloc::Layout,
+    cell::{Cell, RefCell, UnsafeCell},
+    cmp,
+    mem::MaybeUninit,
+    ptr::{self, NonNull},
+    slice,
+};
+
+const MIN_CHUNK_SIZE: usize = 256;
+
+struct Chunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+/// Returns the offset at which `layout` fits in the `capacity` bytes starting at `base` after
+/// the first `used` ones, if it does.
+#[inline]
+fn bump(base: *mut u8, used: usize, capacity: usize, layout: Layout) -> Option<usize> {
+    let addr = (base as usize).checked_add(used)?;
+    let aligned = addr.checked_add(layout.align() - 1)? & !(layout.align() - 1);
+    let start = aligned - base as usize;
+    let end = start.checked_add(layout.size())?;
+    if end <= capacity {
+        Some(start)
+    } else {
+        None
+    }
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $array:ty,
+    $array_size:expr
+) => {
+
+/// A bump arena that serves allocations from an inline byte buffer first, and from
+/// heap-allocated chunks once the inline buffer is full.
+///
+/// Values are handed out as mutable references that live as long as the shared borrow of the
+/// arena, and are never dropped: memory is only reclaimed by `reset` or by dropping the arena.
+///
+/// ```rust
+/// use smallvec::SmallArena;
+///
+/// let arena: SmallArena<[u8; 64]> = SmallArena::new();
+/// let a = arena.alloc(1u32);
+/// let b = arena.alloc_slice_copy(&[1u16, 2, 3]);
+/// *a += 1;
+/// b[0] = 10;
+/// assert_eq!((*a, &*b), (2, &[10, 2, 3][..]));
+/// assert!(!arena.spilled());
+/// ```
+pub struct SmallArena<$($impl_generics)*> {
+    inline: UnsafeCell<MaybeUninit<$array>>,
+    inline_used: Cell<usize>,
+    chunks: RefCell<Vec<Chunk>>,
+    chunk_used: Cell<usize>,
+}
+
+impl<$($impl_generics)*> SmallArena<$($decl_generics)*> {
+    /// Construct an empty arena
+    #[inline]
+    pub fn new() -> Self {
+        SmallArena {
+            inline: UnsafeCell::new(MaybeUninit::uninit()),
+            inline_used: Cell::new(0),
+            chunks: RefCell::new(Vec::new()),
+            chunk_used: Cell::new(0),
+        }
+    }
+
+    /// The number of bytes the arena can hold inline
+    #[inline]
+    pub fn inline_size(&self) -> usize {
+        $array_size
+    }
+
+    /// Returns `true` if the arena had to allocate heap chunks since it was created.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        !self.chunks.borrow().is_empty()
+    }
+
+    /// Move `value` into the arena and return a mutable reference to it.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        unsafe {
+            let ptr = self.alloc_layout(Layout::new::<T>()).as_ptr() as *mut T;
+            ptr::write(ptr, value);
+            &mut *ptr
+        }
+    }
+
+    /// Copy a slice into the arena and return a mutable reference to the copy.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    pub fn alloc_slice_copy<T: Copy>(&self, src: &[T]) -> &mut [T] {
+        unsafe {
+            let layout = infallible(layout_array::<T>(src.len()));
+            let ptr = self.alloc_layout(layout).as_ptr() as *mut T;
+            ptr::copy_nonoverlapping(src.as_ptr(), ptr, src.len());
+            slice::from_raw_parts_mut(ptr, src.len())
+        }
+    }
+
+    /// Allocate a slice of `len` elements in the arena, initializing the element at each index
+    /// with `f(index)`.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_with<T, F: FnMut(usize) -> T>(&self, len: usize, mut f: F) -> &mut [T] {
+        unsafe {
+            let layout = infallible(layout_array::<T>(len));
+            let ptr = self.alloc_layout(layout).as_ptr() as *mut T;
+            for i in 0..len {
+                ptr::write(ptr.add(i), f(i));
+            }
+            slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
+    /// Allocate uninitialized memory for `layout` in the arena.
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        infallible(self.try_alloc_layout(layout))
+    }
+
+    /// Allocate uninitialized memory for `layout` in the arena, returning an error instead of
+    /// aborting if a new heap chunk is needed and can't be allocated.
+    pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, CollectionAllocErr> {
+        if layout.size() == 0 {
+            return Ok(unsafe { NonNull::new_unchecked(layout.align() as *mut u8) });
+        }
+
+        let inline = self.inline.get() as *mut u8;
+        if let Some(start) = bump(inline, self.inline_used.get(), $array_size, layout) {
+            self.inline_used.set(start + layout.size());
+            return Ok(unsafe { NonNull::new_unchecked(inline.add(start)) });
+        }
+
+        let mut chunks = self.chunks.borrow_mut();
+        let mut chunk_size = MIN_CHUNK_SIZE;
+        if let Some(chunk) = chunks.last() {
+            let used = self.chunk_used.get();
+            if let Some(start) = bump(chunk.ptr.as_ptr(), used, chunk.layout.size(), layout) {
+                self.chunk_used.set(start + layout.size());
+                return Ok(unsafe { NonNull::new_unchecked(chunk.ptr.as_ptr().add(start)) });
+            }
+            chunk_size = chunk.layout.size().saturating_mul(2);
+        }
+
+        let chunk_layout = Layout::from_size_align(cmp::max(chunk_size, layout.size()), layout.align())
+            .map_err(|_| CollectionAllocErr::CapacityOverflow)?;
+        let ptr = NonNull::new(unsafe { alloc(chunk_layout) })
+            .ok_or(CollectionAllocErr::AllocErr { layout: chunk_layout })?;
+        chunks.push(Chunk {
+            ptr,
+            layout: chunk_layout,
+        });
+        self.chunk_used.set(layout.size());
+        Ok(ptr)
+    }
+
+    /// Free everything allocated in the arena, keeping the largest heap chunk (if any) to serve
+    /// future allocations.
+    pub fn reset(&mut self) {
+        self.inline_used.set(0);
+        self.chunk_used.set(0);
+        let chunks = self.chunks.get_mut();
+        if let Some(last) = chunks.pop() {
+            chunks.clear();
+            chunks.push(last);
+        }
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallArena<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [{ N }], [u8; N], N);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A], A, A::size());
@@ -0,0 +1,272 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{IntoIter, SmallVec};
+use core::{
+    fmt::{self, Debug},
+    iter::FromIterator,
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A max-heap priority queue that stores its elements in a `SmallVec`, so that small heaps
+/// don't allocate.
+///
+/// ```rust
+/// use smallvec::SmallBinaryHeap;
+///
+/// let mut heap: SmallBinaryHeap<[u32; 16]> = SmallBinaryHeap::new();
+/// heap.push(3);
+/// heap.push(7);
+/// heap.push(5);
+/// assert_eq!(heap.peek(), Some(&7));
+/// assert_eq!(heap.pop(), Some(7));
+/// assert_eq!(&heap.into_sorted_smallvec()[..], &[3, 5]);
+/// ```
+pub struct SmallBinaryHeap<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    data: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty heap
+    #[inline]
+    pub fn new() -> Self {
+        SmallBinaryHeap {
+            data: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty heap with room for at least `n` elements.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallBinaryHeap {
+            data: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// The number of elements in the heap
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the heap is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of elements the heap can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Returns `true` if the data has spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.data.spilled()
+    }
+
+    /// Returns the greatest element, or `None` if the heap is empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&$array_item> {
+        self.data.first()
+    }
+
+    /// Iterate over the elements in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, $array_item> {
+        self.data.iter()
+    }
+
+    /// Remove all the elements of the heap.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    /// Unwrap the underlying vector, with the elements in arbitrary order.
+    #[inline]
+    pub fn into_smallvec(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.data
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    /// Build a heap out of the elements of a vector, in linear time.
+    pub fn from_smallvec(data: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        let mut heap = SmallBinaryHeap { data };
+        let len = heap.len();
+        for pos in (0..len / 2).rev() {
+            heap.sift_down(pos, len);
+        }
+        heap
+    }
+
+    /// Add an element to the heap.
+    #[inline]
+    #[track_caller]
+    pub fn push(&mut self, item: $array_item) {
+        self.data.push(item);
+        let last = self.len() - 1;
+        self.sift_up(last);
+    }
+
+    /// Remove the greatest element and return it, or `None` if the heap is empty.
+    pub fn pop(&mut self) -> Option<$array_item> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        self.data.swap(0, len - 1);
+        let item = self.data.pop();
+        self.sift_down(0, len - 1);
+        item
+    }
+
+    /// Consume the heap and return its elements in ascending order.
+    pub fn into_sorted_smallvec(mut self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+        self.data
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.data[pos] <= self.data[parent] {
+                break;
+            }
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    /// Restore the heap property below `pos`, considering only the first `end` elements.
+    fn sift_down(&mut self, mut pos: usize, end: usize) {
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && self.data[child] < self.data[child + 1] {
+                child += 1;
+            }
+            if self.data[pos] >= self.data[child] {
+                break;
+            }
+            self.data.swap(pos, child);
+            pos = child;
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallBinaryHeap {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    #[inline]
+    fn from(data: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        Self::from_smallvec(data)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<$array_item>
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = $array_item>>(iterable: I) -> Self {
+        Self::from_smallvec(iterable.into_iter().collect())
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    /// Iterate over the elements in arbitrary order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a SmallBinaryHeap<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'a, $array_item>;
+    type Item = &'a $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
@@ -0,0 +1,492 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, Not, Range},
+    slice,
+};
+
+const WORD_BITS: usize = 64;
+
+#[inline]
+fn words_for(bits: usize) -> usize {
+    bits / WORD_BITS + (bits % WORD_BITS).min(1)
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $words:ty
+) => {
+
+/// A growable vector of bits, packed into the `u64` words of a `SmallVec` so that small flag
+/// sets stay inline.
+///
+/// The bits past the length in the last word are always zero.
+///
+/// ```rust
+/// use smallvec::SmallBitVec;
+///
+/// let mut bits: SmallBitVec<[u64; 1]> = SmallBitVec::from_elem(false, 10);
+/// bits.set(3, true);
+/// bits.push(true);
+/// assert_eq!(bits.len(), 11);
+/// assert_eq!(bits.get(3), Some(true));
+/// assert_eq!(bits.iter_ones().collect::<Vec<_>>(), [3, 10]);
+/// assert!(!bits.spilled());
+/// ```
+pub struct SmallBitVec<$($impl_generics)*> {
+    words: $words,
+    len: usize,
+}
+
+impl<$($impl_generics)*> SmallBitVec<$($decl_generics)*> {
+    /// Construct an empty bit vector
+    #[inline]
+    pub fn new() -> Self {
+        SmallBitVec {
+            words: SmallVec::new(),
+            len: 0,
+        }
+    }
+
+    /// Construct an empty bit vector with room for at least `n` bits.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallBitVec {
+            words: SmallVec::with_capacity(words_for(n)),
+            len: 0,
+        }
+    }
+
+    /// Construct a bit vector of `n` bits, all set to `value`.
+    #[inline]
+    #[track_caller]
+    pub fn from_elem(value: bool, n: usize) -> Self {
+        let mut bits = Self::new();
+        bits.resize(n, value);
+        bits
+    }
+
+    /// The number of bits in the vector
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no bits
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bits the vector can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.words.capacity().saturating_mul(WORD_BITS)
+    }
+
+    /// Returns `true` if the words have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.words.spilled()
+    }
+
+    /// Returns the words holding the bits. Bit `i` is bit `i % 64` of word `i / 64`.
+    #[inline]
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the bit at `index`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            Some(self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Set the bit at `index` to `value`.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "index out of bounds");
+        let word = &mut self.words[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Append a bit to the end of the vector.
+    #[inline]
+    #[track_caller]
+    pub fn push(&mut self, value: bool) {
+        if self.len % WORD_BITS == 0 {
+            self.words.push(0);
+        }
+        self.len += 1;
+        if value {
+            self.set(self.len - 1, true);
+        }
+    }
+
+    /// Remove the last bit from the vector and return it, or `None` if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<bool> {
+        let value = self.get(self.len.wrapping_sub(1))?;
+        self.truncate(self.len - 1);
+        Some(value)
+    }
+
+    /// Shorten the vector to `len` bits. Does nothing if it is already that short.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.words.truncate(words_for(len));
+            self.len = len;
+            self.clear_unused_bits();
+        }
+    }
+
+    /// Resize the vector to `len` bits, setting any new bits to `value`.
+    #[track_caller]
+    pub fn resize(&mut self, len: usize, value: bool) {
+        if len <= self.len {
+            self.truncate(len);
+            return;
+        }
+        let fill = if value { !0 } else { 0 };
+        let used = self.len % WORD_BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last |= fill << used;
+            }
+        }
+        self.words.resize(words_for(len), fill);
+        self.len = len;
+        self.clear_unused_bits();
+    }
+
+    /// Remove all the bits of the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// The number of bits that are set
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if any bit is set.
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+
+    /// Returns `true` if every bit is set. This is the case for an empty vector.
+    #[inline]
+    pub fn all(&self) -> bool {
+        self.count_ones() == self.len
+    }
+
+    /// Iterate over the bits, from the first to the last.
+    #[inline]
+    pub fn iter(&self) -> SmallBitVecIter<'_> {
+        SmallBitVecIter {
+            words: &self.words,
+            range: 0..self.len,
+        }
+    }
+
+    /// Iterate over the indices of the bits that are set, in ascending order.
+    #[inline]
+    pub fn iter_ones(&self) -> SmallBitVecOnes<'_> {
+        let mut words = self.words.iter();
+        let word = words.next().copied().unwrap_or(0);
+        SmallBitVecOnes {
+            words,
+            word,
+            base: 0,
+        }
+    }
+
+    /// Unwrap the underlying vector of words.
+    #[inline]
+    pub fn into_words(self) -> $words {
+        self.words
+    }
+
+    fn clear_unused_bits(&mut self) {
+        let used = self.len % WORD_BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+
+    /// Apply `f` to every word of `self` and the matching word of `other`, treating missing
+    /// words as zero. The result has the length of the longer operand.
+    #[track_caller]
+    fn zip_words<F: FnMut(&mut u64, u64)>(&mut self, other: &Self, mut f: F) {
+        if other.len > self.len {
+            self.resize(other.len, false);
+        }
+        let mut other_words = other.words.iter().copied();
+        for word in self.words.iter_mut() {
+            f(word, other_words.next().unwrap_or(0));
+        }
+    }
+}
+
+impl<'a, $($impl_generics)*> BitAnd for &'a SmallBitVec<$($decl_generics)*> {
+    type Output = SmallBitVec<$($decl_generics)*>;
+
+    #[inline]
+    fn bitand(self, other: Self) -> Self::Output {
+        let mut bits = self.clone();
+        bits &= other;
+        bits
+    }
+}
+
+impl<'a, $($impl_generics)*> BitAndAssign<&'a Self> for SmallBitVec<$($decl_generics)*> {
+    /// Clear the bits that aren't set in `other`, extending `self` with zeroes if `other` is
+    /// longer.
+    #[inline]
+    fn bitand_assign(&mut self, other: &'a Self) {
+        self.zip_words(other, |word, other| *word &= other);
+    }
+}
+
+impl<'a, $($impl_generics)*> BitOr for &'a SmallBitVec<$($decl_generics)*> {
+    type Output = SmallBitVec<$($decl_generics)*>;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self::Output {
+        let mut bits = self.clone();
+        bits |= other;
+        bits
+    }
+}
+
+impl<'a, $($impl_generics)*> BitOrAssign<&'a Self> for SmallBitVec<$($decl_generics)*> {
+    /// Set the bits that are set in `other`, extending `self` if `other` is longer.
+    #[inline]
+    fn bitor_assign(&mut self, other: &'a Self) {
+        self.zip_words(other, |word, other| *word |= other);
+    }
+}
+
+impl<'a, $($impl_generics)*> BitXor for &'a SmallBitVec<$($decl_generics)*> {
+    type Output = SmallBitVec<$($decl_generics)*>;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self::Output {
+        let mut bits = self.clone();
+        bits ^= other;
+        bits
+    }
+}
+
+impl<'a, $($impl_generics)*> BitXorAssign<&'a Self> for SmallBitVec<$($decl_generics)*> {
+    /// Flip the bits that are set in `other`, extending `self` if `other` is longer.
+    #[inline]
+    fn bitxor_assign(&mut self, other: &'a Self) {
+        self.zip_words(other, |word, other| *word ^= other);
+    }
+}
+
+impl<$($impl_generics)*> Clone for SmallBitVec<$($decl_generics)*> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SmallBitVec {
+            words: self.words.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallBitVec<$($decl_generics)*> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallBitVec<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($impl_generics)*> Eq for SmallBitVec<$($decl_generics)*> {}
+
+impl<$($impl_generics)*> Extend<bool> for SmallBitVec<$($decl_generics)*> {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iterable: I) {
+        for value in iterable {
+            self.push(value);
+        }
+    }
+}
+
+impl<$($impl_generics)*> FromIterator<bool> for SmallBitVec<$($decl_generics)*> {
+    fn from_iter<I: IntoIterator<Item = bool>>(iterable: I) -> Self {
+        let mut bits = Self::new();
+        bits.extend(iterable);
+        bits
+    }
+}
+
+impl<$($impl_generics)*> Hash for SmallBitVec<$($decl_generics)*> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.words.hash(state);
+    }
+}
+
+impl<$($impl_generics)*> Index<usize> for SmallBitVec<$($decl_generics)*> {
+    type Output = bool;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, index: usize) -> &bool {
+        if self.get(index).expect("index out of bounds") {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a SmallBitVec<$($decl_generics)*> {
+    type IntoIter = SmallBitVecIter<'a>;
+    type Item = bool;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<$($impl_generics)*> Not for SmallBitVec<$($decl_generics)*> {
+    type Output = Self;
+
+    /// Flip every bit of the vector.
+    #[inline]
+    fn not(mut self) -> Self {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.clear_unused_bits();
+        self
+    }
+}
+
+impl<$($impl_generics)*> PartialEq for SmallBitVec<$($decl_generics)*> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.words[..] == other.words[..]
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const WORDS: usize], [{ WORDS }], SmallVec<u64, { WORDS }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u64>], [A], SmallVec<A>);
+
+/// An iterator over the bits of a `SmallBitVec`.
+///
+/// Returned from [`SmallBitVec::iter`][1].
+///
+/// [1]: struct.SmallBitVec.html#method.iter
+#[derive(Clone, Debug)]
+pub struct SmallBitVecIter<'a> {
+    words: &'a [u64],
+    range: Range<usize>,
+}
+
+impl<'a> SmallBitVecIter<'a> {
+    #[inline]
+    fn bit(&self, index: usize) -> bool {
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 != 0
+    }
+}
+
+impl<'a> Iterator for SmallBitVecIter<'a> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        self.range.next().map(|index| self.bit(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for SmallBitVecIter<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<bool> {
+        self.range.next_back().map(|index| self.bit(index))
+    }
+}
+
+impl<'a> ExactSizeIterator for SmallBitVecIter<'a> {}
+
+/// An iterator over the indices of the set bits of a `SmallBitVec`.
+///
+/// Returned from [`SmallBitVec::iter_ones`][1].
+///
+/// [1]: struct.SmallBitVec.html#method.iter_ones
+#[derive(Clone, Debug)]
+pub struct SmallBitVecOnes<'a> {
+    words: slice::Iter<'a, u64>,
+    // The bits of the current word that haven't been yielded yet.
+    word: u64,
+    // The index of the first bit of the current word.
+    base: usize,
+}
+
+impl<'a> Iterator for SmallBitVecOnes<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.word = *self.words.next()?;
+            self.base += WORD_BITS;
+        }
+        let index = self.base + self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(index)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.words.len() * WORD_BITS;
+        (
+            (self.word != 0) as usize,
+            Some(self.word.count_ones() as usize + rest),
+        )
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    hint::unreachable_unchecked,
+    ops::Deref,
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A clone-on-write slice, like `Cow<[T]>`, whose owned form is a `SmallVec`.
+///
+/// It starts out borrowing a slice and only copies the elements, inline if they fit, the first
+/// time it's mutated through `to_mut`. Reading goes through `Deref` either way.
+///
+/// ```rust
+/// use smallvec::SmallCow;
+///
+/// const DEFAULTS: &[u8] = &[1, 2, 3];
+///
+/// let mut unchanged = SmallCow::<[u8; 4]>::from(DEFAULTS);
+/// let mut tweaked = unchanged.clone();
+/// tweaked.to_mut().push(4);
+/// assert!(unchanged.is_borrowed());
+/// assert!(tweaked.is_owned() && !tweaked.to_mut().spilled());
+/// assert_eq!(&tweaked[..], &[1, 2, 3, 4]);
+/// ```
+pub enum SmallCow<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    /// Borrowed elements
+    Borrowed(&'a [$array_item]),
+    /// Owned elements
+    Owned(SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>),
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Returns `true` if the elements are borrowed
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, SmallCow::Borrowed(_))
+    }
+
+    /// Returns `true` if the elements are owned
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Returns a mutable reference to the owned vector, cloning the borrowed elements into it
+    /// first if needed.
+    #[inline]
+    pub fn to_mut(&mut self) -> &mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+    where
+        $array_item: Clone,
+    {
+        if let SmallCow::Borrowed(slice) = *self {
+            *self = SmallCow::Owned(SmallVec::from(slice));
+        }
+        match self {
+            SmallCow::Owned(vec) => vec,
+            SmallCow::Borrowed(_) => unsafe { unreachable_unchecked() },
+        }
+    }
+
+    /// Unwrap the owned vector, cloning the borrowed elements into a new one if needed.
+    #[inline]
+    pub fn into_owned(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+    where
+        $array_item: Clone,
+    {
+        match self {
+            SmallCow::Borrowed(slice) => SmallVec::from(slice),
+            SmallCow::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> AsRef<[$array_item]>
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn as_ref(&self) -> &[$array_item] {
+        self
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            SmallCow::Borrowed(slice) => SmallCow::Borrowed(slice),
+            SmallCow::Owned(vec) => SmallCow::Owned(vec.clone()),
+        }
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        SmallCow::Borrowed(&[])
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Target = [$array_item];
+    #[inline]
+    fn deref(&self) -> &[$array_item] {
+        match self {
+            SmallCow::Borrowed(slice) => slice,
+            SmallCow::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq {}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<&'a [$array_item]>
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn from(slice: &'a [$array_item]) -> Self {
+        SmallCow::Borrowed(slice)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn from(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        SmallCow::Owned(vec)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Hash
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<'a, 'b, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'b SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'b, $array_item>;
+    type Item = &'b $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for SmallCow<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
@@ -0,0 +1,210 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{
+    cmp,
+    convert::TryFrom,
+    fmt::{self, Debug},
+};
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $vec:ty
+) => {
+
+/// A cursor over a byte `SmallVec`, implementing `Read`, `BufRead`, `Write` and `Seek` the way
+/// `std::io::Cursor<Vec<u8>>` does.
+///
+/// Writes overwrite the bytes at the current position and extend the vector past its end,
+/// zero-filling the gap if the position was seeked beyond it.
+///
+/// ```rust
+/// use smallvec::SmallCursor;
+/// use std::io::{Read, Seek, SeekFrom, Write};
+///
+/// let mut cursor: SmallCursor<[u8; 8]> = SmallCursor::default();
+/// cursor.write_all(b"hello").unwrap();
+/// cursor.seek(SeekFrom::Start(1)).unwrap();
+/// cursor.write_all(b"E").unwrap();
+/// cursor.set_position(0);
+///
+/// let mut s = String::new();
+/// cursor.read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "hEllo");
+/// assert!(!cursor.into_inner().spilled());
+/// ```
+pub struct SmallCursor<$($impl_generics)*> {
+    vec: $vec,
+    pos: u64,
+}
+
+impl<$($impl_generics)*> SmallCursor<$($decl_generics)*> {
+    /// Wrap `vec` in a cursor positioned at its start.
+    #[inline]
+    pub fn new(vec: $vec) -> Self {
+        SmallCursor { vec, pos: 0 }
+    }
+
+    /// Unwrap the underlying vector.
+    #[inline]
+    pub fn into_inner(self) -> $vec {
+        self.vec
+    }
+
+    /// Returns a reference to the underlying vector.
+    #[inline]
+    pub fn get_ref(&self) -> &$vec {
+        &self.vec
+    }
+
+    /// Returns a mutable reference to the underlying vector.
+    ///
+    /// The position is left as is, even if the vector gets shorter than it.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut $vec {
+        &mut self.vec
+    }
+
+    /// The current position of the cursor
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Move the cursor to `pos`, which may be past the end of the vector.
+    #[inline]
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+
+    /// Returns the bytes from the current position to the end of the vector, which are empty
+    /// if the position is past the end.
+    #[inline]
+    pub fn remaining_slice(&self) -> &[u8] {
+        let start = cmp::min(self.pos, self.vec.len() as u64) as usize;
+        &self.vec[start..]
+    }
+}
+
+impl<$($impl_generics)*> BufRead for SmallCursor<$($decl_generics)*> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining_slice())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+impl<$($impl_generics)*> Clone for SmallCursor<$($decl_generics)*> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SmallCursor {
+            vec: self.vec.clone(),
+            pos: self.pos,
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallCursor<$($decl_generics)*> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SmallCursor")
+            .field("vec", &self.vec)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallCursor<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(SmallVec::new())
+    }
+}
+
+impl<$($impl_generics)*> From<$vec> for SmallCursor<$($decl_generics)*> {
+    #[inline]
+    fn from(vec: $vec) -> Self {
+        Self::new(vec)
+    }
+}
+
+impl<$($impl_generics)*> Read for SmallCursor<$($decl_generics)*> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = Read::read(&mut self.remaining_slice(), buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(&mut self.remaining_slice(), buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+}
+
+impl<$($impl_generics)*> Seek for SmallCursor<$($decl_generics)*> {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match style {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::End(n) => (self.vec.len() as u64, n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        let pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.wrapping_neg() as u64)
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl<$($impl_generics)*> Write for SmallCursor<$($decl_generics)*> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pos = usize::try_from(self.pos).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cursor position exceeds maximum possible vector length",
+            )
+        })?;
+        if self.vec.len() < pos {
+            self.vec.resize(pos, 0);
+        }
+        let overwritten = cmp::min(self.vec.len() - pos, buf.len());
+        self.vec[pos..pos + overwritten].copy_from_slice(&buf[..overwritten]);
+        self.vec.extend_from_slice(&buf[overwritten..]);
+        self.pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [{ N }], SmallVec<u8, { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A], SmallVec<A>);
@@ -0,0 +1,539 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+    iter::FromIterator,
+    mem,
+    ops::Index,
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $entries:ty
+) => {
+
+/// A map that stores its entries in a `SmallVec` and looks keys up with a linear scan.
+///
+/// For the handful of keys it is meant for, scanning a contiguous inline buffer beats hashing
+/// and doesn't allocate. Keys only need `Eq`. Entries are kept in insertion order, which is
+/// also the iteration order.
+///
+/// ```rust
+/// use smallvec::SmallMap;
+///
+/// let mut map: SmallMap<&str, u32, [(&str, u32); 4]> = SmallMap::new();
+/// map.insert("a", 1);
+/// *map.entry("b").or_insert(0) += 2;
+/// *map.entry("a").or_insert(0) += 2;
+/// assert_eq!(map.get("a"), Some(&3));
+/// assert_eq!(map.remove("b"), Some(2));
+/// assert_eq!(map.len(), 1);
+/// ```
+pub struct SmallMap<$($impl_generics)*> {
+    entries: $entries,
+}
+
+impl<$($impl_generics)*> SmallMap<$($decl_generics)*> {
+    /// Construct an empty map
+    #[inline]
+    pub fn new() -> Self {
+        SmallMap {
+            entries: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty map with room for at least `n` entries.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallMap {
+            entries: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// The number of entries in the map
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of entries the map can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns `true` if the entries have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.entries.spilled()
+    }
+
+    /// Remove all the entries of the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// Iterate over the keys and values, in insertion order.
+    #[inline]
+    pub fn iter(&self) -> SmallMapIter<'_, K, V> {
+        SmallMapIter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Iterate over the keys and mutable values, in insertion order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> SmallMapIterMut<'_, K, V> {
+        SmallMapIterMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    /// Iterate over the keys, in insertion order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the values, in insertion order.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Iterate over the mutable values, in insertion order.
+    #[inline]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.entries.retain(|(k, v)| f(k, v))
+    }
+
+    /// Unwrap the underlying vector of entries.
+    #[inline]
+    pub fn into_smallvec(self) -> $entries {
+        self.entries
+    }
+}
+
+impl<$($impl_generics)*> SmallMap<$($decl_generics)*>
+where
+    K: Eq,
+{
+    fn position<Q: ?Sized + Eq>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.iter().position(|(k, _)| k.borrow() == key)
+    }
+
+    /// Returns `true` if the map contains `key`.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Eq>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Returns a reference to the value of `key`, if it is in the map.
+    #[inline]
+    pub fn get<Q: ?Sized + Eq>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.position(key).map(|i| &self.entries[i].1)
+    }
+
+    /// Returns a mutable reference to the value of `key`, if it is in the map.
+    #[inline]
+    pub fn get_mut<Q: ?Sized + Eq>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.position(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Insert a value for `key`, returning the previous value if there was one. The key
+    /// already in the map is kept in that case.
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.position(&key) {
+            Some(index) => Some(mem::replace(&mut self.entries[index].1, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Remove `key` from the map, returning its value if it was there. The entries after it
+    /// keep their order.
+    #[inline]
+    pub fn remove<Q: ?Sized + Eq>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Remove `key` from the map, returning the stored key and value if it was there.
+    pub fn remove_entry<Q: ?Sized + Eq>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Get the entry of `key` for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> SmallMapEntry<'_, $($decl_generics)*> {
+        match self.position(&key) {
+            Some(index) => SmallMapEntry::Occupied(SmallMapOccupiedEntry { map: self, index }),
+            None => SmallMapEntry::Vacant(SmallMapVacantEntry { map: self, key }),
+        }
+    }
+}
+
+/// A view into a single entry of a `SmallMap`, which may be vacant or occupied.
+///
+/// Returned from [`SmallMap::entry`][1].
+///
+/// [1]: struct.SmallMap.html#method.entry
+pub enum SmallMapEntry<'a, $($impl_generics)*> {
+    /// An entry that is already in the map.
+    Occupied(SmallMapOccupiedEntry<'a, $($decl_generics)*>),
+    /// A key that isn't in the map.
+    Vacant(SmallMapVacantEntry<'a, $($decl_generics)*>),
+}
+
+/// An entry of a `SmallMap` that holds a value.
+pub struct SmallMapOccupiedEntry<'a, $($impl_generics)*> {
+    map: &'a mut SmallMap<$($decl_generics)*>,
+    index: usize,
+}
+
+/// An entry of a `SmallMap` whose key isn't in the map.
+pub struct SmallMapVacantEntry<'a, $($impl_generics)*> {
+    map: &'a mut SmallMap<$($decl_generics)*>,
+    key: K,
+}
+
+impl<'a, $($impl_generics)*> SmallMapEntry<'a, $($decl_generics)*> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            SmallMapEntry::Occupied(entry) => entry.key(),
+            SmallMapEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Insert `default` if the entry is vacant, and return a mutable reference to the value.
+    #[inline]
+    #[track_caller]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and return a mutable reference
+    /// to the value.
+    #[inline]
+    #[track_caller]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            SmallMapEntry::Occupied(entry) => entry.into_mut(),
+            SmallMapEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Insert the default value if the entry is vacant, and return a mutable reference to the
+    /// value.
+    #[inline]
+    #[track_caller]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Call `f` on the value if the entry is occupied.
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let SmallMapEntry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, $($impl_generics)*> SmallMapOccupiedEntry<'a, $($decl_generics)*> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].0
+    }
+
+    /// Returns a reference to the value of the entry.
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].1
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Convert the entry into a mutable reference to its value, bound to the map.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replace the value of the entry, returning the old one.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Remove the entry from the map, returning its value.
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Remove the entry from the map, returning its key and value.
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.entries.remove(self.index)
+    }
+}
+
+impl<'a, $($impl_generics)*> SmallMapVacantEntry<'a, $($decl_generics)*> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take back the key of the entry.
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Insert `value` for the key of the entry, and return a mutable reference to it.
+    #[inline]
+    #[track_caller]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let entries = &mut self.map.entries;
+        entries.push((self.key, value));
+        let last = entries.len() - 1;
+        &mut entries[last].1
+    }
+}
+
+impl<$($impl_generics)*> Clone for SmallMap<$($decl_generics)*>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallMap {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallMap<$($decl_generics)*>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallMap<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($impl_generics)*> Eq for SmallMap<$($decl_generics)*>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<$($impl_generics)*> Extend<(K, V)> for SmallMap<$($decl_generics)*>
+where
+    K: Eq,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        for (key, value) in iterable {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<$($impl_generics)*> FromIterator<(K, V)> for SmallMap<$($decl_generics)*>
+where
+    K: Eq,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iterable);
+        map
+    }
+}
+
+impl<'q, Q: ?Sized + Eq, $($impl_generics)*> Index<&'q Q> for SmallMap<$($decl_generics)*>
+where
+    K: Eq + Borrow<Q>,
+{
+    type Output = V;
+
+    /// Returns the value of `key`.
+    ///
+    /// Panics if the key isn't in the map.
+    #[track_caller]
+    fn index(&self, key: &'q Q) -> &V {
+        self.get(key).expect("key not found in `SmallMap`")
+    }
+}
+
+impl<$($impl_generics)*> IntoIterator for SmallMap<$($decl_generics)*> {
+    type IntoIter = <$entries as IntoIterator>::IntoIter;
+    type Item = (K, V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a SmallMap<$($decl_generics)*> {
+    type IntoIter = SmallMapIter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a mut SmallMap<$($decl_generics)*> {
+    type IntoIter = SmallMapIterMut<'a, K, V>;
+    type Item = (&'a K, &'a mut V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<$($impl_generics)*> PartialEq for SmallMap<$($decl_generics)*>
+where
+    K: Eq,
+    V: PartialEq,
+{
+    /// Two maps are equal if they have the same entries, whatever their order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([K, V, const N: usize], [K, V, { N }], SmallVec<(K, V), { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([K, V, A: Array<Item = (K, V)>], [K, V, A], SmallVec<A>);
+
+/// An iterator over the keys and values of a `SmallMap` or a `SmallOrdMap`.
+///
+/// Returned from [`SmallMap::iter`][1] and [`SmallOrdMap::iter`][2].
+///
+/// [1]: struct.SmallMap.html#method.iter
+/// [2]: struct.SmallOrdMap.html#method.iter
+pub struct SmallMapIter<'a, K, V> {
+    pub(crate) iter: slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for SmallMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for SmallMapIter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for SmallMapIter<'a, K, V> {}
+
+/// An iterator over the keys and mutable values of a `SmallMap` or a `SmallOrdMap`.
+///
+/// Returned from [`SmallMap::iter_mut`][1] and [`SmallOrdMap::iter_mut`][2].
+///
+/// [1]: struct.SmallMap.html#method.iter_mut
+/// [2]: struct.SmallOrdMap.html#method.iter_mut
+pub struct SmallMapIterMut<'a, K, V> {
+    pub(crate) iter: slice::IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for SmallMapIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for SmallMapIterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for SmallMapIterMut<'a, K, V> {}
@@ -0,0 +1,394 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{utils::stable_sort_by, SmallMapIter, SmallMapIterMut, SmallVec};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    iter::FromIterator,
+    mem,
+    ops::Index,
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $entries:ty
+) => {
+
+/// An ordered map that keeps its entries sorted by key in a `SmallVec`, and looks keys up with a
+/// binary search.
+///
+/// Lookups are logarithmic and insertions and removals linear, without the node allocations of
+/// a `BTreeMap`. Iteration is in ascending key order.
+///
+/// ```rust
+/// use smallvec::SmallOrdMap;
+///
+/// let mut map: SmallOrdMap<u32, &str, [(u32, &str); 4]> = SmallOrdMap::new();
+/// map.insert(3, "c");
+/// map.insert(1, "a");
+/// map.insert(2, "b");
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+/// assert_eq!(map.first_key_value(), Some((&1, &"a")));
+/// assert_eq!(map.remove(&2), Some("b"));
+/// ```
+pub struct SmallOrdMap<$($impl_generics)*> {
+    entries: $entries,
+}
+
+impl<$($impl_generics)*> SmallOrdMap<$($decl_generics)*> {
+    /// Construct an empty map
+    #[inline]
+    pub fn new() -> Self {
+        SmallOrdMap {
+            entries: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty map with room for at least `n` entries.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallOrdMap {
+            entries: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// The number of entries in the map
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of entries the map can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns `true` if the entries have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.entries.spilled()
+    }
+
+    /// Remove all the entries of the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// Returns the entry with the smallest key, or `None` if the map is empty.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.first().map(|(k, v)| (k, v))
+    }
+
+    /// Returns the entry with the greatest key, or `None` if the map is empty.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|(k, v)| (k, v))
+    }
+
+    /// Iterate over the keys and values, in ascending key order.
+    #[inline]
+    pub fn iter(&self) -> SmallMapIter<'_, K, V> {
+        SmallMapIter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Iterate over the keys and mutable values, in ascending key order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> SmallMapIterMut<'_, K, V> {
+        SmallMapIterMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    /// Iterate over the keys, in ascending order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the values, in ascending key order.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Iterate over the mutable values, in ascending key order.
+    #[inline]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Keep only the entries for which `f` returns `true`.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.entries.retain(|(k, v)| f(k, v))
+    }
+
+    /// Unwrap the underlying vector of entries, sorted by key.
+    #[inline]
+    pub fn into_smallvec(self) -> $entries {
+        self.entries
+    }
+}
+
+impl<$($impl_generics)*> SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord,
+{
+    /// Build a map out of a vector of entries, sorting them by key. When a key appears more than
+    /// once, the last value wins.
+    pub fn from_smallvec(mut entries: $entries) -> Self {
+        stable_sort_by(&mut entries, |a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|later, kept| {
+            if later.0 == kept.0 {
+                mem::swap(&mut later.1, &mut kept.1);
+                true
+            } else {
+                false
+            }
+        });
+        SmallOrdMap { entries }
+    }
+
+    fn search<Q: ?Sized + Ord>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Returns `true` if the map contains `key`.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// Returns a reference to the value of `key`, if it is in the map.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).ok().map(|i| &self.entries[i].1)
+    }
+
+    /// Returns a mutable reference to the value of `key`, if it is in the map.
+    #[inline]
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Insert a value for `key`, returning the previous value if there was one. The key
+    /// already in the map is kept in that case.
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.search(&key) {
+            Ok(index) => Some(mem::replace(&mut self.entries[index].1, value)),
+            Err(index) => {
+                self.entries.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value of `key`, inserting the result of `default`
+    /// first if the key isn't in the map.
+    #[track_caller]
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, default: F) -> &mut V {
+        let index = match self.search(&key) {
+            Ok(index) => index,
+            Err(index) => {
+                self.entries.insert(index, (key, default()));
+                index
+            }
+        };
+        &mut self.entries[index].1
+    }
+
+    /// Remove `key` from the map, returning its value if it was there.
+    #[inline]
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Remove `key` from the map, returning the stored key and value if it was there.
+    pub fn remove_entry<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        Some(self.entries.remove(index))
+    }
+}
+
+impl<$($impl_generics)*> Clone for SmallOrdMap<$($decl_generics)*>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdMap {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallOrdMap<$($decl_generics)*>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallOrdMap<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($impl_generics)*> Eq for SmallOrdMap<$($decl_generics)*>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<$($impl_generics)*> Extend<(K, V)> for SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        for (key, value) in iterable {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<$($impl_generics)*> From<$entries> for SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord,
+{
+    #[inline]
+    fn from(entries: $entries) -> Self {
+        Self::from_smallvec(entries)
+    }
+}
+
+impl<$($impl_generics)*> FromIterator<(K, V)> for SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        Self::from_smallvec(iterable.into_iter().collect())
+    }
+}
+
+impl<'q, Q: ?Sized + Ord, $($impl_generics)*> Index<&'q Q> for SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord + Borrow<Q>,
+{
+    type Output = V;
+
+    /// Returns the value of `key`.
+    ///
+    /// Panics if the key isn't in the map.
+    #[track_caller]
+    fn index(&self, key: &'q Q) -> &V {
+        self.get(key).expect("key not found in `SmallOrdMap`")
+    }
+}
+
+impl<$($impl_generics)*> IntoIterator for SmallOrdMap<$($decl_generics)*> {
+    type IntoIter = <$entries as IntoIterator>::IntoIter;
+    type Item = (K, V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a SmallOrdMap<$($decl_generics)*> {
+    type IntoIter = SmallMapIter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a mut SmallOrdMap<$($decl_generics)*> {
+    type IntoIter = SmallMapIterMut<'a, K, V>;
+    type Item = (&'a K, &'a mut V);
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<$($impl_generics)*> Ord for SmallOrdMap<$($decl_generics)*>
+where
+    K: Ord,
+    V: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.entries.cmp(&other.entries)
+    }
+}
+
+impl<$($impl_generics)*> PartialEq for SmallOrdMap<$($decl_generics)*>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.entries[..] == other.entries[..]
+    }
+}
+
+impl<$($impl_generics)*> PartialOrd for SmallOrdMap<$($decl_generics)*>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.entries[..].partial_cmp(&other.entries[..])
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([K, V, const N: usize], [K, V, { N }], SmallVec<(K, V), { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([K, V, A: Array<Item = (K, V)>], [K, V, A], SmallVec<A>);
@@ -0,0 +1,425 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{utils::stable_sort_by, IntoIter, SmallVec};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    iter::FromIterator,
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// An ordered set that keeps its elements sorted in a `SmallVec`, and looks them up with a
+/// binary search.
+///
+/// Lookups are logarithmic and insertions and removals linear, without the node allocations of
+/// a `BTreeSet`. The set operations merge two sets in linear time into a sorted `SmallVec`.
+///
+/// ```rust
+/// use smallvec::SmallOrdSet;
+///
+/// let a: SmallOrdSet<[u8; 8]> = [5, 1, 3].iter().copied().collect();
+/// let b: SmallOrdSet<[u8; 8]> = [3, 4, 5].iter().copied().collect();
+/// assert_eq!(a.as_slice(), &[1, 3, 5]);
+/// assert_eq!(&a.union(&b)[..], &[1, 3, 4, 5]);
+/// assert_eq!(&a.intersection(&b)[..], &[3, 5]);
+/// assert_eq!(&a.difference(&b)[..], &[1]);
+/// ```
+pub struct SmallOrdSet<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    items: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty set
+    #[inline]
+    pub fn new() -> Self {
+        SmallOrdSet {
+            items: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty set with room for at least `n` elements.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallOrdSet {
+            items: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// The number of elements in the set
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the set is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of elements the set can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns `true` if the elements have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.items.spilled()
+    }
+
+    /// Remove all the elements of the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    /// Returns the smallest element, or `None` if the set is empty.
+    #[inline]
+    pub fn first(&self) -> Option<&$array_item> {
+        self.items.first()
+    }
+
+    /// Returns the greatest element, or `None` if the set is empty.
+    #[inline]
+    pub fn last(&self) -> Option<&$array_item> {
+        self.items.last()
+    }
+
+    /// Iterate over the elements in ascending order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, $array_item> {
+        self.items.iter()
+    }
+
+    /// Keep only the elements for which `f` returns `true`.
+    pub fn retain<F: FnMut(&$array_item) -> bool>(&mut self, mut f: F) {
+        self.items.retain(|item| f(item))
+    }
+
+    /// Returns the elements as a sorted slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[$array_item] {
+        &self.items
+    }
+
+    /// Unwrap the underlying sorted vector.
+    #[inline]
+    pub fn into_smallvec(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.items
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    /// Build a set out of the elements of a vector, sorting them and dropping the duplicates.
+    pub fn from_smallvec(mut items: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        stable_sort_by(&mut items, Ord::cmp);
+        items.dedup();
+        SmallOrdSet { items }
+    }
+
+    fn search<Q: ?Sized + Ord>(&self, value: &Q) -> Result<usize, usize>
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.items.binary_search_by(|item| item.borrow().cmp(value))
+    }
+
+    /// Returns `true` if the set contains `value`.
+    #[inline]
+    pub fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.search(value).is_ok()
+    }
+
+    /// Returns a reference to the element equal to `value`, if there is one.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, value: &Q) -> Option<&$array_item>
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.search(value).ok().map(|i| &self.items[i])
+    }
+
+    /// Add `value` to the set, returning `false` (and dropping `value`) if it was already there.
+    #[track_caller]
+    pub fn insert(&mut self, value: $array_item) -> bool {
+        match self.search(&value) {
+            Ok(_) => false,
+            Err(index) => {
+                self.items.insert(index, value);
+                true
+            }
+        }
+    }
+
+    /// Remove `value` from the set, returning `true` if it was there.
+    #[inline]
+    pub fn remove<Q: ?Sized + Ord>(&mut self, value: &Q) -> bool
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Remove the element equal to `value` from the set and return it, if there is one.
+    pub fn take<Q: ?Sized + Ord>(&mut self, value: &Q) -> Option<$array_item>
+    where
+        $array_item: Borrow<Q>,
+    {
+        let index = self.search(value).ok()?;
+        Some(self.items.remove(index))
+    }
+
+    /// Returns `true` if every element of the set is in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|item| other.contains(item))
+    }
+
+    /// Returns `true` if the two sets have no element in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        for (a, b) in Merge::new(&self.items, &other.items) {
+            if a.is_some() && b.is_some() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone + Ord,
+{
+    /// Returns the sorted elements that are in `self` or `other`.
+    pub fn union(&self, other: &Self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let mut out = SmallVec::with_capacity(self.len().max(other.len()));
+        for (a, b) in Merge::new(&self.items, &other.items) {
+            out.push(a.or(b).unwrap().clone());
+        }
+        out
+    }
+
+    /// Returns the sorted elements that are in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let mut out = SmallVec::new();
+        for (a, b) in Merge::new(&self.items, &other.items) {
+            if let (Some(a), Some(_)) = (a, b) {
+                out.push(a.clone());
+            }
+        }
+        out
+    }
+
+    /// Returns the sorted elements that are in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let mut out = SmallVec::new();
+        for (a, b) in Merge::new(&self.items, &other.items) {
+            if let (Some(a), None) = (a, b) {
+                out.push(a.clone());
+            }
+        }
+        out
+    }
+
+    /// Returns the sorted elements that are in exactly one of `self` and `other`.
+    pub fn symmetric_difference(&self, other: &Self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let mut out = SmallVec::new();
+        for (a, b) in Merge::new(&self.items, &other.items) {
+            match (a, b) {
+                (Some(_), Some(_)) => {}
+                (a, b) => out.push(a.or(b).unwrap().clone()),
+            }
+        }
+        out
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdSet {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        for item in iterable {
+            self.insert(item);
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    #[inline]
+    fn from(items: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        Self::from_smallvec(items)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<$array_item>
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = $array_item>>(iterable: I) -> Self {
+        Self::from_smallvec(iterable.into_iter().collect())
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'a, $array_item>;
+    type Item = &'a $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Ord
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.items.cmp(&other.items)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.items[..] == other.items[..]
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialOrd
+    for SmallOrdSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.items[..].partial_cmp(&other.items[..])
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
+
+/// Walks two sorted, deduplicated slices in step, yielding each distinct element once along with
+/// which of the slices contain it.
+struct Merge<'a, T> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T: Ord> Merge<'a, T> {
+    #[inline]
+    fn new(a: &'a [T], b: &'a [T]) -> Self {
+        Merge { a, b }
+    }
+}
+
+impl<'a, T: Ord> Iterator for Merge<'a, T> {
+    type Item = (Option<&'a T>, Option<&'a T>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let ordering = match (self.a.first(), self.b.first()) {
+            (None, None) => return None,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.cmp(b),
+        };
+        let (a, b) = match ordering {
+            Ordering::Less => (Some(&self.a[0]), None),
+            Ordering::Greater => (None, Some(&self.b[0])),
+            Ordering::Equal => (Some(&self.a[0]), Some(&self.b[0])),
+        };
+        if a.is_some() {
+            self.a = &self.a[1..];
+        }
+        if b.is_some() {
+            self.b = &self.b[1..];
+        }
+        Some((a, b))
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{IntoIter, SmallVec};
+use core::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+    iter::FromIterator,
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A set that stores its elements in a `SmallVec` and looks them up with a linear scan.
+///
+/// The set counterpart of [`SmallMap`][1]: elements only need `Eq`, and are kept in insertion
+/// order.
+///
+/// ```rust
+/// use smallvec::SmallSet;
+///
+/// let mut set: SmallSet<[char; 4]> = SmallSet::new();
+/// assert!(set.insert('a'));
+/// assert!(set.insert('b'));
+/// assert!(!set.insert('a'));
+/// assert!(set.contains(&'b'));
+/// assert!(set.remove(&'a'));
+/// assert_eq!(set.len(), 1);
+/// ```
+///
+/// [1]: struct.SmallMap.html
+pub struct SmallSet<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    items: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty set
+    #[inline]
+    pub fn new() -> Self {
+        SmallSet {
+            items: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty set with room for at least `n` elements.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallSet {
+            items: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// The number of elements in the set
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the set is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of elements the set can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns `true` if the elements have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.items.spilled()
+    }
+
+    /// Remove all the elements of the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items.clear()
+    }
+
+    /// Iterate over the elements, in insertion order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, $array_item> {
+        self.items.iter()
+    }
+
+    /// Keep only the elements for which `f` returns `true`.
+    pub fn retain<F: FnMut(&$array_item) -> bool>(&mut self, mut f: F) {
+        self.items.retain(|item| f(item))
+    }
+
+    /// Returns the elements as a slice, in insertion order.
+    #[inline]
+    pub fn as_slice(&self) -> &[$array_item] {
+        &self.items
+    }
+
+    /// Unwrap the underlying vector.
+    #[inline]
+    pub fn into_smallvec(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.items
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+    fn position<Q: ?Sized + Eq>(&self, value: &Q) -> Option<usize>
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.items.iter().position(|item| item.borrow() == value)
+    }
+
+    /// Returns `true` if the set contains `value`.
+    #[inline]
+    pub fn contains<Q: ?Sized + Eq>(&self, value: &Q) -> bool
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.position(value).is_some()
+    }
+
+    /// Returns a reference to the element equal to `value`, if there is one.
+    #[inline]
+    pub fn get<Q: ?Sized + Eq>(&self, value: &Q) -> Option<&$array_item>
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.position(value).map(|i| &self.items[i])
+    }
+
+    /// Add `value` to the set, returning `false` (and dropping `value`) if it was already there.
+    #[track_caller]
+    pub fn insert(&mut self, value: $array_item) -> bool {
+        if self.position(&value).is_some() {
+            return false;
+        }
+        self.items.push(value);
+        true
+    }
+
+    /// Remove `value` from the set, returning `true` if it was there. The elements after it keep
+    /// their order.
+    #[inline]
+    pub fn remove<Q: ?Sized + Eq>(&mut self, value: &Q) -> bool
+    where
+        $array_item: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Remove the element equal to `value` from the set and return it, if there is one.
+    pub fn take<Q: ?Sized + Eq>(&mut self, value: &Q) -> Option<$array_item>
+    where
+        $array_item: Borrow<Q>,
+    {
+        let index = self.position(value)?;
+        Some(self.items.remove(index))
+    }
+
+    /// Returns `true` if every element of the set is in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|item| other.contains(item))
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallSet {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        for item in iterable {
+            self.insert(item);
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<$array_item>
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+    fn from_iter<I: IntoIterator<Item = $array_item>>(iterable: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iterable);
+        set
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'a, $array_item>;
+    type Item = &'a $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for SmallSet<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+    /// Two sets are equal if they have the same elements, whatever their order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
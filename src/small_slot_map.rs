@@ -0,0 +1,345 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use core::{
+    fmt::{self, Debug},
+    iter::Enumerate,
+    mem,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+const NO_FREE_SLOT: usize = usize::MAX;
+
+/// A key returned by [`SmallSlotMap::insert`][1], which stays unique to the inserted value even
+/// after the value is removed and its slot is reused.
+///
+/// [1]: struct.SmallSlotMap.html#method.insert
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SlotKey {
+    index: usize,
+    generation: usize,
+}
+
+/// A slot of a `SmallSlotMap`. Only needed to name the backing store of the map.
+pub struct Slot<T> {
+    generation: usize,
+    value: SlotValue<T>,
+}
+
+enum SlotValue<T> {
+    Occupied(T),
+    Vacant { next_free: usize },
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $slots:ty
+) => {
+
+/// A slot map (generational arena) that stores its first slots inline.
+///
+/// Values are addressed through the [`SlotKey`][1] returned on insertion. Removing a value frees
+/// its slot for reuse but bumps its generation, so keys to removed values never alias values
+/// inserted later.
+///
+/// [1]: struct.SlotKey.html
+pub struct SmallSlotMap<$($impl_generics)*> {
+    slots: $slots,
+    free_head: usize,
+    len: usize,
+}
+
+impl<$($impl_generics)*> SmallSlotMap<$($decl_generics)*> {
+    /// Construct an empty map
+    #[inline]
+    pub fn new() -> Self {
+        SmallSlotMap {
+            slots: SmallVec::new(),
+            free_head: NO_FREE_SLOT,
+            len: 0,
+        }
+    }
+
+    /// Construct an empty map with room for at least `n` values.
+    #[inline]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallSlotMap {
+            slots: SmallVec::with_capacity(n),
+            free_head: NO_FREE_SLOT,
+            len: 0,
+        }
+    }
+
+    /// The number of values stored in the map
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of values the map can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Returns `true` if the slots have spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.slots.spilled()
+    }
+
+    /// Insert a value and return the key that addresses it.
+    ///
+    /// ```rust
+    /// use smallvec::{SmallSlotMap, Slot};
+    ///
+    /// let mut map: SmallSlotMap<&str, [Slot<&str>; 4]> = SmallSlotMap::new();
+    /// let a = map.insert("a");
+    /// assert_eq!(map[a], "a");
+    /// assert_eq!(map.remove(a), Some("a"));
+    ///
+    /// let b = map.insert("b");
+    /// assert_eq!(map.get(a), None);
+    /// assert_eq!(map.get(b), Some(&"b"));
+    /// ```
+    pub fn insert(&mut self, value: T) -> SlotKey {
+        if self.free_head == NO_FREE_SLOT {
+            let index = self.slots.len();
+            self.slots.push(Slot {
+                generation: 0,
+                value: SlotValue::Occupied(value),
+            });
+            self.len += 1;
+            return SlotKey {
+                index,
+                generation: 0,
+            };
+        }
+        let index = self.free_head;
+        let slot = &mut self.slots[index];
+        if let SlotValue::Vacant { next_free } = slot.value {
+            self.free_head = next_free;
+        }
+        slot.value = SlotValue::Occupied(value);
+        self.len += 1;
+        SlotKey {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    /// Remove the value addressed by `key` and return it, or `None` if it was already removed.
+    pub fn remove(&mut self, key: SlotKey) -> Option<T> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        let slot = &mut self.slots[key.index];
+        slot.generation = slot.generation.wrapping_add(1);
+        let vacant = SlotValue::Vacant {
+            next_free: self.free_head,
+        };
+        self.free_head = key.index;
+        self.len -= 1;
+        match mem::replace(&mut slot.value, vacant) {
+            SlotValue::Occupied(value) => Some(value),
+            SlotValue::Vacant { .. } => None,
+        }
+    }
+
+    /// Returns `true` if `key` addresses a value in the map.
+    #[inline]
+    pub fn contains_key(&self, key: SlotKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value addressed by `key`, if any.
+    #[inline]
+    pub fn get(&self, key: SlotKey) -> Option<&T> {
+        match self.slots.get(key.index) {
+            Some(Slot {
+                generation,
+                value: SlotValue::Occupied(value),
+            }) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value addressed by `key`, if any.
+    #[inline]
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut T> {
+        match self.slots.get_mut(key.index) {
+            Some(Slot {
+                generation,
+                value: SlotValue::Occupied(value),
+            }) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Remove all values from the map. Keys to the removed values stay invalid.
+    pub fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            let generation = self.slots[index].generation;
+            self.remove(SlotKey { index, generation });
+        }
+    }
+
+    /// An iterator over the keys and values of the map, in slot order.
+    #[inline]
+    pub fn iter(&self) -> SmallSlotMapIter<'_, T> {
+        SmallSlotMapIter {
+            iter: self.slots.iter().enumerate(),
+            len: self.len,
+        }
+    }
+
+    /// An iterator over the keys and mutable values of the map, in slot order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> SmallSlotMapIterMut<'_, T> {
+        SmallSlotMapIterMut {
+            len: self.len,
+            iter: self.slots.iter_mut().enumerate(),
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallSlotMap<$($decl_generics)*>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallSlotMap<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($impl_generics)*> Index<SlotKey> for SmallSlotMap<$($decl_generics)*> {
+    type Output = T;
+
+    /// Panics if `key` doesn't address a value in the map.
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: SlotKey) -> &T {
+        self.get(key).expect("invalid SlotKey")
+    }
+}
+
+impl<$($impl_generics)*> IndexMut<SlotKey> for SmallSlotMap<$($decl_generics)*> {
+    /// Panics if `key` doesn't address a value in the map.
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: SlotKey) -> &mut T {
+        self.get_mut(key).expect("invalid SlotKey")
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a SmallSlotMap<$($decl_generics)*> {
+    type IntoIter = SmallSlotMapIter<'a, T>;
+    type Item = (SlotKey, &'a T);
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($impl_generics)*> IntoIterator for &'a mut SmallSlotMap<$($decl_generics)*> {
+    type IntoIter = SmallSlotMapIterMut<'a, T>;
+    type Item = (SlotKey, &'a mut T);
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], SmallVec<Slot<T>, { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([T, A: Array<Item = Slot<T>>], [T, A], SmallVec<A>);
+
+/// An iterator over the keys and values of a `SmallSlotMap`.
+///
+/// Returned from [`SmallSlotMap::iter`][1].
+///
+/// [1]: struct.SmallSlotMap.html#method.iter
+pub struct SmallSlotMapIter<'a, T> {
+    iter: Enumerate<slice::Iter<'a, Slot<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for SmallSlotMapIter<'a, T> {
+    type Item = (SlotKey, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in &mut self.iter {
+            if let SlotValue::Occupied(ref value) = slot.value {
+                self.len -= 1;
+                let key = SlotKey {
+                    index,
+                    generation: slot.generation,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SmallSlotMapIter<'a, T> {}
+
+/// An iterator over the keys and mutable values of a `SmallSlotMap`.
+///
+/// Returned from [`SmallSlotMap::iter_mut`][1].
+///
+/// [1]: struct.SmallSlotMap.html#method.iter_mut
+pub struct SmallSlotMapIterMut<'a, T> {
+    iter: Enumerate<slice::IterMut<'a, Slot<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for SmallSlotMapIterMut<'a, T> {
+    type Item = (SlotKey, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in &mut self.iter {
+            if let SlotValue::Occupied(ref mut value) = slot.value {
+                self.len -= 1;
+                let key = SlotKey {
+                    index,
+                    generation: slot.generation,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SmallSlotMapIterMut<'a, T> {}
@@ -0,0 +1,416 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+    str::{self, Utf8Error},
+};
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $vec:ty
+) => {
+
+/// A UTF-8 string that stores short contents inline, on top of a byte `SmallVec`.
+///
+/// ```rust
+/// use smallvec::SmallString;
+///
+/// let mut s: SmallString<[u8; 8]> = SmallString::from("héllo");
+/// s.push('!');
+/// assert_eq!(s, "héllo!");
+/// assert!(!s.spilled());
+///
+/// s.push_str(" world");
+/// assert!(s.spilled());
+/// assert_eq!(s.into_string(), "héllo! world");
+/// ```
+pub struct SmallString<$($impl_generics)*> {
+    vec: $vec,
+}
+
+impl<$($impl_generics)*> SmallString<$($decl_generics)*> {
+    /// Construct an empty string
+    #[inline]
+    pub fn new() -> Self {
+        SmallString {
+            vec: SmallVec::new(),
+        }
+    }
+
+    /// Construct an empty string with room for at least `n` bytes.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallString {
+            vec: SmallVec::with_capacity(n),
+        }
+    }
+
+    /// Wrap a byte vector, or give back the error and the vector if it isn't valid UTF-8.
+    #[inline]
+    pub fn from_utf8(vec: $vec) -> Result<Self, ($vec, Utf8Error)> {
+        match str::from_utf8(&vec) {
+            Ok(_) => Ok(SmallString { vec }),
+            Err(error) => Err((vec, error)),
+        }
+    }
+
+    /// Wrap a byte vector without checking that it is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be valid UTF-8.
+    #[inline]
+    pub unsafe fn from_utf8_unchecked(vec: $vec) -> Self {
+        SmallString { vec }
+    }
+
+    /// The length of the string, in bytes
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the string is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// The number of bytes the string can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// The maximum number of bytes this string can hold inline
+    #[inline]
+    pub fn inline_size(&self) -> usize {
+        self.vec.inline_size()
+    }
+
+    /// Returns `true` if the data has spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.vec.spilled()
+    }
+
+    /// Reserve capacity for `additional` more bytes.
+    #[inline]
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Shrink the capacity of the string as much as possible, moving it back inline if it fits.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
+
+    /// Shrink the capacity of the string down to `min_capacity` bytes, or to its length if that's
+    /// larger, moving it back inline if it fits.
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.vec.shrink_to(min_capacity)
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    /// Extracts a mutable string slice containing the entire string.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    /// Returns the bytes of the string.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Append a string slice.
+    #[inline]
+    #[track_caller]
+    pub fn push_str(&mut self, s: &str) {
+        self.vec.extend_from_slice(s.as_bytes())
+    }
+
+    /// Append a character.
+    #[inline]
+    #[track_caller]
+    pub fn push(&mut self, ch: char) {
+        match ch.len_utf8() {
+            1 => self.vec.push(ch as u8),
+            _ => self.vec.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+
+    /// Remove the last character and return it, or `None` if the string is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        self.vec.truncate(new_len);
+        Some(ch)
+    }
+
+    /// Shorten the string to `new_len` bytes. Has no effect if `new_len` is greater than the
+    /// length of the string.
+    ///
+    /// Panics if `new_len` doesn't lie on a `char` boundary.
+    #[inline]
+    #[track_caller]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(
+                self.as_str().is_char_boundary(new_len),
+                "`truncate` length (is {}) is not on a char boundary",
+                new_len
+            );
+            self.vec.truncate(new_len);
+        }
+    }
+
+    /// Remove all the contents of the string.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Convert the string into a `String`, without reallocating if it has already spilled onto
+    /// the heap.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn into_string(self) -> String {
+        unsafe { String::from_utf8_unchecked(self.vec.into_vec()) }
+    }
+
+    /// Convert the string into its byte vector.
+    #[inline]
+    pub fn into_bytes(self) -> $vec {
+        self.vec
+    }
+}
+
+impl<$($impl_generics)*> AsRef<str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<$($impl_generics)*> AsRef<[u8]> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<$($impl_generics)*> Borrow<str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<$($impl_generics)*> BorrowMut<str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl<$($impl_generics)*> Clone for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SmallString {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<$($impl_generics)*> Debug for SmallString<$($decl_generics)*> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<$($impl_generics)*> Default for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($impl_generics)*> Deref for SmallString<$($decl_generics)*> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<$($impl_generics)*> DerefMut for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<$($impl_generics)*> Display for SmallString<$($decl_generics)*> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<$($impl_generics)*> Eq for SmallString<$($decl_generics)*> {}
+
+impl<$($impl_generics)*> Extend<char> for SmallString<$($decl_generics)*> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        self.reserve(iter.size_hint().0);
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a, $($impl_generics)*> Extend<&'a str> for SmallString<$($decl_generics)*> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iterable: I) {
+        for s in iterable {
+            self.push_str(s);
+        }
+    }
+}
+
+impl<'a, $($impl_generics)*> From<&'a str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn from(s: &'a str) -> Self {
+        SmallString {
+            vec: SmallVec::from_slice(s.as_bytes()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($impl_generics)*> From<String> for SmallString<$($decl_generics)*> {
+    /// Take over the buffer of the `String` if it doesn't fit inline.
+    #[inline]
+    fn from(s: String) -> Self {
+        SmallString {
+            vec: SmallVec::from_vec(s.into_bytes()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($impl_generics)*> From<SmallString<$($decl_generics)*>> for String {
+    #[inline]
+    fn from(s: SmallString<$($decl_generics)*>) -> Self {
+        s.into_string()
+    }
+}
+
+impl<$($impl_generics)*> FromIterator<char> for SmallString<$($decl_generics)*> {
+    fn from_iter<I: IntoIterator<Item = char>>(iterable: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iterable);
+        s
+    }
+}
+
+impl<'a, $($impl_generics)*> FromIterator<&'a str> for SmallString<$($decl_generics)*> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iterable: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iterable);
+        s
+    }
+}
+
+impl<$($impl_generics)*> fmt::Write for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.push(ch);
+        Ok(())
+    }
+}
+
+impl<$($impl_generics)*> Hash for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<$($impl_generics)*> Ord for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<$($impl_generics)*> PartialEq for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<$($impl_generics)*> PartialEq<str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, $($impl_generics)*> PartialEq<&'a str> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($impl_generics)*> PartialEq<String> for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<$($impl_generics)*> PartialOrd for SmallString<$($decl_generics)*> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [{ N }], SmallVec<u8, { N }>);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A], SmallVec<A>);
@@ -1,29 +1,59 @@
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use crate::allocator::Allocator;
+#[cfg(feature = "specialization")]
+use crate::spec_extend::{SpecExtend, SpecExtendWith, SpecFill};
 #[cfg(feature = "specialization")]
 use crate::spec_from::SpecFrom;
-use crate::utils::deallocate;
+use crate::utils::{
+    allocate, index_out_of_bounds, infallible, insertion_index_out_of_bounds, slice_range,
+};
 #[cfg(not(feature = "const_generics"))]
 use crate::Array;
 use crate::{
-    set_len_on_drop::SetLenOnDrop, small_vec_data::SmallVecData, Drain, ExtendFromSlice, IntoIter,
+    allocator::{DeallocOnDrop, Global, RawAlloc},
+    insert_gap::InsertGap,
+    set_len_on_drop::SetLenOnDrop,
+    small_vec_data::{NonMaxUsize, SmallVecData},
+    CollectionAllocErr, CursorMut, Drain, ExtendFromSlice, ExtractIf, IntoIter, SortableFloat,
 };
-use alloc::{vec, vec::Vec};
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
+#[cfg(feature = "const_generics")]
+use core::convert::TryFrom;
+#[cfg(feature = "specialization")]
+use core::iter::TrustedLen;
 use core::{
     borrow::{Borrow, BorrowMut},
-    cmp::{Eq, Ord, Ordering, PartialOrd},
+    cmp::{self, Eq, Ord, Ordering, PartialOrd},
     fmt::{self, Debug},
     hash::{Hash, Hasher},
     hint::unreachable_unchecked,
-    iter::{repeat, FromIterator},
+    iter::{repeat_with, FromIterator},
+    marker::PhantomData,
     mem::{self, MaybeUninit},
-    ops::{Deref, DerefMut, Index, IndexMut, Range, RangeFrom, RangeFull, RangeTo},
+    ops::{Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull, RangeTo},
     ptr, slice,
 };
 #[cfg(feature = "std")]
 use std::io;
+#[cfg(feature = "alloc")]
+use {
+    crate::{
+        utils::{capacity_overflow, reallocate},
+        Splice, VecGuard,
+    },
+    alloc::{
+        alloc::{alloc, handle_alloc_error},
+        borrow::Cow,
+        boxed::Box,
+        rc::Rc,
+        vec::Vec,
+    },
+    core::alloc::Layout,
+};
 #[cfg(feature = "serde")]
 use {
-    crate::small_vec_visitor::SmallVecVisitor,
-    core::marker::PhantomData,
+    crate::{AppendSeed, SmallVecVisitor},
     serde::{
         de::{Deserialize, Deserializer},
         ser::{Serialize, SerializeSeq, Serializer},
@@ -34,6 +64,8 @@ macro_rules! create_with_parts {
 (
     <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
     <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    [$($alloc:ident = $alloc_default:ident)?],
+    $alloc_ty:ty,
     $array:ty,
     $array_item:ty,
     $array_size:expr
@@ -65,21 +97,91 @@ macro_rules! create_with_parts {
 /// assert_eq!(v.len(), 5);
 /// assert!(v.spilled());
 /// ```
-pub struct SmallVec<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
-    // The capacity field is used to determine which of the storage variants is active:
+pub struct SmallVec<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator = $alloc_default)?> {
+    // The capacity field is used to determine which of the storage variants is active, and is
+    // stored as a `NonMaxUsize` so that `Option<SmallVec>` can use its niche:
     // If capacity <= $array_size then the inline variant is used and capacity holds the current length of the vector (number of elements actually in use).
     // If capacity > $array_size then the heap variant is used and capacity holds the size of the memory allocation.
-    capacity: usize,
+    capacity: NonMaxUsize,
     data: SmallVecData<$s_decl_ty$(, {$s_decl_const_ty})?>,
+    alloc: $alloc_ty,
+    // Neither storage variant owns its elements as far as the drop checker can tell, so with
+    // `may_dangle` this is what makes it check that dropping the vector may drop them.
+    phantom: PhantomData<$array_item>,
 }
 
 impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
     /// Construct an empty vector
+    #[cfg(not(feature = "const_new"))]
     #[inline]
     pub fn new() -> Self {
         SmallVec {
-            capacity: 0,
+            capacity: NonMaxUsize::new(0),
+            data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit()),
+            alloc: Global,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Construct an empty vector
+    #[cfg(feature = "const_new")]
+    #[inline]
+    pub const fn new() -> Self {
+        SmallVec {
+            capacity: NonMaxUsize::new(0),
             data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit()),
+            alloc: Global,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a full vector from an array of its inline size, in const contexts too.
+    ///
+    /// Together with `new`, this allows putting vectors in `static`s and `const`s. See also
+    /// [`smallvec_inline!`](crate::smallvec_inline).
+    ///
+    /// ```rust,ignore
+    /// use smallvec::SmallVec;
+    ///
+    /// static DEFAULTS: SmallVec<u8, 4> = SmallVec::from_const([1, 2, 3, 4]);
+    /// assert_eq!(&DEFAULTS[..], &[1, 2, 3, 4]);
+    /// ```
+    #[cfg(feature = "const_new")]
+    #[inline]
+    pub const fn from_const(items: $array) -> Self {
+        SmallVec {
+            capacity: NonMaxUsize::new($array_size),
+            data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::new(items)),
+            alloc: Global,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Construct an empty vector directly on the heap.
+    ///
+    /// `Box::new(SmallVec::new())` may build the vector on the stack before moving it into the
+    /// box, which copies the whole inline buffer and can overflow the stack when the inline
+    /// capacity is large. This only initializes the length of the vector in the new allocation.
+    ///
+    /// ```
+    /// # use smallvec::SmallVec;
+    ///
+    /// let mut v: Box<SmallVec<[u8; 1 << 20]>> = SmallVec::new_boxed();
+    /// v.extend_from_slice(b"abc");
+    /// assert_eq!(&v[..], b"abc");
+    /// assert!(!v.spilled());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn new_boxed() -> Box<Self> {
+        let layout = Layout::new::<Self>();
+        unsafe {
+            let ptr = alloc(layout) as *mut Self;
+            if ptr.is_null() {
+                handle_alloc_error(layout);
+            }
+            ptr::addr_of_mut!((*ptr).capacity).write(NonMaxUsize::new(0));
+            ptr::addr_of_mut!((*ptr).data).write(SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit()));
+            Box::from_raw(ptr)
         }
     }
 
@@ -97,10 +199,38 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// assert!(v.capacity() >= 100);
     /// ```
     #[inline]
+    #[track_caller]
     pub fn with_capacity(n: usize) -> Self {
+        // Build the result in place rather than growing a local `new()`, which would copy the
+        // whole (uninitialized) inline buffer into the return slot.
+        if n <= $array_size {
+            return Self::new();
+        }
+        SmallVec {
+            capacity: NonMaxUsize::new(n),
+            data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(infallible(allocate(n)), 0),
+            alloc: Global,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Construct an empty vector with enough capacity pre-allocated to store at least `n`
+    /// elements, returning an error instead of aborting if the allocation fails.
+    ///
+    /// ```
+    /// # use smallvec::{CollectionAllocErr, SmallVec};
+    ///
+    /// let v: SmallVec<[u8; 3]> = SmallVec::try_with_capacity(100).unwrap();
+    /// assert!(v.capacity() >= 100);
+    ///
+    /// let err = SmallVec::<[u64; 3]>::try_with_capacity(usize::MAX);
+    /// assert!(matches!(err, Err(CollectionAllocErr::CapacityOverflow)));
+    /// ```
+    #[inline]
+    pub fn try_with_capacity(n: usize) -> Result<Self, CollectionAllocErr> {
         let mut v = Self::new();
-        v.reserve_exact(n);
-        v
+        v.try_grow(n)?;
+        Ok(v)
     }
 
     /// Construct a new `SmallVec` from a `Vec<$array_item>`.
@@ -115,6 +245,7 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     ///
     /// assert_eq!(&*small_vec, &[1, 2, 3, 4, 5]);
     /// ```
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn from_vec(mut vec: Vec<$array_item>) -> Self {
         if vec.capacity() <= $array_size {
@@ -125,18 +256,78 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
                 ptr::copy_nonoverlapping(vec.as_ptr(), data.inline_mut(), len);
 
                 SmallVec {
-                    capacity: len,
+                    capacity: NonMaxUsize::new(len),
                     data,
+                    alloc: Global,
+                    phantom: PhantomData,
                 }
             }
         } else {
             let (ptr, cap, len) = (vec.as_mut_ptr(), vec.capacity(), vec.len());
+            // Vectors of zero-sized types report a capacity of `usize::MAX` but own no buffer.
+            if len > NonMaxUsize::MAX || (cap > NonMaxUsize::MAX && mem::size_of::<$array_item>() != 0) {
+                capacity_overflow();
+            }
             mem::forget(vec);
 
             SmallVec {
-                capacity: cap,
+                capacity: NonMaxUsize::new(cap),
                 data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, len),
+                alloc: Global,
+                phantom: PhantomData,
+            }
+        }
+    }
+
+    /// Construct a new `SmallVec` from a `Vec<$array_item>` without keeping any unused capacity.
+    ///
+    /// Unlike `from_vec`, the elements are moved to the inline buffer whenever they fit in it,
+    /// and otherwise the heap buffer is shrunk to the vector's length. Returns an error if
+    /// shrinking the allocation fails.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut vec = Vec::with_capacity(100);
+    /// vec.extend_from_slice(&[1, 2, 3]);
+    /// let small_vec: SmallVec<[_; 3]> = SmallVec::try_from_vec_compact(vec).unwrap();
+    ///
+    /// assert!(!small_vec.spilled());
+    /// assert_eq!(&*small_vec, &[1, 2, 3]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn try_from_vec_compact(mut vec: Vec<$array_item>) -> Result<Self, CollectionAllocErr> {
+        let len = vec.len();
+        if len <= $array_size {
+            unsafe {
+                let mut data = SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit());
+                vec.set_len(0);
+                ptr::copy_nonoverlapping(vec.as_ptr(), data.inline_mut(), len);
+                Ok(SmallVec {
+                    capacity: NonMaxUsize::new(len),
+                    data,
+                    alloc: Global,
+                    phantom: PhantomData,
+                })
             }
+        } else {
+            if len > NonMaxUsize::MAX {
+                return Err(CollectionAllocErr::CapacityOverflow);
+            }
+            let (ptr, cap) = (vec.as_mut_ptr(), vec.capacity());
+            // Vectors of zero-sized types report a capacity of `usize::MAX` but own no buffer.
+            let ptr = if cap > len && mem::size_of::<$array_item>() != 0 {
+                unsafe { reallocate(ptr, cap, len)? }
+            } else {
+                ptr
+            };
+            mem::forget(vec);
+            Ok(SmallVec {
+                capacity: NonMaxUsize::new(len),
+                data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, len),
+                alloc: Global,
+                phantom: PhantomData,
+            })
         }
     }
 
@@ -154,8 +345,10 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     #[inline]
     pub fn from_buf(buf: $array) -> Self {
         SmallVec {
-            capacity: $array_size,
+            capacity: NonMaxUsize::new($array_size),
             data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::new(buf)),
+            alloc: Global,
+            phantom: PhantomData,
         }
     }
 
@@ -172,8 +365,11 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// assert_eq!(&*small_vec, &[1, 2, 3, 4, 5]);
     /// ```
     #[inline]
+    #[track_caller]
     pub fn from_buf_and_len(buf: $array, len: usize) -> Self {
-        assert!(len <= $array_size);
+        if len > $array_size {
+            insertion_index_out_of_bounds("from_buf_and_len", len, $array_size);
+        }
         unsafe { Self::from_buf_and_len_unchecked(buf, len) }
     }
 
@@ -181,6 +377,10 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// copying elements. Also sets the length. The user is responsible
     /// for ensuring that `len <= $array_size`.
     ///
+    /// # Safety
+    ///
+    /// `len` must be less or equal to the inline capacity.
+    ///
     /// ```rust
     /// use smallvec::SmallVec;
     ///
@@ -194,8 +394,65 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     #[inline]
     pub unsafe fn from_buf_and_len_unchecked(buf: $array, len: usize) -> Self {
         SmallVec {
-            capacity: len,
+            capacity: NonMaxUsize::new(len),
             data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::new(buf)),
+            alloc: Global,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?> {
+    /// Construct an empty vector whose spilled storage is allocated with `alloc`.
+    ///
+    /// Conversions from and into `Vec`, and the iterators that borrow the vector other than
+    /// `drain`, are only available with the global allocator.
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[inline]
+    pub fn new_in(alloc: $alloc_ty) -> Self {
+        Self::empty_in(alloc)
+    }
+
+    /// Construct an empty vector with enough capacity pre-allocated to store at least `n`
+    /// elements, allocated with `alloc` if it doesn't fit inline.
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity_in(n: usize, alloc: $alloc_ty) -> Self {
+        infallible(Self::try_with_capacity_in(n, alloc))
+    }
+
+    /// Construct an empty vector with enough capacity pre-allocated to store at least `n`
+    /// elements, allocated with `alloc` if it doesn't fit inline, returning an error instead of
+    /// aborting if the allocation fails.
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    pub fn try_with_capacity_in(n: usize, alloc: $alloc_ty) -> Result<Self, CollectionAllocErr> {
+        if n <= $array_size {
+            return Ok(Self::empty_in(alloc));
+        }
+        let ptr = alloc.allocate_array(n)?;
+        Ok(SmallVec {
+            capacity: NonMaxUsize::new(n),
+            data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, 0),
+            alloc,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns a reference to the allocator of the spilled storage.
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[inline]
+    pub fn allocator(&self) -> &$alloc_ty {
+        &self.alloc
+    }
+
+    #[inline]
+    fn empty_in(alloc: $alloc_ty) -> Self {
+        SmallVec {
+            capacity: NonMaxUsize::new(0),
+            data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit()),
+            alloc,
+            phantom: PhantomData,
         }
     }
 
@@ -204,9 +461,98 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// This will explicitly set the size of the vector, without actually
     /// modifying its buffers, so it is up to the caller to ensure that the
     /// vector is actually the specified size.
+    ///
+    /// This works the same whether the vector is inline or spilled, and never moves the
+    /// elements between the two: use it after writing through [`as_mut_ptr`](Self::as_mut_ptr)
+    /// to adopt the elements that were written.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less or equal to `capacity()` and the elements at
+    /// `old_len..new_len` must be initialized.
     pub unsafe fn set_len(&mut self, new_len: usize) {
-        let (_, len_ptr, _) = self.triple_mut();
-        *len_ptr = new_len;
+        let (_, len_ptr, cap) = self.triple_mut();
+        debug_assert!(new_len <= cap);
+        len_ptr.set(new_len);
+    }
+
+    /// Returns a raw pointer to the buffer of the vector.
+    ///
+    /// The pointer is valid for reads of `len()` elements. It points into the vector itself
+    /// while it is inline, so it is invalidated by moving the vector, and into the heap buffer
+    /// once it has spilled, in which case it stays valid until the vector reallocates or is
+    /// dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *const $array_item {
+        self.triple().0
+    }
+
+    /// Returns a raw mutable pointer to the buffer of the vector.
+    ///
+    /// Unlike going through the slice, the pointer is valid for writes of `capacity()`
+    /// elements, so it can be handed to code that fills the spare capacity before calling
+    /// [`set_len`](Self::set_len). It is invalidated in the same cases as
+    /// [`as_ptr`](Self::as_ptr), and by any call that may reallocate.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// // Stands for a C function filling at most `cap` bytes and returning how many it wrote.
+    /// unsafe fn fill(buf: *mut u8, cap: usize) -> usize {
+    ///     let n = cap.min(3);
+    ///     core::ptr::write_bytes(buf, 7, n);
+    ///     n
+    /// }
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::new();
+    /// unsafe {
+    ///     let n = fill(v.as_mut_ptr(), v.capacity());
+    ///     v.set_len(n);
+    /// }
+    /// assert_eq!(&v[..], &[7, 7, 7]);
+    /// assert!(!v.spilled());
+    /// ```
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut $array_item {
+        self.triple_mut().0
+    }
+
+    /// Returns the remaining spare capacity of the vector as a slice of `MaybeUninit<T>`.
+    ///
+    /// The elements written there become part of the vector once [`set_len`](Self::set_len)
+    /// is called.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::from_slice(&[1]);
+    /// let spare = v.spare_capacity_mut();
+    /// spare[0].write(2);
+    /// spare[1].write(3);
+    /// unsafe { v.set_len(3) };
+    /// assert_eq!(&v[..], &[1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<$array_item>] {
+        self.split_at_spare_mut().1
+    }
+
+    /// Returns the contents of the vector, and its remaining spare capacity as a slice of
+    /// `MaybeUninit<T>`.
+    ///
+    /// This allows filling the spare capacity from the current elements without going through
+    /// raw pointers.
+    #[inline]
+    pub fn split_at_spare_mut(&mut self) -> (&mut [$array_item], &mut [MaybeUninit<$array_item>]) {
+        unsafe {
+            let (ptr, len, cap) = self.triple_mut();
+            let len = len.get();
+            let spare = ptr.add(len) as *mut MaybeUninit<$array_item>;
+            (
+                slice::from_raw_parts_mut(ptr, len),
+                slice::from_raw_parts_mut(spare, cap - len),
+            )
+        }
     }
 
     /// The maximum number of elements this vector can hold inline
@@ -240,20 +586,20 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
         unsafe {
             if self.spilled() {
                 let (ptr, len) = self.data.heap();
-                (ptr, len, self.capacity)
+                (ptr, len, self.capacity.get())
             } else {
-                (self.data.inline(), self.capacity, $array_size)
+                (self.data.inline(), self.capacity.get(), $array_size)
             }
         }
     }
 
     /// Returns a tuple with (data ptr, len ptr, capacity)
     #[inline]
-    fn triple_mut(&mut self) -> (*mut $array_item, &mut usize, usize) {
+    pub(crate) fn triple_mut(&mut self) -> (*mut $array_item, &mut NonMaxUsize, usize) {
         unsafe {
             if self.spilled() {
                 let (ptr, len_ptr) = self.data.heap_mut();
-                (ptr, len_ptr, self.capacity)
+                (ptr, len_ptr, self.capacity.get())
             } else {
                 (
                     self.data.inline_mut(),
@@ -267,37 +613,186 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// Returns `true` if the data has spilled into a separate heap-allocated buffer.
     #[inline]
     pub fn spilled(&self) -> bool {
-        self.capacity > $array_size
+        self.capacity.get() > $array_size
     }
 
-    /// Empty the vector and return an iterator over its former contents.
-    pub fn drain(&mut self) -> Drain<$array_item> {
+    /// Append the items of `iter`, growing by its lower size bound whenever the vector is full.
+    fn extend_desugared<I: Iterator<Item = $array_item>>(&mut self, mut iter: I) {
+        let (lower_size_bound, _) = iter.size_hint();
+        self.reserve(lower_size_bound);
+
         unsafe {
-            let ptr = self.as_mut_ptr();
+            let (ptr, len_ptr, cap) = self.triple_mut();
+            let mut len = SetLenOnDrop::new(len_ptr);
+            while len.get() < cap {
+                if let Some(out) = iter.next() {
+                    ptr::write(ptr.add(len.get()), out);
+                    len.increment_len(1);
+                } else {
+                    return;
+                }
+            }
+        }
 
-            let current_len = self.len();
-            self.set_len(0);
+        while let Some(elem) = iter.next() {
+            let (_, len, cap) = self.triple_mut();
+            let len = len.get();
+            if len == cap {
+                // Grow for everything the iterator still promises, not one element at a time.
+                self.reserve(iter.size_hint().0.saturating_add(1));
+            }
+            self.push(elem);
+        }
+    }
+
+    /// Append the items of an iterator whose upper size bound is exact, reserving once and
+    /// writing them without checking the capacity.
+    #[cfg(feature = "specialization")]
+    fn extend_trusted<I: TrustedLen<Item = $array_item>>(&mut self, iter: I) {
+        let additional = match iter.size_hint() {
+            (_, Some(additional)) => additional,
+            // More than `usize::MAX` items
+            (_, None) => infallible(Err(CollectionAllocErr::CapacityOverflow)),
+        };
+        self.reserve(additional);
+        unsafe {
+            let (ptr, len_ptr, _) = self.triple_mut();
+            let mut len = SetLenOnDrop::new(len_ptr);
+            iter.for_each(|item| {
+                ptr::write(ptr.add(len.get()), item);
+                len.increment_len(1);
+            });
+        }
+    }
 
-            let slice = slice::from_raw_parts_mut(ptr, current_len);
+    /// Move `count` items starting at `src` to the end of the vector.
+    ///
+    /// The items must not be used afterwards, their ownership passes to the vector.
+    #[cfg(feature = "specialization")]
+    unsafe fn append_raw(&mut self, src: *const $array_item, count: usize) {
+        self.reserve(count);
+        let (ptr, len_ptr, _) = self.triple_mut();
+        let len = len_ptr.get();
+        ptr::copy_nonoverlapping(src, ptr.add(len), count);
+        len_ptr.set(len + count);
+    }
 
+    /// Remove the elements in `range` and return an iterator over them.
+    ///
+    /// The elements after the range are moved back in place when the iterator is dropped, and
+    /// the elements of the range that weren't consumed are dropped then too. If the iterator is
+    /// leaked instead, the vector is left with the elements before the range.
+    ///
+    /// Panics if the start of the range is greater than its end, or if its end is greater than
+    /// the length of the vector.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2, 3, 4, 5]);
+    /// let drained: Vec<u8> = v.drain(1..3).collect();
+    /// assert_eq!(drained, [2, 3]);
+    /// assert_eq!(&*v, &[1, 4, 5]);
+    ///
+    /// v.drain(..);
+    /// assert!(v.is_empty());
+    /// ```
+    #[track_caller]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, $array_item> {
+        let len = self.len();
+        let Range { start, end } = slice_range(range, len);
+        unsafe {
+            let (ptr, len_ptr, _) = self.triple_mut();
+            len_ptr.set(start);
             Drain {
-                iter: slice.iter_mut(),
+                iter: slice::from_raw_parts_mut(ptr.add(start), end - start).iter_mut(),
+                len: len_ptr,
+                ptr,
+                tail_start: end,
+                tail_len: len - end,
             }
         }
     }
 
     /// Append an item to the vector.
     #[inline]
+    #[track_caller]
     pub fn push(&mut self, value: $array_item) {
+        infallible(self.try_push(value))
+    }
+
+    /// Append an item to the vector, returning an error instead of panicking or aborting if
+    /// the vector needs to grow and the allocation fails. The item is dropped on failure.
+    #[inline]
+    pub fn try_push(&mut self, value: $array_item) -> Result<(), CollectionAllocErr> {
         unsafe {
-            let (_, &mut len, cap) = self.triple_mut();
+            let (_, len, cap) = self.triple_mut();
+            let len = len.get();
             if len == cap {
-                self.reserve(1);
+                self.try_reserve(1)?;
             }
             let (ptr, len_ptr, _) = self.triple_mut();
-            *len_ptr = len + 1;
+            len_ptr.set(len + 1);
+            ptr::write(ptr.add(len), value);
+        }
+        Ok(())
+    }
+
+    /// Append an item to the vector if there is spare capacity, or give it back as `Err` instead
+    /// of growing.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 2]> = SmallVec::new();
+    /// assert_eq!(v.push_within_capacity(1), Ok(()));
+    /// assert_eq!(v.push_within_capacity(2), Ok(()));
+    /// assert_eq!(v.push_within_capacity(3), Err(3));
+    /// assert!(!v.spilled());
+    /// ```
+    #[inline]
+    pub fn push_within_capacity(&mut self, value: $array_item) -> Result<(), $array_item> {
+        unsafe {
+            let (ptr, len_ptr, cap) = self.triple_mut();
+            let len = len_ptr.get();
+            if len == cap {
+                return Err(value);
+            }
+            len_ptr.set(len + 1);
             ptr::write(ptr.add(len), value);
         }
+        Ok(())
+    }
+
+    /// Move all the elements of `other` to the end of the vector, leaving `other` empty.
+    ///
+    /// The elements are moved with a single copy after reserving room for them. When the vector
+    /// is empty and `other` has spilled, the two vectors are swapped instead, so that the
+    /// vector takes over the heap buffer of `other` without copying anything.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2]);
+    /// let mut other: SmallVec<[u8; 4]> = SmallVec::from_slice(&[3, 4, 5]);
+    /// v.append(&mut other);
+    /// assert_eq!(&v[..], &[1, 2, 3, 4, 5]);
+    /// assert!(other.is_empty());
+    /// ```
+    #[track_caller]
+    pub fn append(&mut self, other: &mut Self) {
+        if self.is_empty() && other.spilled() {
+            mem::swap(self, other);
+            return;
+        }
+        let count = other.len();
+        self.reserve(count);
+        unsafe {
+            let len = self.len();
+            other.set_len(0);
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(len), count);
+            self.set_len(len + count);
+        }
     }
 
     /// Remove an item from the end of the vector and return it, or None if empty.
@@ -305,44 +800,75 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     pub fn pop(&mut self) -> Option<$array_item> {
         unsafe {
             let (ptr, len_ptr, _) = self.triple_mut();
-            if *len_ptr == 0 {
+            if len_ptr.get() == 0 {
                 return None;
             }
-            let last_index = *len_ptr - 1;
-            *len_ptr = last_index;
+            let last_index = len_ptr.get() - 1;
+            len_ptr.set(last_index);
             Some(ptr::read(ptr.add(last_index)))
         }
     }
 
+    /// Remove the last item of the vector and return it if `predicate` returns `true` for it, or
+    /// return `None` and leave the vector unchanged otherwise.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut scopes: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2]);
+    /// assert_eq!(scopes.pop_if(|scope| *scope == 1), None);
+    /// assert_eq!(scopes.pop_if(|scope| *scope == 2), Some(2));
+    /// assert_eq!(&*scopes, &[1]);
+    /// ```
+    #[inline]
+    pub fn pop_if<F: FnOnce(&mut $array_item) -> bool>(&mut self, predicate: F) -> Option<$array_item> {
+        let last = self.last_mut()?;
+        if predicate(last) {
+            self.pop()
+        } else {
+            None
+        }
+    }
+
     /// Re-allocate to set the capacity to `max(new_cap, inline_size())`.
     ///
     /// Panics if `new_cap` is less than the vector's length.
+    #[track_caller]
     pub fn grow(&mut self, new_cap: usize) {
+        infallible(self.try_grow(new_cap))
+    }
+
+    /// Re-allocate to set the capacity to `max(new_cap, inline_size())`, returning an error
+    /// instead of aborting if the allocation fails.
+    ///
+    /// Panics if `new_cap` is less than the vector's length.
+    #[track_caller]
+    pub fn try_grow(&mut self, new_cap: usize) -> Result<(), CollectionAllocErr> {
         unsafe {
-            let (ptr, &mut len, cap) = self.triple_mut();
+            let (ptr, len, cap) = self.triple_mut();
+            let len = len.get();
             let unspilled = !self.spilled();
-            assert!(new_cap >= len);
+            assert!(new_cap >= len, "`grow` capacity (is {}) should be >= len (is {})", new_cap, len);
             if new_cap <= self.inline_size() {
                 if unspilled {
-                    return;
+                    return Ok(());
                 }
                 self.data = SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit());
                 ptr::copy_nonoverlapping(ptr, self.data.inline_mut(), len);
-                self.capacity = len;
+                self.capacity = NonMaxUsize::new(len);
             } else if new_cap != cap {
-                let mut vec = Vec::with_capacity(new_cap);
-                let new_alloc = vec.as_mut_ptr();
-                mem::forget(vec);
+                let new_alloc = self.alloc.allocate_array(new_cap)?;
                 ptr::copy_nonoverlapping(ptr, new_alloc, len);
                 self.data = SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(new_alloc, len);
-                self.capacity = new_cap;
+                self.capacity = NonMaxUsize::new(new_cap);
                 if unspilled {
-                    return;
+                    return Ok(());
                 }
             } else {
-                return;
+                return Ok(());
             }
-            deallocate(ptr, cap);
+            self.alloc.deallocate_array(ptr, cap);
+            Ok(())
         }
     }
 
@@ -350,44 +876,112 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     ///
     /// May reserve more space to avoid frequent reallocations.
     ///
-    /// If the new capacity would overflow `usize` then it will be set to `usize::max_value()`
-    /// instead. (This means that inserting `additional` new elements is not guaranteed to be
-    /// possible after calling this function.)
+    /// Panics if the new capacity overflows `usize`.
     #[inline]
+    #[track_caller]
     pub fn reserve(&mut self, additional: usize) {
+        infallible(self.try_reserve(additional))
+    }
+
+    /// Reserve capacity for `additional` more elements to be inserted, returning an error
+    /// instead of panicking or aborting if the capacity overflows or the allocation fails.
+    ///
+    /// May reserve more space to avoid frequent reallocations.
+    ///
+    /// ```rust
+    /// use smallvec::{CollectionAllocErr, SmallVec};
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::new();
+    /// v.try_reserve(10).unwrap();
+    /// assert!(v.capacity() >= 10);
+    ///
+    /// let err = v.try_reserve(usize::MAX);
+    /// assert!(matches!(err, Err(CollectionAllocErr::CapacityOverflow)));
+    /// ```
+    #[inline]
+    #[allow(clippy::legacy_numeric_constants)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
         // prefer triple_mut() even if triple() would work
         // so that the optimizer removes duplicated calls to it
         // from callers like insert()
-        let (_, &mut len, cap) = self.triple_mut();
+        let (_, len, cap) = self.triple_mut();
+        let len = len.get();
         if cap - len < additional {
-            let new_cap = len
+            let min_cap = len
                 .checked_add(additional)
-                .and_then(usize::checked_next_power_of_two)
-                .unwrap_or(usize::max_value());
-            self.grow(new_cap);
+                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+            // Rounding up to a power of two must not overflow a capacity that fits on its own.
+            let new_cap = cmp::min(
+                min_cap.checked_next_power_of_two().unwrap_or(usize::max_value()),
+                cmp::max(min_cap, NonMaxUsize::MAX),
+            );
+            self.try_grow(new_cap)?;
         }
+        Ok(())
     }
 
     /// Reserve the minimum capacity for `additional` more elements to be inserted.
     ///
     /// Panics if the new capacity overflows `usize`.
+    #[track_caller]
     pub fn reserve_exact(&mut self, additional: usize) {
-        let (_, &mut len, cap) = self.triple_mut();
-        if cap - len < additional {
-            match len.checked_add(additional) {
-                Some(cap) => self.grow(cap),
-                None => panic!("reserve_exact overflow"),
-            }
-        }
+        infallible(self.try_reserve_exact(additional))
     }
 
-    /// Shrink the capacity of the vector as much as possible.
+    /// Reserve the minimum capacity for `additional` more elements to be inserted, returning an
+    /// error instead of panicking or aborting if the capacity overflows or the allocation fails.
     ///
-    /// When possible, this will move data from an external heap buffer to the vector's inline
-    /// storage.
-    pub fn shrink_to_fit(&mut self) {
-        if !self.spilled() {
-            return;
+    /// ```rust
+    /// use smallvec::{CollectionAllocErr, SmallVec};
+    ///
+    /// let mut v: SmallVec<[u64; 4]> = SmallVec::new();
+    /// v.try_reserve_exact(10).unwrap();
+    /// assert_eq!(v.capacity(), 10);
+    ///
+    /// let err = v.try_reserve_exact(usize::MAX / 8);
+    /// assert!(matches!(err, Err(CollectionAllocErr::CapacityOverflow)));
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        let (_, len, cap) = self.triple_mut();
+        let len = len.get();
+        if cap - len < additional {
+            let new_cap = len
+                .checked_add(additional)
+                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+            self.try_grow(new_cap)?;
+        }
+        Ok(())
+    }
+
+    /// Return whether the vector has room for `additional` more elements without moving the
+    /// elements.
+    ///
+    /// Neither `realloc` nor the `Allocator` traits can be told not to move a heap allocation, so
+    /// this never reallocates: it only checks the spare capacity, inline or spilled, and pointers
+    /// into the vector stay valid whatever the result. Use `reserve` when the elements may move.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2]);
+    /// let ptr = v.as_ptr();
+    /// assert!(v.reserve_in_place(2));
+    /// assert!(!v.reserve_in_place(3));
+    /// assert!(!v.spilled() && v.as_ptr() == ptr);
+    /// ```
+    #[inline]
+    pub fn reserve_in_place(&mut self, additional: usize) -> bool {
+        let (_, len, cap) = self.triple();
+        cap - len >= additional
+    }
+
+    /// Shrink the capacity of the vector as much as possible.
+    ///
+    /// When possible, this will move data from an external heap buffer to the vector's inline
+    /// storage.
+    pub fn shrink_to_fit(&mut self) {
+        if !self.spilled() {
+            return;
         }
         let len = self.len();
         if self.inline_size() >= len {
@@ -395,14 +989,43 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
                 let (ptr, len) = self.data.heap();
                 self.data = SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(MaybeUninit::uninit());
                 ptr::copy_nonoverlapping(ptr, self.data.inline_mut(), len);
-                deallocate(ptr, self.capacity);
-                self.capacity = len;
+                self.alloc.deallocate_array(ptr, self.capacity.get());
+                self.capacity = NonMaxUsize::new(len);
             }
         } else if self.capacity() > len {
             self.grow(len);
         }
     }
 
+    /// Shrink the capacity of the vector with a lower bound.
+    ///
+    /// The capacity stays at least as large as both the length and `min_capacity`. When both fit
+    /// in the inline storage, the data is moved back into it like with `shrink_to_fit`. This has
+    /// no effect if the capacity is already smaller than `min_capacity`.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::with_capacity(100);
+    /// v.extend_from_slice(&[1, 2, 3, 4, 5]);
+    /// v.shrink_to(10);
+    /// assert_eq!(v.capacity(), 10);
+    /// v.truncate(3);
+    /// v.shrink_to(2);
+    /// assert!(!v.spilled());
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if !self.spilled() {
+            return;
+        }
+        let new_cap = self.len().max(min_capacity);
+        if new_cap <= self.inline_size() {
+            self.shrink_to_fit();
+        } else if self.capacity() > new_cap {
+            self.grow(new_cap);
+        }
+    }
+
     /// Shorten the vector, keeping the first `len` elements and dropping the rest.
     ///
     /// If `len` is greater than or equal to the vector's current length, this has no
@@ -413,14 +1036,113 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     pub fn truncate(&mut self, len: usize) {
         unsafe {
             let (ptr, len_ptr, _) = self.triple_mut();
-            while len < *len_ptr {
-                let last_index = *len_ptr - 1;
-                *len_ptr = last_index;
+            while len < len_ptr.get() {
+                let last_index = len_ptr.get() - 1;
+                len_ptr.set(last_index);
                 ptr::drop_in_place(ptr.add(last_index));
             }
         }
     }
 
+    /// Drop elements from the end of the vector for as long as `f` returns `true` for them, and
+    /// return how many were removed.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::from_slice(b"abc\0\0");
+    /// assert_eq!(v.truncate_while(|&b| b == 0), 2);
+    /// assert_eq!(&*v, b"abc");
+    /// ```
+    pub fn truncate_while<F: FnMut(&$array_item) -> bool>(&mut self, mut f: F) -> usize {
+        let len = self.len();
+        let removed = self.iter().rev().take_while(|x| f(x)).count();
+        self.truncate(len - removed);
+        removed
+    }
+
+    /// Drop elements from the front of the vector for as long as `f` returns `true` for them,
+    /// shifting the remaining elements to the left, and return how many were removed.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::from_slice(b"  abc");
+    /// assert_eq!(v.truncate_front_while(|&b| b == b' '), 2);
+    /// assert_eq!(&*v, b"abc");
+    /// ```
+    pub fn truncate_front_while<F: FnMut(&$array_item) -> bool>(&mut self, mut f: F) -> usize {
+        let len = self.len();
+        let removed = self.iter().take_while(|x| f(x)).count();
+        if removed > 0 {
+            unsafe {
+                let ptr = self.as_mut_ptr();
+                // If a destructor panics, leak the remaining elements rather than double-drop.
+                self.set_len(0);
+                ptr::drop_in_place(slice::from_raw_parts_mut(ptr, removed));
+                ptr::copy(ptr.add(removed), ptr, len - removed);
+                self.set_len(len - removed);
+            }
+        }
+        removed
+    }
+
+    /// Resizes the vector in-place so that `len()` is equal to `new_len`, filling new slots with
+    /// the values returned by calling `f`.
+    ///
+    /// If `new_len` is less than the current length, the vector is simply truncated.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[Box<u32>; 4]> = SmallVec::new();
+    /// let mut next = 0;
+    /// v.resize_with(3, || {
+    ///     next += 1;
+    ///     Box::new(next)
+    /// });
+    /// assert_eq!(v.iter().map(|b| **b).collect::<Vec<_>>(), [1, 2, 3]);
+    /// v.resize_with(1, || unreachable!());
+    /// assert_eq!(*v[0], 1);
+    /// ```
+    pub fn resize_with<F: FnMut() -> $array_item>(&mut self, new_len: usize, f: F) {
+        let len = self.len();
+        if new_len > len {
+            self.extend(repeat_with(f).take(new_len - len));
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, first appending elements produced
+    /// by `f` until the vector is long enough to have one there.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut counts: SmallVec<[u32; 4]> = SmallVec::new();
+    /// for &id in &[2, 0, 2] {
+    ///     *counts.get_mut_or_grow_with(id, || 0) += 1;
+    /// }
+    /// assert_eq!(&*counts, &[1, 0, 2]);
+    /// ```
+    pub fn get_mut_or_grow_with<F: FnMut() -> $array_item>(&mut self, index: usize, f: F) -> &mut $array_item {
+        if index >= self.len() {
+            self.resize_with(index + 1, f);
+        }
+        &mut self[index]
+    }
+
+    /// Returns a mutable reference to the element at `index`, first appending default values
+    /// until the vector is long enough to have one there.
+    #[inline]
+    pub fn get_mut_or_grow(&mut self, index: usize) -> &mut $array_item
+    where
+        $array_item: Default,
+    {
+        self.get_mut_or_grow_with(index, Default::default)
+    }
+
     /// Extracts a slice containing the entire vector.
     ///
     /// Equivalent to `&s[..]`.
@@ -441,8 +1163,12 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     ///
     /// Panics if `index` is out of bounds.
     #[inline]
+    #[track_caller]
     pub fn swap_remove(&mut self, index: usize) -> $array_item {
         let len = self.len();
+        if index >= len {
+            index_out_of_bounds("swap_remove", index, len);
+        }
         self.swap(len - 1, index);
         self.pop().unwrap_or_else(|| unsafe { unreachable_unchecked() })
     }
@@ -457,12 +1183,15 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// left.
     ///
     /// Panics if `index` is out of bounds.
+    #[track_caller]
     pub fn remove(&mut self, index: usize) -> $array_item {
         unsafe {
             let (mut ptr, len_ptr, _) = self.triple_mut();
-            let len = *len_ptr;
-            assert!(index < len);
-            *len_ptr = len - 1;
+            let len = len_ptr.get();
+            if index >= len {
+                index_out_of_bounds("remove", index, len);
+            }
+            len_ptr.set(len - 1);
             ptr = ptr.add(index);
             let item = ptr::read(ptr);
             ptr::copy(ptr.add(1), ptr, len - index - 1);
@@ -473,95 +1202,111 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// Insert an element at position `index`, shifting all elements after it to the right.
     ///
     /// Panics if `index` is out of bounds.
+    #[track_caller]
     pub fn insert(&mut self, index: usize, element: $array_item) {
-        self.reserve(1);
+        let len = self.len();
+        if index > len {
+            insertion_index_out_of_bounds("insert", index, len);
+        }
+        infallible(self.try_insert(index, element))
+    }
+
+    /// Insert an element at position `index`, shifting all elements after it to the right,
+    /// returning an error instead of panicking or aborting if the vector needs to grow and the
+    /// allocation fails. The element is dropped on failure.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn try_insert(&mut self, index: usize, element: $array_item) -> Result<(), CollectionAllocErr> {
+        let len = self.len();
+        if index > len {
+            insertion_index_out_of_bounds("try_insert", index, len);
+        }
+        self.try_reserve(1)?;
 
         unsafe {
             let (mut ptr, len_ptr, _) = self.triple_mut();
-            let len = *len_ptr;
-            assert!(index <= len);
-            *len_ptr = len + 1;
+            len_ptr.set(len + 1);
             ptr = ptr.add(index);
             ptr::copy(ptr, ptr.add(1), len - index);
             ptr::write(ptr, element);
         }
+        Ok(())
     }
 
     /// Insert multiple elements at position `index`, shifting all following elements toward the
     /// back.
+    ///
+    /// The elements are moved once, by the lower bound of the size hint of the iterator, which
+    /// is exact for `ExactSizeIterator`s and slices, and the items are written straight into the
+    /// gap. Items beyond the hint are appended and rotated into place.
+    #[track_caller]
     pub fn insert_many<I: IntoIterator<Item = $array_item>>(&mut self, index: usize, iterable: I) {
-        let iter = iterable.into_iter();
-        if index == self.len() {
+        let mut iter = iterable.into_iter();
+        let len = self.len();
+        if index == len {
             return self.extend(iter);
+        } else if index > len {
+            insertion_index_out_of_bounds("insert_many", index, len);
         }
 
         let (lower_size_bound, _) = iter.size_hint();
-        assert!(lower_size_bound <= core::isize::MAX as usize); // Ensure offset is indexable
-        assert!(index + lower_size_bound >= index); // Protect against overflow
         self.reserve(lower_size_bound);
-
         unsafe {
-            let old_len = self.len();
-            assert!(index <= old_len);
-            let mut ptr = self.as_mut_ptr().add(index);
-
-            // Move the trailing elements.
-            ptr::copy(ptr, ptr.add(lower_size_bound), old_len - index);
-
-            // In case the iterator panics, don't double-drop the items we just copied above.
-            self.set_len(index);
-
-            let mut num_added = 0;
-            for element in iter {
-                let mut cur = ptr.add(num_added);
-                if num_added >= lower_size_bound {
-                    // Iterator provided more elements than the hint.  Move trailing items again.
-                    self.reserve(1);
-                    ptr = self.as_mut_ptr().add(index);
-                    cur = ptr.add(num_added);
-                    ptr::copy(cur, cur.add(1), old_len - index);
+            let (ptr, len_ptr, _) = self.triple_mut();
+            let mut gap = InsertGap::open(ptr, len_ptr, index, lower_size_bound);
+            while !gap.is_full() {
+                match iter.next() {
+                    Some(element) => gap.push(element),
+                    // Fewer items than the hint, dropping the gap closes it.
+                    None => return,
                 }
-                ptr::write(cur, element);
-                num_added += 1;
-            }
-            if num_added < lower_size_bound {
-                // Iterator provided fewer elements than the hint
-                ptr::copy(
-                    ptr.add(lower_size_bound),
-                    ptr.add(num_added),
-                    old_len - index,
-                );
             }
+        }
 
-            self.set_len(old_len + num_added);
+        let old_len = self.len();
+        self.extend(iter);
+        if self.len() > old_len {
+            let end = index + lower_size_bound;
+            self[end..].rotate_left(old_len - end);
         }
     }
 
-    /// Convert a SmallVec to a Vec, without reallocating if the SmallVec has already spilled onto
-    /// the heap.
-    pub fn into_vec(self) -> Vec<$array_item> {
-        if self.spilled() {
-            unsafe {
-                let (ptr, len) = self.data.heap();
-                let v = Vec::from_raw_parts(ptr, len, self.capacity);
-                mem::forget(self);
-                v
-            }
-        } else {
-            self.into_iter().collect()
+    /// Append the `Ok` values of an iterator, stopping at the first `Err` and returning it.
+    ///
+    /// The values appended before the error stay in the vector.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u32; 4]> = SmallVec::from_slice(&[1]);
+    /// assert_eq!(v.try_extend("2 3".split(' ').map(str::parse)), Ok(()));
+    /// assert!(v.try_extend("4 x 5".split(' ').map(str::parse)).is_err());
+    /// assert_eq!(&*v, &[1, 2, 3, 4]);
+    /// ```
+    pub fn try_extend<E, I>(&mut self, iterable: I) -> Result<(), E>
+    where
+        I: IntoIterator<Item = Result<$array_item, E>>,
+    {
+        let iter = iterable.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item?);
         }
+        Ok(())
     }
 
     /// Convert the SmallVec into an `A` if possible. Otherwise return `Err(Self)`.
     ///
     /// This method returns `Err(Self)` if the SmallVec is too short (and the `A` contains uninitialized elements),
     /// or if the SmallVec is too long (and all the elements were spilled to the heap).
-    pub fn into_inner(self) -> Result<$array, Self> {
+    pub fn into_inner(mut self) -> Result<$array, Self> {
         if self.spilled() || self.len() != $array_size {
             Err(self)
         } else {
             unsafe {
                 let data = ptr::read(&self.data);
+                ptr::drop_in_place(&mut self.alloc);
                 mem::forget(self);
                 Ok(data.into_inline())
             }
@@ -586,6 +1331,33 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
         self.truncate(len - del);
     }
 
+    /// Keeps only the `k` elements that come first in the order defined by `compare`, and drops
+    /// the rest.
+    ///
+    /// The selection is done in place, without allocating, in linear time on average. The order
+    /// of the remaining elements is unspecified.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u32; 8]> = SmallVec::from_slice(&[5, 1, 4, 2, 3]);
+    /// // Keep the three largest values.
+    /// v.retain_top_k(3, |a, b| b.cmp(a));
+    /// v.sort_unstable();
+    /// assert_eq!(&*v, &[3, 4, 5]);
+    /// ```
+    pub fn retain_top_k<F>(&mut self, k: usize, compare: F)
+    where
+        F: FnMut(&$array_item, &$array_item) -> Ordering,
+    {
+        if k == 0 {
+            self.clear();
+        } else if k < self.len() {
+            self.select_nth_unstable_by(k - 1, compare);
+            self.truncate(k);
+        }
+    }
+
     /// Removes consecutive duplicate elements.
     pub fn dedup(&mut self)
     where
@@ -595,6 +1367,18 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     }
 
     /// Removes consecutive duplicate elements using the given equality relation.
+    ///
+    /// `same_bucket(a, b)` is passed the element being checked as `a` and the last element kept
+    /// before it as `b`, and `a` is removed if it returns `true`. If `same_bucket` panics, the
+    /// vector is left holding all of its elements, in an unspecified order.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[&str; 4]> = SmallVec::from_slice(&["a", "A", "b", "B", "B", "a"]);
+    /// v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(&*v, &["a", "b", "a"]);
+    /// ```
     pub fn dedup_by<F>(&mut self, mut same_bucket: F)
     where
         F: FnMut(&mut $array_item, &mut $array_item) -> bool,
@@ -616,7 +1400,7 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
                 if !same_bucket(&mut *p_r, &mut *p_wm1) {
                     if r != w {
                         let p_w = p_wm1.add(1);
-                        mem::swap(&mut *p_r, &mut *p_w);
+                        ptr::swap(p_r, p_w);
                     }
                     w += 1;
                 }
@@ -627,6 +1411,14 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     }
 
     /// Removes consecutive elements that map to the same key.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u32; 4]> = SmallVec::from_slice(&[10, 11, 20, 21, 22, 10]);
+    /// v.dedup_by_key(|x| *x / 10);
+    /// assert_eq!(&*v, &[10, 20, 10]);
+    /// ```
     pub fn dedup_by_key<F, K>(&mut self, mut key: F)
     where
         F: FnMut(&mut $array_item) -> K,
@@ -635,6 +1427,322 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
         self.dedup_by(|a, b| key(a) == key(b));
     }
 
+    /// Append an element unless the vector already holds an equal one. Returns `true` if the
+    /// element was appended.
+    ///
+    /// The lookup is a linear scan, which is usually the fastest option for small vectors.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut seen: SmallVec<[u32; 4]> = SmallVec::new();
+    /// assert!(seen.push_unique(1));
+    /// assert!(seen.push_unique(2));
+    /// assert!(!seen.push_unique(1));
+    /// assert_eq!(&*seen, &[1, 2]);
+    /// ```
+    #[inline]
+    pub fn push_unique(&mut self, value: $array_item) -> bool
+    where
+        $array_item: PartialEq<$array_item>,
+    {
+        if self.contains(&value) {
+            return false;
+        }
+        self.push(value);
+        true
+    }
+
+    /// Append an element unless the vector already holds one with the same key. Returns `true`
+    /// if the element was appended.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut names: SmallVec<[&str; 4]> = SmallVec::new();
+    /// assert!(names.push_unique_by_key("Foo", |s| s.to_lowercase()));
+    /// assert!(!names.push_unique_by_key("foo", |s| s.to_lowercase()));
+    /// assert_eq!(&*names, &["Foo"]);
+    /// ```
+    #[inline]
+    pub fn push_unique_by_key<F, K>(&mut self, value: $array_item, mut key: F) -> bool
+    where
+        F: FnMut(&$array_item) -> K,
+        K: PartialEq<K>,
+    {
+        let value_key = key(&value);
+        if self.iter().any(|element| key(element) == value_key) {
+            return false;
+        }
+        self.push(value);
+        true
+    }
+
+    /// Insert an element into a sorted vector, after any equal elements, so that it stays
+    /// sorted. Returns the index of the element.
+    ///
+    /// The position is found with a binary search. If the vector isn't sorted, the element is
+    /// inserted at an unspecified position.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u32; 4]> = SmallVec::from_slice(&[1, 3, 5]);
+    /// assert_eq!(v.insert_sorted(4), 2);
+    /// assert_eq!(v.insert_sorted(0), 0);
+    /// assert_eq!(&*v, &[0, 1, 3, 4, 5]);
+    /// ```
+    #[inline]
+    pub fn insert_sorted(&mut self, value: $array_item) -> usize
+    where
+        $array_item: Ord,
+    {
+        self.insert_sorted_by(value, Ord::cmp)
+    }
+
+    /// Insert an element into a vector sorted with `compare`, after any elements it compares
+    /// equal to. Returns the index of the element.
+    #[inline]
+    pub fn insert_sorted_by<F>(&mut self, value: $array_item, mut compare: F) -> usize
+    where
+        F: FnMut(&$array_item, &$array_item) -> Ordering,
+    {
+        let index = self.partition_point(|element| compare(element, &value) != Ordering::Greater);
+        self.insert(index, value);
+        index
+    }
+
+    /// Insert an element into a vector sorted by `key`, after any elements with an equal key.
+    /// Returns the index of the element.
+    ///
+    /// The key of the new element is computed once.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[(u8, char); 4]> = SmallVec::from_slice(&[(1, 'a'), (2, 'b')]);
+    /// assert_eq!(v.insert_sorted_by_key((1, 'c'), |&(k, _)| k), 1);
+    /// assert_eq!(&*v, &[(1, 'a'), (1, 'c'), (2, 'b')]);
+    /// ```
+    #[inline]
+    pub fn insert_sorted_by_key<F, K>(&mut self, value: $array_item, mut key: F) -> usize
+    where
+        F: FnMut(&$array_item) -> K,
+        K: Ord,
+    {
+        let value_key = key(&value);
+        let index = self.partition_point(|element| key(element) <= value_key);
+        self.insert(index, value);
+        index
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Replace the elements in `range` with the items of `replace_with`, and return an
+    /// iterator over the removed elements.
+    ///
+    /// The replacement happens when the returned iterator is dropped, whether the removed
+    /// elements were consumed or not. `replace_with` may yield more or fewer items than the
+    /// range holds.
+    ///
+    /// Panics if the start of the range is greater than its end, or if its end is greater than
+    /// the length of the vector.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2, 3, 4]);
+    /// let removed: Vec<u8> = v.splice(1..3, [7, 8, 9].iter().cloned()).collect();
+    /// assert_eq!(removed, [2, 3]);
+    /// assert_eq!(&*v, &[1, 7, 8, 9, 4]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[track_caller]
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, $s_decl_ty$(, {$s_decl_const_ty})?>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = $array_item>,
+    {
+        let len = self.len();
+        let Range { start, end } = slice_range(range, len);
+        unsafe {
+            self.set_len(start);
+        }
+        Splice {
+            vec: self,
+            replace_with: replace_with.into_iter(),
+            front: start,
+            back: end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    /// Returns a cursor pointing to the first element, which can be used to insert and remove
+    /// elements while walking the vector.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[i32; 8]> = SmallVec::from_slice(&[1, 2, 3, 4]);
+    /// let mut cursor = v.cursor_mut();
+    /// while let Some(&mut x) = cursor.current() {
+    ///     if x % 2 == 0 {
+    ///         cursor.remove_current();
+    ///         cursor.insert_before(x * 10);
+    ///         cursor.insert_before(x * 100);
+    ///     } else {
+    ///         cursor.move_next();
+    ///     }
+    /// }
+    /// drop(cursor);
+    /// assert_eq!(&*v, &[1, 20, 200, 3, 40, 400]);
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, $s_decl_ty$(, {$s_decl_const_ty})?> {
+        let len = self.len();
+        // The cursor owns the elements until it is dropped, so a leaked cursor leaks them
+        // instead of leaving the vector in an inconsistent state.
+        unsafe { self.set_len(0) };
+        CursorMut {
+            vec: self,
+            head: 0,
+            back: 0,
+            tail: len,
+        }
+    }
+
+    /// Return an iterator that removes the elements for which `pred` returns `true`, and yields
+    /// them by value.
+    ///
+    /// The elements are checked in order, and only as the iterator is consumed: the elements
+    /// that weren't checked when the iterator is dropped stay in the vector. The order of the
+    /// remaining elements is preserved. `pred` may mutate the elements it is passed.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::from_slice(&[1, 2, 3, 4, 5, 6]);
+    /// let evens: Vec<u8> = v.extract_if(|x| *x % 2 == 0).collect();
+    /// assert_eq!(evens, [2, 4, 6]);
+    /// assert_eq!(&*v, &[1, 3, 5]);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, F, $s_decl_ty$(, {$s_decl_const_ty})?>
+    where
+        F: FnMut(&mut $array_item) -> bool,
+    {
+        let old_len = self.len();
+        // Leaking the iterator leaks the elements instead of exposing moved-out ones.
+        unsafe {
+            self.set_len(0);
+        }
+        ExtractIf {
+            vec: self,
+            pred,
+            idx: 0,
+            del: 0,
+            old_len,
+        }
+    }
+
+    /// Temporarily view the vector as a `Vec`, for APIs that only accept `&mut Vec<T>`.
+    ///
+    /// The elements are moved into the `Vec` (without reallocating if the vector has spilled)
+    /// and moved back when the returned guard is dropped. A vector that was inline goes back
+    /// inline if it still fits. The vector is left empty if the guard is leaked.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// fn fill(out: &mut Vec<u8>) {
+    ///     out.extend_from_slice(b"abc");
+    /// }
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::new();
+    /// fill(&mut v.as_vec_mut());
+    /// assert_eq!(&*v, b"abc");
+    /// assert!(!v.spilled());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn as_vec_mut(&mut self) -> VecGuard<'_, $s_decl_ty$(, {$s_decl_const_ty})?> {
+        let was_inline = !self.spilled();
+        let inner = mem::take(self).into_vec();
+        VecGuard {
+            vec: self,
+            inner,
+            was_inline,
+        }
+    }
+
+    /// Exchange the contents of the vector with those of a `Vec`.
+    ///
+    /// Heap buffers change owner without copying the elements: only the elements of an inline
+    /// vector, or of a `Vec` short enough to be stored inline, are moved with a bulk copy.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 2]> = SmallVec::from_slice(&[1, 2, 3]);
+    /// let mut vec = vec![4];
+    /// v.swap_with_vec(&mut vec);
+    /// assert_eq!(&*v, &[4]);
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn swap_with_vec(&mut self, vec: &mut Vec<$array_item>) {
+        let other = Self::from_vec(mem::take(vec));
+        *vec = mem::replace(self, other).into_vec();
+    }
+
+    /// Convert a SmallVec to a Vec, without reallocating if the SmallVec has already spilled onto
+    /// the heap.
+    #[cfg(feature = "alloc")]
+    pub fn into_vec(self) -> Vec<$array_item> {
+        if self.spilled() {
+            unsafe {
+                let (ptr, len) = self.data.heap();
+                let v = Vec::from_raw_parts(ptr, len, self.capacity.get());
+                mem::forget(self);
+                v
+            }
+        } else {
+            self.into_iter().collect()
+        }
+    }
+
+    /// Convert a SmallVec to a `Box<[T]>`, moving inline elements to the heap and shrinking a
+    /// spilled buffer to fit.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 2]> = SmallVec::with_capacity(8);
+    /// v.extend_from_slice(b"abc");
+    /// let b: Box<[u8]> = v.into_boxed_slice();
+    /// assert_eq!(&*b, b"abc");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_boxed_slice(self) -> Box<[$array_item]> {
+        self.into_vec().into_boxed_slice()
+    }
+
+    /// Consume the vector and leak its elements, returning a mutable slice that lives as long as
+    /// the caller wants. Inline elements are moved to the heap first.
+    ///
+    /// Like `Vec::leak`, the heap buffer is never freed, along with any unused capacity.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u32; 4]> = SmallVec::from_slice(&[1, 2, 3]);
+    /// let table: &'static mut [u32] = v.leak();
+    /// table[0] = 10;
+    /// assert_eq!(table, &[10, 2, 3]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn leak<'a>(self) -> &'a mut [$array_item] {
+        self.into_vec().leak()
+    }
+
     /// Creates a `SmallVec` directly from the raw components of another
     /// `SmallVec`.
     ///
@@ -644,7 +1752,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     /// checked:
     ///
     /// * `ptr` needs to have been previously allocated via `SmallVec` for its
-    ///   spilled storage (at least, it's highly likely to be incorrect if it
+    ///   spilled storage, as returned by [`into_raw_parts`](Self::into_raw_parts),
+    ///   or by `Vec` (at least, it's highly likely to be incorrect if it
     ///   wasn't).
     /// * `ptr`'s `$array_item` type needs to be the same size and alignment that
     ///   it was allocated with
@@ -657,7 +1766,9 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     ///
     /// Additionally, `capacity` must be greater than the amount of inline
     /// storage `A` has; that is, the new `SmallVec` must need to spill over
-    /// into heap allocated storage. This condition is asserted against.
+    /// into heap allocated storage. With the `u32_len` feature, it must also be
+    /// less than `u32::MAX` unless the elements are zero-sized. These conditions
+    /// are asserted against.
     ///
     /// The ownership of `ptr` is effectively transferred to the
     /// `SmallVec` which may then deallocate, reallocate or change the
@@ -701,17 +1812,89 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec<$s_de
     ///         assert_eq!(&*rebuilt, &[4, 5, 6]);
     ///     }
     /// }
+    #[cfg(feature = "alloc")]
     pub unsafe fn from_raw_parts(ptr: *mut $array_item, length: usize, capacity: usize) -> Self {
         assert!(capacity > $array_size);
+        assert!(capacity <= NonMaxUsize::MAX || mem::size_of::<$array_item>() == 0);
         SmallVec {
-            capacity,
+            capacity: NonMaxUsize::new(capacity),
             data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, length),
+            alloc: Global,
+            phantom: PhantomData,
         }
     }
+
+    /// Decomposes the vector into the pointer, length and capacity of a heap buffer, giving up
+    /// its ownership.
+    ///
+    /// An inline vector is first moved to a heap buffer with room for one more element than the
+    /// inline capacity, so the parts can always be given back to
+    /// [`from_raw_parts`](Self::from_raw_parts) for the same type. A spilled vector keeps its
+    /// buffer as is. The buffer comes from the global allocator, so the parts can also be
+    /// adopted by `Vec::from_raw_parts`.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u32; 4]> = SmallVec::from_slice(&[1, 2]);
+    /// let (ptr, len, cap) = v.into_raw_parts();
+    /// assert_eq!((len, cap), (2, 5));
+    /// let v: SmallVec<[u32; 4]> = unsafe { SmallVec::from_raw_parts(ptr, len, cap) };
+    /// assert_eq!(&v[..], &[1, 2]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_raw_parts(mut self) -> (*mut $array_item, usize, usize) {
+        if !self.spilled() {
+            self.grow($array_size + 1);
+        }
+        let mut this = mem::ManuallyDrop::new(self);
+        let (ptr, len, cap) = this.triple_mut();
+        let len = len.get();
+        (ptr, len, cap)
+    }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*>
-    SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?>
+    SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $($alloc: Clone,)?
+{
+    /// Split the vector in two at `at`, returning the elements from `at` onward in a new vector
+    /// and keeping the others in place. The capacity of `self` is unchanged.
+    ///
+    /// The new vector stores the elements inline when they fit, and allocates for exactly as
+    /// many elements otherwise.
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 4]> = SmallVec::from_slice(&[1, 2, 3, 4, 5, 6]);
+    /// let tail = v.split_off(3);
+    /// assert_eq!((&v[..], &tail[..]), (&[1, 2, 3][..], &[4, 5, 6][..]));
+    /// assert!(!tail.spilled());
+    /// ```
+    #[track_caller]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        if at > len {
+            insertion_index_out_of_bounds("split_off", at, len);
+        }
+        let tail_len = len - at;
+        let mut other = Self::empty_in(self.alloc.clone());
+        other.reserve_exact(tail_len);
+        unsafe {
+            self.set_len(at);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), tail_len);
+            other.set_len(tail_len);
+        }
+        other
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?>
+    SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Clone,
 {
@@ -722,15 +1905,90 @@ where
     /// If `len` is greater than the current length, `value` is appended to the
     /// vector until its length equals `len`.
     pub fn resize(&mut self, len: usize, value: $array_item) {
+        infallible(self.try_resize(len, value))
+    }
+
+    /// Resizes the vector so that its length is equal to `len`, returning an error instead of
+    /// panicking or aborting if the vector needs to grow and the allocation fails. The vector is
+    /// left unchanged on failure.
+    pub fn try_resize(&mut self, len: usize, value: $array_item) -> Result<(), CollectionAllocErr> {
         let old_len = self.len();
 
         if len > old_len {
-            self.extend(repeat(value).take(len - old_len));
+            self.try_reserve(len - old_len)?;
+            self.extend_with(len - old_len, value);
         } else {
             self.truncate(len);
         }
+        Ok(())
     }
 
+    /// Append clones of the elements in the `src` range of the vector to its end.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::from_slice(b"abc");
+    /// v.extend_from_within(1..);
+    /// v.extend_from_within(..2);
+    /// assert_eq!(&v[..], b"abcbcab");
+    /// ```
+    #[track_caller]
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R) {
+        let Range { start, end } = slice_range(src, self.len());
+        self.reserve(end - start);
+        unsafe {
+            let (ptr, len_ptr, _) = self.triple_mut();
+            let mut local_len = SetLenOnDrop::new(len_ptr);
+            for i in start..end {
+                ptr::write(ptr.add(local_len.get()), (*ptr.add(i)).clone());
+                local_len.increment_len(1);
+            }
+        }
+    }
+
+    /// Append `n` clones of `value`. The capacity must already be reserved.
+    #[cfg(not(feature = "specialization"))]
+    #[inline]
+    fn extend_with(&mut self, n: usize, value: $array_item) {
+        self.extend_with_clones(n, value)
+    }
+
+    /// Append `n` clones of `value`. The capacity must already be reserved.
+    #[cfg(feature = "specialization")]
+    #[inline]
+    fn extend_with(&mut self, n: usize, value: $array_item) {
+        self.spec_extend_with(n, value)
+    }
+
+    /// Append `n` clones of `value`, moving `value` itself into the last slot. The capacity
+    /// must already be reserved.
+    fn extend_with_clones(&mut self, n: usize, value: $array_item) {
+        unsafe {
+            let (ptr, len_ptr, cap) = self.triple_mut();
+            debug_assert!(cap - len_ptr.get() >= n);
+            let mut local_len = SetLenOnDrop::new(len_ptr);
+            let mut dst = ptr.add(local_len.get());
+            for _ in 1..n {
+                ptr::write(dst, value.clone());
+                dst = dst.add(1);
+                local_len.increment_len(1);
+            }
+            if n > 0 {
+                ptr::write(dst, value);
+                local_len.increment_len(1);
+            }
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*>
+    SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
     /// Creates a `SmallVec` with `n` copies of `elem`.
     /// ```
     /// use smallvec::SmallVec;
@@ -739,21 +1997,46 @@ where
     /// assert_eq!(v, SmallVec::from_buf(['d', 'd']));
     /// ```
     pub fn from_elem(elem: $array_item, n: usize) -> Self {
-        if n > $array_size {
-            vec![elem; n].into()
-        } else {
-            let mut v = Self::new();
-            unsafe {
-                let (ptr, len_ptr, _) = v.triple_mut();
-                let mut local_len = SetLenOnDrop::new(len_ptr);
+        let mut v = Self::with_capacity(n);
+        v.extend_with(n, elem);
+        v
+    }
+}
 
-                for i in 0..n {
-                    core::ptr::write(ptr.add(i), elem.clone());
-                    local_len.increment_len(1);
-                }
-            }
-            v
-        }
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: SortableFloat,
+{
+    /// Sort the floats in the IEEE 754 `totalOrder`, without preserving the order of equal
+    /// elements.
+    ///
+    /// Negative NaNs sort first and positive NaNs last, and `-0.0` sorts before `0.0`.
+    #[inline]
+    pub fn sort_unstable_by_total_cmp(&mut self) {
+        self.sort_unstable_by(SortableFloat::total_cmp);
+    }
+
+    /// Sort the floats in ascending order, with all the NaNs at the end whatever their sign,
+    /// without preserving the order of equal elements.
+    ///
+    /// The numbers are ordered like `sort_unstable_by_total_cmp` does, so `-0.0` sorts before
+    /// `0.0`.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[f64; 4]> = SmallVec::from_slice(&[2.5, -f64::NAN, -1.0, 0.5]);
+    /// v.sort_floats_unstable();
+    /// assert_eq!(&v[..3], &[-1.0, 0.5, 2.5]);
+    /// assert!(v[3].is_nan());
+    /// ```
+    pub fn sort_floats_unstable(&mut self) {
+        self.sort_unstable_by(|a, b| match (a.is_nan(), b.is_nan()) {
+            (false, false) => a.total_cmp(b),
+            (false, true) => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (true, true) => Ordering::Equal,
+        });
     }
 }
 
@@ -768,33 +2051,71 @@ where
         let len = slice.len();
         if len <= $array_size {
             SmallVec {
-                capacity: len,
+                capacity: NonMaxUsize::new(len),
                 data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_inline(unsafe {
                     let mut data = MaybeUninit::<$array>::uninit();
-                    let slice_mut = &mut *data.as_mut_ptr();
-                    ptr::copy_nonoverlapping(slice.as_ptr(), slice_mut.as_mut_ptr(), len);
+                    ptr::copy_nonoverlapping(slice.as_ptr(), data.as_mut_ptr() as *mut $array_item, len);
                     data
                 }),
+                alloc: Global,
+                phantom: PhantomData,
             }
         } else {
-            let mut b = slice.to_vec();
-            let (ptr, cap) = (b.as_mut_ptr(), b.capacity());
-            mem::forget(b);
-            SmallVec {
-                capacity: cap,
-                data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, len),
+            infallible(Self::try_from_slice(slice))
+        }
+    }
+
+    /// Copy the elements from a slice into a new `SmallVec`, returning an error instead of
+    /// aborting if the slice doesn't fit inline and the heap allocation fails.
+    pub fn try_from_slice(slice: &[$array_item]) -> Result<Self, CollectionAllocErr> {
+        let len = slice.len();
+        if len <= $array_size {
+            Ok(Self::from_slice(slice))
+        } else {
+            unsafe {
+                let ptr = allocate(len)?;
+                ptr::copy_nonoverlapping(slice.as_ptr(), ptr, len);
+                Ok(SmallVec {
+                    capacity: NonMaxUsize::new(len),
+                    data: SmallVecData::<$s_decl_ty$(, {$s_decl_const_ty})?>::from_heap(ptr, len),
+                    alloc: Global,
+                    phantom: PhantomData,
+                })
             }
         }
     }
+
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Copy,
+{
     /// Copy elements from a slice into the vector at position `index`, shifting any following
     /// elements toward the back.
     ///
     /// For slices of `Copy` types, this is more efficient than `insert`.
+    #[track_caller]
     pub fn insert_from_slice(&mut self, index: usize, slice: &[$array_item]) {
-        self.reserve(slice.len());
+        let len = self.len();
+        if index > len {
+            insertion_index_out_of_bounds("insert_from_slice", index, len);
+        }
+        infallible(self.try_insert_from_slice(index, slice))
+    }
 
+    /// Copy elements from a slice into the vector at position `index`, shifting any following
+    /// elements toward the back, returning an error instead of panicking or aborting if the
+    /// vector needs to grow and the allocation fails. The vector is left unchanged on failure.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn try_insert_from_slice(&mut self, index: usize, slice: &[$array_item]) -> Result<(), CollectionAllocErr> {
         let len = self.len();
-        assert!(index <= len);
+        if index > len {
+            insertion_index_out_of_bounds("try_insert_from_slice", index, len);
+        }
+        self.try_reserve(slice.len())?;
 
         unsafe {
             let slice_ptr = slice.as_ptr();
@@ -803,20 +2124,31 @@ where
             ptr::copy_nonoverlapping(slice_ptr, ptr, slice.len());
             self.set_len(len + slice.len());
         }
+        Ok(())
     }
 
     /// Copy elements from a slice and append them to the vector.
     ///
     /// For slices of `Copy` types, this is more efficient than `extend`.
     #[inline]
+    #[track_caller]
     pub fn extend_from_slice(&mut self, slice: &[$array_item]) {
         let len = self.len();
         self.insert_from_slice(len, slice);
     }
+
+    /// Copy elements from a slice and append them to the vector, returning an error instead of
+    /// panicking or aborting if the vector needs to grow and the allocation fails. The vector is
+    /// left unchanged on failure.
+    #[inline]
+    pub fn try_extend_from_slice(&mut self, slice: &[$array_item]) -> Result<(), CollectionAllocErr> {
+        let len = self.len();
+        self.try_insert_from_slice(len, slice)
+    }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> AsMut<[$array_item]>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> AsMut<[$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn as_mut(&mut self) -> &mut [$array_item] {
@@ -825,8 +2157,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> AsMut<[$array_
 }
 
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> AsRef<[$array_item]>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> AsRef<[$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn as_ref(&self) -> &[$array_item] {
@@ -834,8 +2166,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> AsRef<[$array_
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Borrow<[$array_item]>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Borrow<[$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn borrow(&self) -> &[$array_item] {
@@ -843,8 +2175,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Borrow<[$array
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> BorrowMut<[$array_item]>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> BorrowMut<[$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn borrow_mut(&mut self) -> &mut [$array_item] {
@@ -852,13 +2184,15 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> BorrowMut<[$ar
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Clone
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Clone,
+    $($alloc: Clone,)?
 {
     fn clone(&self) -> Self {
-        let mut new_vector = Self::with_capacity(self.len());
+        let mut new_vector = Self::empty_in(self.alloc.clone());
+        new_vector.reserve_exact(self.len());
         for element in self.iter() {
             new_vector.push((*element).clone())
         }
@@ -866,8 +2200,8 @@ where
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Debug
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Debug,
 {
@@ -885,8 +2219,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Deref
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     type Target = [$array_item];
     #[inline]
@@ -898,27 +2232,33 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DerefMut
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> DerefMut
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     #[inline]
     fn deref_mut(&mut self) -> &mut [$array_item] {
         unsafe {
-            let (ptr, &mut len, _) = self.triple_mut();
+            let (ptr, len, _) = self.triple_mut();
+            let len = len.get();
             slice::from_raw_parts_mut(ptr, len)
         }
     }
 }
 
 #[cfg(feature = "may_dangle")]
-unsafe impl<#[may_dangle] $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+unsafe impl<#[may_dangle] $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Drop
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     fn drop(&mut self) {
         unsafe {
             if self.spilled() {
                 let (ptr, len) = self.data.heap();
-                Vec::from_raw_parts(ptr, len, self.capacity);
+                let _buffer = DeallocOnDrop {
+                    alloc: &self.alloc,
+                    ptr,
+                    capacity: self.capacity.get(),
+                };
+                ptr::drop_in_place(slice::from_raw_parts_mut(ptr, len));
             } else {
                 ptr::drop_in_place(&mut self[..]);
             }
@@ -927,14 +2267,19 @@ unsafe impl<#[may_dangle] $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound
 }
 
 #[cfg(not(feature = "may_dangle"))]
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Drop
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     fn drop(&mut self) {
         unsafe {
             if self.spilled() {
                 let (ptr, len) = self.data.heap();
-                Vec::from_raw_parts(ptr, len, self.capacity);
+                let _buffer = DeallocOnDrop {
+                    alloc: &self.alloc,
+                    ptr,
+                    capacity: self.capacity.get(),
+                };
+                ptr::drop_in_place(slice::from_raw_parts_mut(ptr, len));
             } else {
                 ptr::drop_in_place(&mut self[..]);
             }
@@ -949,46 +2294,92 @@ where
     $array_item: Deserialize<'de>,
 {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_seq(SmallVecVisitor {
-            phantom: PhantomData,
-        })
+        deserializer.deserialize_seq(SmallVecVisitor::new())
+    }
+
+    /// Deserialize a sequence into `place`, replacing its contents but reusing its buffer.
+    fn deserialize_in_place<D: Deserializer<'de>>(
+        deserializer: D,
+        place: &mut Self,
+    ) -> Result<(), D::Error> {
+        place.clear();
+        deserializer.deserialize_seq(AppendSeed(place))
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Eq
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Eq {}
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Extend<$array_item>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
+    #[cfg(not(feature = "specialization"))]
+    #[inline]
     fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
-        let mut iter = iterable.into_iter();
-        let (lower_size_bound, _) = iter.size_hint();
-        self.reserve(lower_size_bound);
+        self.extend_desugared(iterable.into_iter())
+    }
 
-        unsafe {
-            let (ptr, len_ptr, cap) = self.triple_mut();
-            let mut len = SetLenOnDrop::new(len_ptr);
-            while len.get() < cap {
-                if let Some(out) = iter.next() {
-                    ptr::write(ptr.add(len.get()), out);
-                    len.increment_len(1);
-                } else {
-                    return;
-                }
-            }
-        }
+    #[cfg(feature = "specialization")]
+    #[inline]
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        self.spec_extend(iterable.into_iter())
+    }
 
-        for elem in iter {
-            self.push(elem);
-        }
+    #[cfg(feature = "extend_one")]
+    #[inline]
+    fn extend_one(&mut self, item: $array_item) {
+        self.push(item);
+    }
+
+    #[cfg(feature = "extend_one")]
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+// With arrays, `A::Item` could itself be a reference, so these would conflict with the impls for
+// `A::Item`. They are implemented for each array size in `array.rs` instead.
+#[cfg(feature = "const_generics")]
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Extend<&'a $array_item>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Copy + 'a,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a $array_item>>(&mut self, iterable: I) {
+        self.extend(iterable.into_iter().copied())
+    }
+
+    #[cfg(feature = "extend_one")]
+    #[inline]
+    fn extend_one(&mut self, &item: &'a $array_item) {
+        self.push(item);
+    }
+
+    #[cfg(feature = "extend_one")]
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<&'a $array_item>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Copy + 'a,
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = &'a $array_item>>(iterable: I) -> Self {
+        iterable.into_iter().copied().collect()
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> ExtendFromSlice<$array_item>
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> ExtendFromSlice<$array_item>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Copy,
 {
@@ -1006,6 +2397,98 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<$array>
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<Cow<'a, [$array_item]>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    /// Clone the elements if they are borrowed, or take over the owned `Vec` like `from_vec`.
+    #[inline]
+    fn from(cow: Cow<'a, [$array_item]>) -> Self {
+        match cow {
+            Cow::Borrowed(slice) => Self::from(slice),
+            Cow::Owned(vec) => Self::from_vec(vec),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for Cow<'a, [$array_item]>
+where
+    $array_item: Clone,
+{
+    #[inline]
+    fn from(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        Cow::Owned(vec.into_vec())
+    }
+}
+
+// The heap buffer of the boxed slice is adopted when the elements don't fit inline.
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<Box<[$array_item]>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn from(slice: Box<[$array_item]>) -> Self {
+        Self::from_vec(slice.into_vec())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for Box<[$array_item]>
+{
+    #[inline]
+    fn from(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        vec.into_boxed_slice()
+    }
+}
+
+// A spilled vector hands its buffer to `Rc::from(Vec)`, which copies the elements into the new
+// allocation and frees the buffer without dropping them. Inline elements are read straight into
+// the new allocation: collecting a `TrustedLen` iterator into an `Rc<[T]>` allocates it once with
+// the exact length.
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for Rc<[$array_item]>
+{
+    #[inline]
+    fn from(mut vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        if vec.spilled() {
+            return Rc::from(vec.into_vec());
+        }
+        let len = vec.len();
+        unsafe {
+            // The elements are moved out, so the vector must not drop them.
+            vec.set_len(0);
+            let ptr = vec.as_ptr();
+            (0..len).map(|i| ptr::read(ptr.add(i))).collect()
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for Arc<[$array_item]>
+{
+    #[inline]
+    fn from(mut vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        if vec.spilled() {
+            return Arc::from(vec.into_vec());
+        }
+        let len = vec.len();
+        unsafe {
+            // The elements are moved out, so the vector must not drop them.
+            vec.set_len(0);
+            let ptr = vec.as_ptr();
+            (0..len).map(|i| ptr::read(ptr.add(i))).collect()
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<Vec<$array_item>>
     for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
 {
@@ -1037,14 +2520,17 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<$
     for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
 {
     fn from_iter<I: IntoIterator<Item = $array_item>>(iterable: I) -> Self {
-        let mut v = Self::new();
-        v.extend(iterable);
+        // Allocate the hinted size up front (exactly, like `Vec`) instead of filling the inline
+        // buffer first and spilling from there.
+        let iter = iterable.into_iter();
+        let mut v = Self::with_capacity(iter.size_hint().0);
+        v.extend(iter);
         v
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Hash
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Hash
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Hash,
 {
@@ -1053,10 +2539,10 @@ where
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> IntoIterator
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
-    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>;
     type Item = $array_item;
     fn into_iter(mut self) -> Self::IntoIter {
         unsafe {
@@ -1072,8 +2558,8 @@ impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
     }
 }
 
-impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
-    for &'a SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> IntoIterator
+    for &'a SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     type IntoIter = slice::Iter<'a, $array_item>;
     type Item = &'a $array_item;
@@ -1082,8 +2568,8 @@ impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterat
     }
 }
 
-impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
-    for &'a mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> IntoIterator
+    for &'a mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 {
     type IntoIter = slice::IterMut<'a, $array_item>;
     type Item = &'a mut $array_item;
@@ -1092,8 +2578,8 @@ impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterat
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Ord
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Ord
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Ord,
 {
@@ -1103,26 +2589,108 @@ where
     }
 }
 
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialOrd
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? U> PartialEq<[U]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? U> PartialEq<&'a [U]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &&'a [U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? U> PartialEq<&'a mut [U]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &&'a mut [U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? U> PartialEq<Vec<U>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> PartialOrd<[$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &[$array_item]) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, other)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> PartialOrd<&'a [$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: PartialOrd,
 {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &&'a [$array_item]) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, *other)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> PartialOrd<&'a mut [$array_item]>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &&'a mut [$array_item]) -> Option<Ordering> {
         PartialOrd::partial_cmp(&**self, &**other)
     }
 }
 
-unsafe impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Send
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+#[cfg(feature = "alloc")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> PartialOrd<Vec<$array_item>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
-    $array_item: Send {}
+    $array_item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Vec<$array_item>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+unsafe impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Send
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Send,
+    $($alloc: Send,)?
+{}
 
 
 #[cfg(feature = "serde")]
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Serialize
-    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Serialize
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
 where
     $array_item: Serialize,
 {
@@ -1135,6 +2703,72 @@ where
     }
 }
 
+#[cfg(feature = "specialization")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? I: Iterator<Item = $array_item>>
+    SpecExtend<$array_item, I> for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+{
+    #[inline]
+    default fn spec_extend(&mut self, iter: I) {
+        self.extend_desugared(iter)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator,)? I: TrustedLen<Item = $array_item>>
+    SpecExtend<$array_item, I> for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+{
+    #[inline]
+    default fn spec_extend(&mut self, iter: I) {
+        self.extend_trusted(iter)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?>
+    SpecExtend<$array_item, IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+{
+    fn spec_extend(&mut self, mut iter: IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>) {
+        let remaining = iter.as_slice();
+        unsafe {
+            self.append_raw(remaining.as_ptr(), remaining.len());
+        }
+        // The items were moved out, only the buffer is left to free.
+        iter.current = iter.end;
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?>
+    SpecExtendWith<$array_item> for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Clone,
+{
+    #[inline]
+    default fn spec_extend_with(&mut self, n: usize, value: $array_item) {
+        self.extend_with_clones(n, value)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?>
+    SpecExtendWith<$array_item> for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
+where
+    $array_item: Copy,
+{
+    #[inline]
+    fn spec_extend_with(&mut self, n: usize, value: $array_item) {
+        unsafe {
+            let (ptr, len_ptr, cap) = self.triple_mut();
+            let len = len_ptr.get();
+            debug_assert!(cap - len >= n);
+            // Copying can't panic, so the length only has to be updated once at the end.
+            SpecFill::spec_fill(ptr.add(len), n, value);
+            len_ptr.set(len + n);
+        }
+    }
+}
+
 #[cfg(feature = "specialization")]
 impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*>
     SpecFrom<&'a [$array_item], $s_decl_ty$(, {$s_decl_const_ty})?>
@@ -1163,20 +2797,22 @@ where
 
 macro_rules! impl_index {
     ($index_type: ty, $output_type: ty) => {
-        impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Index<$index_type>
-            for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+        impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> Index<$index_type>
+            for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
         {
             type Output = $output_type;
             #[inline]
+            #[track_caller]
             fn index(&self, index: $index_type) -> &$output_type {
                 &(&**self)[index]
             }
         }
 
-        impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IndexMut<$index_type>
-            for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+        impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*, $($alloc: Allocator)?> IndexMut<$index_type>
+            for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?, $($alloc)?>
         {
             #[inline]
+            #[track_caller]
             fn index_mut(&mut self, index: $index_type) -> &mut $output_type {
                 &mut (&mut **self)[index]
             }
@@ -1193,29 +2829,303 @@ impl_index!(RangeFull, [$array_item]);
     }
 }
 
-#[cfg(feature = "const_generics")]
-impl<T, const N: usize> PartialEq<Self> for SmallVec<T, { N }>
+macro_rules! impl_cross_type_cmp {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $item:ty,
+    [$($other_impl_generics:tt)*],
+    [$($other_decl_generics:tt)*],
+    $other_item:ty,
+    $array:ty,
+    [$($ord_impl_generics:tt)*],
+    [$($ord_decl_generics:tt)*],
+    $ord_array:ty,
+    |$other:ident| $array_slice:expr
+    $(, $alloc:ident, $other_alloc:ident)?
+) => {
+
+impl<$($impl_generics)*, $($other_impl_generics)* $(, $alloc: Allocator, $other_alloc: Allocator)?>
+    PartialEq<SmallVec<$($other_decl_generics)* $(, $other_alloc)?>>
+    for SmallVec<$($decl_generics)* $(, $alloc)?>
 where
-    T: PartialEq,
+    $item: PartialEq<$other_item>,
 {
     #[inline]
-    fn eq(&self, other: &Self) -> bool {
+    fn eq(&self, other: &SmallVec<$($other_decl_generics)* $(, $other_alloc)?>) -> bool {
         self[..] == other[..]
     }
+}
+
+impl<$($impl_generics)*, $($other_impl_generics)* $(, $alloc: Allocator)?> PartialEq<$array>
+    for SmallVec<$($decl_generics)* $(, $alloc)?>
+where
+    $item: PartialEq<$other_item>,
+{
     #[inline]
-    fn ne(&self, other: &Self) -> bool {
-        self[..] != other[..]
+    fn eq(&self, $other: &$array) -> bool {
+        self[..] == *$array_slice
     }
 }
 
-#[cfg(not(feature = "const_generics"))]
-impl<A: Array, B: Array> PartialEq<SmallVec<B>> for SmallVec<A>
+impl<$($impl_generics)*, $($ord_impl_generics)* $(, $alloc: Allocator, $other_alloc: Allocator)?>
+    PartialOrd<SmallVec<$($ord_decl_generics)* $(, $other_alloc)?>>
+    for SmallVec<$($decl_generics)* $(, $alloc)?>
 where
-    A::Item: PartialEq<B::Item>,
+    $item: PartialOrd,
 {
     #[inline]
-    fn eq(&self, other: &SmallVec<B>) -> bool {
-        self[..] == other[..]
+    fn partial_cmp(&self, other: &SmallVec<$($ord_decl_generics)* $(, $other_alloc)?>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<$($impl_generics)*, $($ord_impl_generics)* $(, $alloc: Allocator)?> PartialOrd<$ord_array>
+    for SmallVec<$($decl_generics)* $(, $alloc)?>
+where
+    $item: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, $other: &$ord_array) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, $array_slice)
+    }
+}
+
+    }
+}
+
+#[cfg(all(
+    feature = "const_generics",
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+impl_cross_type_cmp!(
+    [T, const N: usize],
+    [T, { N }],
+    T,
+    [U, const M: usize],
+    [U, { M }],
+    U,
+    [U; M],
+    [const M: usize],
+    [T, { M }],
+    [T; M],
+    |other| &other[..]
+);
+#[cfg(all(
+    feature = "const_generics",
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+impl_cross_type_cmp!(
+    [T, const N: usize],
+    [T, { N }],
+    T,
+    [U, const M: usize],
+    [U, { M }],
+    U,
+    [U; M],
+    [const M: usize],
+    [T, { M }],
+    [T; M],
+    |other| &other[..],
+    AllocA,
+    AllocB
+);
+// Without const generics, arrays of any length are reached through the `Array` trait.
+#[cfg(all(
+    not(feature = "const_generics"),
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+impl_cross_type_cmp!(
+    [A: Array],
+    [A],
+    A::Item,
+    [B: Array],
+    [B],
+    B::Item,
+    B,
+    [B: Array<Item = A::Item>],
+    [B],
+    B,
+    |other| unsafe { slice::from_raw_parts(other.as_ptr(), B::size()) }
+);
+#[cfg(all(
+    not(feature = "const_generics"),
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+impl_cross_type_cmp!(
+    [A: Array],
+    [A],
+    A::Item,
+    [B: Array],
+    [B],
+    B::Item,
+    B,
+    [B: Array<Item = A::Item>],
+    [B],
+    B,
+    |other| unsafe { slice::from_raw_parts(other.as_ptr(), B::size()) },
+    AllocA,
+    AllocB
+);
+
+#[cfg(feature = "const_generics")]
+impl<T, const N: usize> SmallVec<T, { N }> {
+    /// Move all the elements of an array to the end of the vector.
+    ///
+    /// The elements are moved in with a single copy after one capacity check, so unlike
+    /// `extend`, this doesn't require `T: Clone` or iterate over the array.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<String, 4> = SmallVec::new();
+    /// v.extend_from_array([String::from("a"), String::from("b")]);
+    /// assert_eq!(&*v, &["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn extend_from_array<const M: usize>(&mut self, array: [T; M]) {
+        self.reserve(M);
+        let array = mem::ManuallyDrop::new(array);
+        unsafe {
+            let (ptr, len_ptr, _) = self.triple_mut();
+            ptr::copy_nonoverlapping(array.as_ptr(), ptr.add(len_ptr.get()), M);
+            len_ptr.set(len_ptr.get() + M);
+        }
+    }
+
+    /// Move the elements into an array of length `M`, whether they are inline or spilled.
+    /// Otherwise, if `len() != M`, return `Err(Self)`.
+    #[inline]
+    pub fn try_into_array<const M: usize>(mut self) -> Result<[T; M], Self> {
+        if self.len() != M {
+            return Err(self);
+        }
+        unsafe {
+            self.set_len(0);
+            Ok(ptr::read(self.as_ptr() as *const [T; M]))
+        }
+    }
+}
+
+#[cfg(not(feature = "const_generics"))]
+impl<A: Array> SmallVec<A> {
+    /// Move all the elements of an array to the end of the vector.
+    ///
+    /// The elements are moved in with a single copy after one capacity check, so unlike
+    /// `extend`, this doesn't require `A::Item: Clone` or iterate over the array.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[String; 4]> = SmallVec::new();
+    /// v.extend_from_array([String::from("a"), String::from("b")]);
+    /// assert_eq!(&*v, &["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn extend_from_array<B: Array<Item = A::Item>>(&mut self, array: B) {
+        self.reserve(B::size());
+        let array = mem::ManuallyDrop::new(array);
+        unsafe {
+            let (ptr, len_ptr, _) = self.triple_mut();
+            ptr::copy_nonoverlapping(array.as_ptr(), ptr.add(len_ptr.get()), B::size());
+            len_ptr.set(len_ptr.get() + B::size());
+        }
+    }
+
+    /// Move the elements into an array of length `B::size()`, whether they are inline or
+    /// spilled. Otherwise, if `len() != B::size()`, return `Err(Self)`.
+    ///
+    /// Unlike [`into_inner`](#method.into_inner), the length doesn't have to be the inline
+    /// capacity.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let v: SmallVec<[u8; 2]> = SmallVec::from_slice(&[1, 2, 3]);
+    /// let v = v.try_into_array::<[u8; 4]>().unwrap_err();
+    /// assert_eq!(v.try_into_array::<[u8; 3]>(), Ok([1, 2, 3]));
+    /// ```
+    #[inline]
+    pub fn try_into_array<B: Array<Item = A::Item>>(mut self) -> Result<B, Self> {
+        if self.len() != B::size() {
+            return Err(self);
+        }
+        // Like the inline storage, `B` holds its items contiguously from its start.
+        unsafe {
+            self.set_len(0);
+            Ok(ptr::read(self.as_ptr() as *const B))
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<T, const N: usize, const M: usize> TryFrom<SmallVec<T, { N }>> for [T; M] {
+    type Error = SmallVec<T, { N }>;
+
+    #[inline]
+    fn try_from(vec: SmallVec<T, { N }>) -> Result<Self, Self::Error> {
+        vec.try_into_array()
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<T, U, const N: usize> SmallVec<(T, U), { N }> {
+    /// Split a vector of pairs into a vector of the first elements and a vector of the second
+    /// ones, in a single pass. Both are allocated with the final length up front.
+    pub fn unzip(self) -> (SmallVec<T, { N }>, SmallVec<U, { N }>) {
+        let len = self.len();
+        let mut left = SmallVec::with_capacity(len);
+        let mut right = SmallVec::with_capacity(len);
+        for (t, u) in self {
+            left.push(t);
+            right.push(u);
+        }
+        (left, right)
+    }
+}
+
+#[cfg(not(feature = "const_generics"))]
+impl<T, U, A: Array<Item = (T, U)>> SmallVec<A> {
+    /// Split a vector of pairs into a vector of the first elements and a vector of the second
+    /// ones, in a single pass. Both are allocated with the final length up front.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let pairs: SmallVec<[(u8, char); 4]> = SmallVec::from_slice(&[(1, 'a'), (2, 'b')]);
+    /// let (numbers, letters): (SmallVec<[u8; 4]>, SmallVec<[char; 4]>) = pairs.unzip();
+    /// assert_eq!(&*numbers, &[1, 2]);
+    /// assert_eq!(&*letters, &['a', 'b']);
+    /// ```
+    pub fn unzip<B: Array<Item = T>, C: Array<Item = U>>(self) -> (SmallVec<B>, SmallVec<C>) {
+        let len = self.len();
+        let mut left = SmallVec::with_capacity(len);
+        let mut right = SmallVec::with_capacity(len);
+        for (t, u) in self {
+            left.push(t);
+            right.push(u);
+        }
+        (left, right)
+    }
+}
+
+// Formatted text is appended as UTF-8. Running out of memory, or of inline capacity without the
+// `alloc` feature, is reported as `fmt::Error` instead of panicking.
+#[cfg(feature = "const_generics")]
+impl<const N: usize> fmt::Write for SmallVec<u8, { N }> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_extend_from_slice(s.as_bytes())
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(not(feature = "const_generics"))]
+impl<A: Array<Item = u8>> fmt::Write for SmallVec<A> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_extend_from_slice(s.as_bytes())
+            .map_err(|_| fmt::Error)
     }
 }
 
@@ -1233,6 +3143,16 @@ impl<const N: usize> io::Write for SmallVec<u8, { N }> {
         Ok(())
     }
 
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend_from_slice(buf);
+        }
+        Ok(len)
+    }
+
     #[inline]
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
@@ -1253,13 +3173,39 @@ impl<A: Array<Item = u8>> io::Write for SmallVec<A> {
         Ok(())
     }
 
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend_from_slice(buf);
+        }
+        Ok(len)
+    }
+
     #[inline]
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
 }
 
-#[cfg(feature = "const_generics")]
-create_with_parts!(<T, {const} N: usize>, <T, {N}>, [T; N], T, N);
-#[cfg(not(feature = "const_generics"))]
-create_with_parts!(<A: Array>, <A>, A, A::Item, A::size());
+#[cfg(all(
+    feature = "const_generics",
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, [Alloc = Global], Alloc, [T; N], T, N);
+#[cfg(all(
+    feature = "const_generics",
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, [], Global, [T; N], T, N);
+#[cfg(all(
+    not(feature = "const_generics"),
+    any(feature = "allocator_api", feature = "allocator-api2")
+))]
+create_with_parts!(<A: Array>, <A>, [Alloc = Global], Alloc, A, A::Item, A::size());
+#[cfg(all(
+    not(feature = "const_generics"),
+    not(any(feature = "allocator_api", feature = "allocator-api2"))
+))]
+create_with_parts!(<A: Array>, <A>, [], Global, A, A::Item, A::size());
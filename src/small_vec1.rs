@@ -0,0 +1,280 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{utils::index_out_of_bounds, IntoIter, SmallVec};
+use core::{
+    convert::TryFrom,
+    fmt::{self, Debug},
+    hint::unreachable_unchecked,
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A `SmallVec` that always holds at least one element.
+///
+/// The length can only shrink down to one, so `first` and `last` don't need to return an
+/// `Option`. The elements are available as a slice through `Deref`, and the underlying vector
+/// through `as_smallvec`/`into_smallvec`.
+///
+/// ```rust
+/// use smallvec::SmallVec1;
+///
+/// let mut v: SmallVec1<[u8; 4]> = SmallVec1::new(1);
+/// v.push(2);
+/// assert_eq!(v.pop(), Some(2));
+/// assert_eq!(v.pop(), None);
+/// assert_eq!(*v.first(), 1);
+/// assert_eq!(*v.last(), 1);
+/// ```
+pub struct SmallVec1<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct a vector holding a single element
+    #[inline]
+    pub fn new(first: $array_item) -> Self {
+        let mut vec = SmallVec::new();
+        vec.push(first);
+        SmallVec1 { vec }
+    }
+
+    /// Wrap a `SmallVec`, or give it back as `Err` if it is empty.
+    #[inline]
+    pub fn from_smallvec(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Result<Self, SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>> {
+        if vec.is_empty() {
+            Err(vec)
+        } else {
+            Ok(SmallVec1 { vec })
+        }
+    }
+
+    /// Returns a reference to the underlying `SmallVec`.
+    #[inline]
+    pub fn as_smallvec(&self) -> &SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        &self.vec
+    }
+
+    /// Unwrap the underlying `SmallVec`.
+    #[inline]
+    pub fn into_smallvec(self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.vec
+    }
+
+    /// Returns a reference to the first element.
+    #[inline]
+    pub fn first(&self) -> &$array_item {
+        self.vec.first().unwrap_or_else(|| unsafe { unreachable_unchecked() })
+    }
+
+    /// Returns a mutable reference to the first element.
+    #[inline]
+    pub fn first_mut(&mut self) -> &mut $array_item {
+        self.vec.first_mut().unwrap_or_else(|| unsafe { unreachable_unchecked() })
+    }
+
+    /// Returns a reference to the last element.
+    #[inline]
+    pub fn last(&self) -> &$array_item {
+        self.vec.last().unwrap_or_else(|| unsafe { unreachable_unchecked() })
+    }
+
+    /// Returns a mutable reference to the last element.
+    #[inline]
+    pub fn last_mut(&mut self) -> &mut $array_item {
+        self.vec.last_mut().unwrap_or_else(|| unsafe { unreachable_unchecked() })
+    }
+
+    /// Returns `true` if the data has spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.vec.spilled()
+    }
+
+    /// The number of items the vector can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Append an item to the vector.
+    #[inline]
+    pub fn push(&mut self, value: $array_item) {
+        self.vec.push(value);
+    }
+
+    /// Remove an item from the end of the vector and return it, or `None` if it is the only
+    /// one left.
+    #[inline]
+    pub fn pop(&mut self) -> Option<$array_item> {
+        if self.vec.len() == 1 {
+            None
+        } else {
+            self.vec.pop()
+        }
+    }
+
+    /// Insert an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, element: $array_item) {
+        self.vec.insert(index, element);
+    }
+
+    /// Remove and return the element at position `index`, shifting all elements after it to the
+    /// left, or return `None` if it is the only one left.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> Option<$array_item> {
+        if index >= self.vec.len() {
+            index_out_of_bounds("remove", index, self.vec.len());
+        }
+        if self.vec.len() == 1 {
+            None
+        } else {
+            Some(self.vec.remove(index))
+        }
+    }
+
+    /// Shorten the vector, keeping the first `len` elements and dropping the rest. A `len` of
+    /// zero keeps the first element.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(if len == 0 { 1 } else { len });
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallVec1 {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.vec.fmt(f)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Target = [$array_item];
+    #[inline]
+    fn deref(&self) -> &[$array_item] {
+        &self.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DerefMut
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [$array_item] {
+        &mut self.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        self.vec.extend(iterable)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn from(vec: SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        vec.vec
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> TryFrom<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Error = SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>;
+
+    #[inline]
+    fn try_from(vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Result<Self, Self::Error> {
+        Self::from_smallvec(vec)
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = IntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::Iter<'a, $array_item>;
+    type Item = &'a $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a mut SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = slice::IterMut<'a, $array_item>;
+    type Item = &'a mut $array_item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for SmallVec1<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq {}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
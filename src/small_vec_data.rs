@@ -1,105 +1,142 @@
 #[cfg(not(feature = "const_generics"))]
 use crate::Array;
-use core::{mem::MaybeUninit, ptr::NonNull};
+#[cfg(feature = "u32_len")]
+use core::num::NonZeroU32;
+#[cfg(not(feature = "u32_len"))]
+use core::num::NonZeroUsize;
+use core::{
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr::NonNull,
+};
 
-macro_rules! create_with_parts {
-(
-    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
-    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
-    $array:ty,
-    $array_item:ty
-) => {
+/// A `usize` that is never more than `NonMaxUsize::MAX`, stored plus one so that zero is left as
+/// a niche.
+///
+/// Lengths and capacities of vectors are stored this way, which lets `Option<SmallVec>` use the
+/// niche and be as small as `SmallVec`. With the `u32_len` feature they are stored in a `u32`.
+#[derive(Clone, Copy)]
+pub struct NonMaxUsize(
+    #[cfg(not(feature = "u32_len"))] NonZeroUsize,
+    #[cfg(feature = "u32_len")] NonZeroU32,
+);
 
-#[cfg(feature = "union")]
-pub union SmallVecData<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
-    inline: MaybeUninit<$array>,
-    heap: (NonNull<$array_item>, usize),
-}
+impl NonMaxUsize {
+    /// The largest length or capacity of a vector.
+    #[cfg(not(feature = "u32_len"))]
+    pub const MAX: usize = usize::MAX - 1;
+    /// The largest length or capacity of a vector.
+    #[cfg(feature = "u32_len")]
+    pub const MAX: usize = u32::MAX as usize - 1;
 
-#[cfg(feature = "union")]
-impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVecData<$s_decl_ty$(, {$s_decl_const_ty})?> {
-    #[inline]
-    pub unsafe fn inline(&self) -> *const $array_item {
-        (*self.inline.as_ptr()).as_ptr()
-    }
     #[inline]
-    pub unsafe fn inline_mut(&mut self) -> *mut $array_item {
-        (*self.inline.as_mut_ptr()).as_mut_ptr()
+    pub const fn new(value: usize) -> Self {
+        // Capacities are kept below `MAX` when growing, and lengths can't exceed them. Only the
+        // capacity of zero-sized elements, which own no buffer, may be clamped here.
+        let value = if value > Self::MAX { Self::MAX } else { value };
+        #[cfg(not(feature = "u32_len"))]
+        unsafe {
+            NonMaxUsize(NonZeroUsize::new_unchecked(value + 1))
+        }
+        #[cfg(feature = "u32_len")]
+        unsafe {
+            NonMaxUsize(NonZeroU32::new_unchecked(value as u32 + 1))
+        }
     }
+    #[cfg(not(feature = "u32_len"))]
     #[inline]
-    pub fn from_inline(inline: MaybeUninit<$array>) -> Self {
-        SmallVecData { inline }
+    pub const fn get(self) -> usize {
+        self.0.get() - 1
     }
+    #[cfg(feature = "u32_len")]
     #[inline]
-    pub unsafe fn into_inline(self) -> $array {
-        self.inline.assume_init()
+    pub const fn get(self) -> usize {
+        self.0.get() as usize - 1
     }
     #[inline]
-    pub unsafe fn heap(&self) -> (*mut $array_item, usize) {
-        (self.heap.0.as_ptr(), self.heap.1)
+    pub fn set(&mut self, value: usize) {
+        *self = NonMaxUsize::new(value);
     }
+}
+
+/// The pointer and length of a spilled vector.
+///
+/// With the `u32_len` feature they are packed in 12 bytes on 64-bit targets instead of 16, so that
+/// the capacity beside them doesn't need padding of its own.
+#[cfg_attr(feature = "u32_len", repr(C, packed(4)))]
+pub struct Heap<T> {
+    ptr: NonNull<T>,
+    len: NonMaxUsize,
+}
+
+impl<T> Clone for Heap<T> {
     #[inline]
-    pub unsafe fn heap_mut(&mut self) -> (*mut $array_item, &mut usize) {
-        (self.heap.0.as_ptr(), &mut self.heap.1)
-    }
-    #[inline]
-    pub fn from_heap(ptr: *mut $array_item, len: usize) -> Self {
-        SmallVecData {
-            heap: (NonNull::new(ptr).unwrap(), len),
-        }
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
-#[cfg(not(feature = "union"))]
-pub enum SmallVecData<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
-    Inline(MaybeUninit<$array>),
-    Heap((NonNull<$array_item>, usize)),
+impl<T> Copy for Heap<T> {}
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array:ty,
+    $array_item:ty
+) => {
+
+// Which field is active is only told by the capacity of the `SmallVec`, there is no tag: the inline
+// buffer overlaps the heap pointer and length, and reading the length or the pointer of either
+// variant is a select rather than a branch. The elements are dropped by `SmallVec`, so the inline
+// buffer doesn't need drop glue of its own.
+pub union SmallVecData<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    inline: ManuallyDrop<MaybeUninit<$array>>,
+    heap: Heap<$array_item>,
 }
 
-#[cfg(not(feature = "union"))]
 impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVecData<$s_decl_ty$(, {$s_decl_const_ty})?> {
     #[inline]
     pub unsafe fn inline(&self) -> *const $array_item {
-        match *self {
-            SmallVecData::Inline(ref a) => (*a.as_ptr()).as_ptr(),
-            _ => debug_unreachable!(),
-        }
+        self.inline.as_ptr() as *const $array_item
     }
     #[inline]
     pub unsafe fn inline_mut(&mut self) -> *mut $array_item {
-        match *self {
-            SmallVecData::Inline(ref mut a) => (*a.as_mut_ptr()).as_mut_ptr(),
-            _ => debug_unreachable!(),
+        self.inline.as_mut_ptr() as *mut $array_item
+    }
+    #[cfg(feature = "const_new")]
+    #[inline]
+    pub const fn from_inline(inline: MaybeUninit<$array>) -> Self {
+        SmallVecData {
+            inline: ManuallyDrop::new(inline),
         }
     }
+    #[cfg(not(feature = "const_new"))]
     #[inline]
     pub fn from_inline(inline: MaybeUninit<$array>) -> Self {
-        SmallVecData::Inline(inline)
+        SmallVecData {
+            inline: ManuallyDrop::new(inline),
+        }
     }
     #[inline]
     pub unsafe fn into_inline(self) -> $array {
-        match self {
-            SmallVecData::Inline(a) => a.assume_init(),
-            _ => debug_unreachable!(),
-        }
+        ManuallyDrop::into_inner(self.inline).assume_init()
     }
     #[inline]
     pub unsafe fn heap(&self) -> (*mut $array_item, usize) {
-        match *self {
-            SmallVecData::Heap(data) => (data.0.as_ptr(), data.1),
-            _ => debug_unreachable!(),
-        }
+        (self.heap.ptr.as_ptr(), self.heap.len.get())
     }
     #[inline]
-    pub unsafe fn heap_mut(&mut self) -> (*mut $array_item, &mut usize) {
-        match *self {
-            SmallVecData::Heap(ref mut data) => (data.0.as_ptr(), &mut data.1),
-            _ => debug_unreachable!(),
-        }
+    pub unsafe fn heap_mut(&mut self) -> (*mut $array_item, &mut NonMaxUsize) {
+        (self.heap.ptr.as_ptr(), &mut self.heap.len)
     }
     #[inline]
     pub fn from_heap(ptr: *mut $array_item, len: usize) -> Self {
-        SmallVecData::Heap((NonNull::new(ptr).unwrap(), len))
+        SmallVecData {
+            heap: Heap {
+                ptr: NonNull::new(ptr).unwrap(),
+                len: NonMaxUsize::new(len),
+            },
+        }
     }
 }
 
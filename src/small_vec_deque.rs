@@ -0,0 +1,571 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::{utils::infallible, CollectionAllocErr, SmallVec};
+use core::{
+    cmp,
+    fmt::{self, Debug},
+    iter::FromIterator,
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A double-ended queue implemented as a ring buffer in the storage of a `SmallVec`, so that
+/// short queues don't allocate.
+///
+/// ```rust
+/// use smallvec::SmallVecDeque;
+///
+/// let mut queue: SmallVecDeque<[u32; 4]> = SmallVecDeque::new();
+/// queue.push_back(2);
+/// queue.push_back(3);
+/// queue.push_front(1);
+/// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+/// assert_eq!(queue.pop_front(), Some(1));
+/// assert_eq!(queue.pop_back(), Some(3));
+/// assert!(!queue.spilled());
+/// ```
+pub struct SmallVecDeque<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    // Only used for its capacity: its length stays zero, and the elements are tracked by `head`
+    // and `len`.
+    buf: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+    head: usize,
+    len: usize,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty deque
+    #[inline]
+    pub fn new() -> Self {
+        SmallVecDeque {
+            buf: SmallVec::new(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Construct an empty deque with room for at least `n` elements.
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity(n: usize) -> Self {
+        SmallVecDeque {
+            buf: SmallVec::with_capacity(n),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// The number of elements in the deque
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of elements the deque can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns `true` if the data has spilled into a separate heap-allocated buffer.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.buf.spilled()
+    }
+
+    /// The physical index of the element at `index`, which must be less than the capacity.
+    #[inline]
+    fn wrap(&self, index: usize) -> usize {
+        let tail_room = self.capacity() - self.head;
+        if index >= tail_room {
+            index - tail_room
+        } else {
+            self.head + index
+        }
+    }
+
+    /// Reserve capacity for `additional` more elements.
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        infallible(self.try_reserve(additional))
+    }
+
+    /// Reserve capacity for `additional` more elements, returning an error instead of panicking
+    /// or aborting if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        if self.capacity() - self.len >= additional {
+            return Ok(());
+        }
+        // Let the vector move the elements along with its buffer.
+        self.make_contiguous();
+        unsafe { self.buf.set_len(self.len) };
+        let result = self.buf.try_reserve(additional);
+        unsafe { self.buf.set_len(0) };
+        result
+    }
+
+    #[inline]
+    #[track_caller]
+    fn grow_if_full(&mut self) {
+        if self.len == self.capacity() {
+            self.reserve(cmp::max(self.len, 1));
+        }
+    }
+
+    /// Append an element to the back of the deque.
+    #[inline]
+    #[track_caller]
+    pub fn push_back(&mut self, value: $array_item) {
+        self.grow_if_full();
+        let index = self.wrap(self.len);
+        unsafe { ptr::write(self.buf.as_mut_ptr().add(index), value) };
+        self.len += 1;
+    }
+
+    /// Prepend an element to the front of the deque.
+    #[inline]
+    #[track_caller]
+    pub fn push_front(&mut self, value: $array_item) {
+        self.grow_if_full();
+        self.head = if self.head == 0 {
+            self.capacity() - 1
+        } else {
+            self.head - 1
+        };
+        unsafe { ptr::write(self.buf.as_mut_ptr().add(self.head), value) };
+        self.len += 1;
+    }
+
+    /// Remove the element at the back of the deque and return it, or `None` if it is empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<$array_item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let index = self.wrap(self.len);
+        unsafe { Some(ptr::read(self.buf.as_ptr().add(index))) }
+    }
+
+    /// Remove the element at the front of the deque and return it, or `None` if it is empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<$array_item> {
+        if self.len == 0 {
+            return None;
+        }
+        let head = self.head;
+        self.head = self.wrap(1);
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.buf.as_ptr().add(head))) }
+    }
+
+    /// Returns a reference to the element at `index` from the front, if there is one.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&$array_item> {
+        if index < self.len {
+            unsafe { Some(&*self.buf.as_ptr().add(self.wrap(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index` from the front, if there is one.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut $array_item> {
+        if index < self.len {
+            let index = self.wrap(index);
+            unsafe { Some(&mut *self.buf.as_mut_ptr().add(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the front element, if there is one.
+    #[inline]
+    pub fn front(&self) -> Option<&$array_item> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the front element, if there is one.
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut $array_item> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the back element, if there is one.
+    #[inline]
+    pub fn back(&self) -> Option<&$array_item> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Returns a mutable reference to the back element, if there is one.
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut $array_item> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    /// Returns the elements as two slices which, one after the other, hold them in order from
+    /// front to back. The second slice is empty when the elements don't wrap around the end of
+    /// the buffer.
+    #[inline]
+    pub fn as_slices(&self) -> (&[$array_item], &[$array_item]) {
+        let tail_room = self.capacity() - self.head;
+        let ptr = self.buf.as_ptr();
+        unsafe {
+            if self.len <= tail_room {
+                (slice::from_raw_parts(ptr.add(self.head), self.len), &[])
+            } else {
+                (
+                    slice::from_raw_parts(ptr.add(self.head), tail_room),
+                    slice::from_raw_parts(ptr, self.len - tail_room),
+                )
+            }
+        }
+    }
+
+    /// Returns the elements as two mutable slices which, one after the other, hold them in order
+    /// from front to back.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [$array_item], &mut [$array_item]) {
+        let tail_room = self.capacity() - self.head;
+        let ptr = self.buf.as_mut_ptr();
+        unsafe {
+            if self.len <= tail_room {
+                (slice::from_raw_parts_mut(ptr.add(self.head), self.len), &mut [])
+            } else {
+                (
+                    slice::from_raw_parts_mut(ptr.add(self.head), tail_room),
+                    slice::from_raw_parts_mut(ptr, self.len - tail_room),
+                )
+            }
+        }
+    }
+
+    /// Move the elements so that they are contiguous and start at the beginning of the buffer,
+    /// and return them as a slice.
+    pub fn make_contiguous(&mut self) -> &mut [$array_item] {
+        if self.head != 0 {
+            // The uninitialized slots are rotated along, which only moves their bytes around.
+            self.buf.spare_capacity_mut().rotate_left(self.head);
+            self.head = 0;
+        }
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr(), self.len) }
+    }
+
+    /// Iterate over the elements from front to back.
+    #[inline]
+    pub fn iter(&self) -> SmallVecDequeIter<'_, $array_item> {
+        let (front, back) = self.as_slices();
+        SmallVecDequeIter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Iterate over mutable references to the elements from front to back.
+    #[inline]
+    pub fn iter_mut(&mut self) -> SmallVecDequeIterMut<'_, $array_item> {
+        let (front, back) = self.as_mut_slices();
+        SmallVecDequeIterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// Remove all the elements of the deque.
+    #[inline]
+    pub fn clear(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back) = (front as *mut [$array_item], back as *mut [$array_item]);
+        self.head = 0;
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+
+    /// Convert the deque into a `SmallVec` holding its elements from front to back, moving them
+    /// to the start of the buffer first if needed.
+    pub fn into_smallvec(mut self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.make_contiguous();
+        let mut buf = core::mem::take(&mut self.buf);
+        unsafe { buf.set_len(self.len) };
+        self.len = 0;
+        buf
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Clone
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut deque = Self::with_capacity(self.len);
+        deque.extend(self.iter().cloned());
+        deque
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Eq
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Eq,
+{
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Extend<$array_item>
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn extend<I: IntoIterator<Item = $array_item>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> From<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    /// Take over the storage of the vector, without moving its elements.
+    #[inline]
+    fn from(mut buf: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) -> Self {
+        let len = buf.len();
+        unsafe { buf.set_len(0) };
+        SmallVecDeque { buf, head: 0, len }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> FromIterator<$array_item>
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn from_iter<I: IntoIterator<Item = $array_item>>(iterable: I) -> Self {
+        Self::from(iterable.into_iter().collect::<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>())
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Index<usize>
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Output = $array_item;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, index: usize) -> &$array_item {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IndexMut<usize>
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, index: usize) -> &mut $array_item {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = SmallVecDequeIntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    type Item = $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        SmallVecDequeIntoIter { deque: self }
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = SmallVecDequeIter<'a, $array_item>;
+    type Item = &'a $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> IntoIterator
+    for &'a mut SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type IntoIter = SmallVecDequeIterMut<'a, $array_item>;
+    type Item = &'a mut $array_item;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PartialEq
+    for SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+/// An iterator that moves the elements out of a `SmallVecDeque`, from front to back.
+///
+/// Returned from the `IntoIterator` implementation of `SmallVecDeque`.
+pub struct SmallVecDequeIntoIter<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    deque: SmallVecDeque<$s_decl_ty$(, {$s_decl_const_ty})?>,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Iterator
+    for SmallVecDequeIntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Item = $array_item;
+
+    #[inline]
+    fn next(&mut self) -> Option<$array_item> {
+        self.deque.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DoubleEndedIterator
+    for SmallVecDequeIntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<$array_item> {
+        self.deque.pop_back()
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> ExactSizeIterator
+    for SmallVecDequeIntoIter<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
+
+/// An iterator over the elements of a `SmallVecDeque`.
+///
+/// Returned from [`SmallVecDeque::iter`][1].
+///
+/// [1]: struct.SmallVecDeque.html#method.iter
+pub struct SmallVecDequeIter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for SmallVecDequeIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SmallVecDequeIter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SmallVecDequeIter<'a, T> {}
+
+/// An iterator over mutable references to the elements of a `SmallVecDeque`.
+///
+/// Returned from [`SmallVecDeque::iter_mut`][1].
+///
+/// [1]: struct.SmallVecDeque.html#method.iter_mut
+pub struct SmallVecDequeIterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for SmallVecDequeIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.front.next() {
+            Some(item) => Some(item),
+            None => self.back.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SmallVecDequeIterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.back.next_back() {
+            Some(item) => Some(item),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SmallVecDequeIterMut<'a, T> {}
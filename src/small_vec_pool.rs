@@ -0,0 +1,182 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug},
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A pool of spilled `SmallVec` buffers.
+///
+/// Vectors handed back to the pool are cleared, and kept for reuse if they have spilled, so a
+/// vector taken from the pool later starts with the capacity of a previous one instead of
+/// allocating again. At most `max_pooled` buffers are kept, any extra one is freed.
+///
+/// ```rust
+/// use smallvec::SmallVecPool;
+///
+/// let pool: SmallVecPool<[u8; 4]> = SmallVecPool::new(8);
+/// {
+///     let mut buf = pool.get();
+///     buf.extend_from_slice(b"spills to the heap");
+/// }
+/// assert_eq!(pool.len(), 1);
+///
+/// let buf = pool.get();
+/// assert!(buf.is_empty());
+/// assert!(buf.capacity() >= 18);
+/// ```
+pub struct SmallVecPool<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    pool: RefCell<Vec<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>>,
+    max_pooled: usize,
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVecPool<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct an empty pool that keeps at most `max_pooled` buffers
+    #[inline]
+    pub fn new(max_pooled: usize) -> Self {
+        SmallVecPool {
+            pool: RefCell::new(Vec::new()),
+            max_pooled,
+        }
+    }
+
+    /// The number of buffers currently kept in the pool
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pool.borrow().len()
+    }
+
+    /// Returns `true` if the pool keeps no buffers
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of buffers the pool keeps
+    #[inline]
+    pub fn max_pooled(&self) -> usize {
+        self.max_pooled
+    }
+
+    /// Take an empty vector out of the pool, reusing a pooled buffer if there is one.
+    #[inline]
+    pub fn take(&self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        self.pool.borrow_mut().pop().unwrap_or_default()
+    }
+
+    /// Hand a vector back to the pool. It is cleared, and its buffer is kept if it has spilled
+    /// and the pool isn't full.
+    pub fn recycle(&self, mut vec: SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>) {
+        vec.clear();
+        if vec.spilled() {
+            let mut pool = self.pool.borrow_mut();
+            if pool.len() < self.max_pooled {
+                pool.push(vec);
+            }
+        }
+    }
+
+    /// Borrow an empty vector from the pool, which is recycled when the returned guard is
+    /// dropped.
+    #[inline]
+    pub fn get(&self) -> PooledSmallVec<'_, $s_decl_ty$(, {$s_decl_const_ty})?> {
+        PooledSmallVec {
+            pool: self,
+            vec: ManuallyDrop::new(self.take()),
+        }
+    }
+
+    /// Free all the pooled buffers.
+    pub fn clear(&self) {
+        let pooled = mem::take(&mut *self.pool.borrow_mut());
+        drop(pooled);
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for SmallVecPool<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SmallVecPool")
+            .field("len", &self.len())
+            .field("max_pooled", &self.max_pooled)
+            .finish()
+    }
+}
+
+/// A vector borrowed from a `SmallVecPool`, recycled into it when dropped.
+///
+/// Returned from [`SmallVecPool::get`][1].
+///
+/// [1]: struct.SmallVecPool.html#method.get
+pub struct PooledSmallVec<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    pool: &'a SmallVecPool<$s_decl_ty$(, {$s_decl_const_ty})?>,
+    vec: ManuallyDrop<SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>>,
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> PooledSmallVec<'a, $s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Keep the vector instead of recycling it.
+    #[inline]
+    pub fn into_inner(mut self) -> SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?> {
+        let vec = unsafe { ManuallyDrop::take(&mut self.vec) };
+        mem::forget(self);
+        vec
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Debug
+    for PooledSmallVec<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (*self.vec).fmt(f)
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
+    for PooledSmallVec<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Target = SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DerefMut
+    for PooledSmallVec<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vec
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
+    for PooledSmallVec<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn drop(&mut self) {
+        let vec = unsafe { ManuallyDrop::take(&mut self.vec) };
+        self.pool.recycle(vec);
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
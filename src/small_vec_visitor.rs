@@ -1,8 +1,20 @@
 #[cfg(not(feature = "const_generics"))]
 use crate::Array;
 use crate::SmallVec;
-use core::{fmt, marker::PhantomData};
-use serde::de::{Deserialize, SeqAccess, Visitor};
+use core::{cmp, fmt, marker::PhantomData, mem};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+
+// Preallocate at most this many bytes from a sequence's `size_hint`, which comes from the input
+// and can't be trusted. Longer sequences grow as their elements are read.
+const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+/// The number of elements to reserve up front for a sequence with the given `size_hint`.
+pub(crate) fn cautious_capacity<T>(hint: Option<usize>) -> usize {
+    cmp::min(
+        hint.unwrap_or(0),
+        MAX_PREALLOC_BYTES / cmp::max(mem::size_of::<T>(), 1),
+    )
+}
 
 macro_rules! create_with_parts {
 (
@@ -11,12 +23,46 @@ macro_rules! create_with_parts {
     $array_item:ty
 ) => {
 
-#[cfg(feature = "serde")]
+/// The serde `Visitor` that builds a `SmallVec` from a sequence.
+///
+/// It is what `SmallVec`'s `Deserialize` implementation uses, and can be reused by
+/// `Deserialize` implementations of wrapper types.
+///
+/// ```rust
+/// use serde::{Deserialize, Deserializer};
+/// use smallvec::{SmallVec, SmallVecVisitor};
+///
+/// struct Path(SmallVec<[u32; 4]>);
+///
+/// impl<'de> Deserialize<'de> for Path {
+///     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+///         deserializer.deserialize_seq(SmallVecVisitor::new()).map(Path)
+///     }
+/// }
+/// ```
 pub struct SmallVecVisitor<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
     pub(crate) phantom: PhantomData<$s_decl_ty>,
 }
 
-#[cfg(feature = "serde")]
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> SmallVecVisitor<$s_decl_ty$(, {$s_decl_const_ty})?> {
+    /// Construct a visitor
+    #[inline]
+    pub fn new() -> Self {
+        SmallVecVisitor {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<$($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Default
+    for SmallVecVisitor<$s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'de, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Visitor<'de>
     for SmallVecVisitor<$s_decl_ty$(, {$s_decl_const_ty})?>
 where
@@ -32,7 +78,7 @@ where
     where
         B: SeqAccess<'de>,
     {
-        let len = seq.size_hint().unwrap_or(0);
+        let len = cautious_capacity::<$array_item>(seq.size_hint());
         let mut values = SmallVec::with_capacity(len);
 
         while let Some(value) = seq.next_element()? {
@@ -43,6 +89,71 @@ where
     }
 }
 
+
+/// A `DeserializeSeed` that deserializes a sequence by appending its elements to an existing
+/// `SmallVec`, reusing its capacity.
+///
+/// ```rust
+/// use serde::de::{
+///     value::{Error, SeqDeserializer},
+///     DeserializeSeed,
+/// };
+/// use smallvec::{AppendSeed, SmallVec};
+///
+/// let mut records: SmallVec<[u32; 8]> = SmallVec::new();
+/// for input in vec![vec![1, 2], vec![3]] {
+///     let de = SeqDeserializer::<_, Error>::new(input.into_iter());
+///     AppendSeed(&mut records).deserialize(de).unwrap();
+/// }
+/// assert_eq!(&*records, &[1, 2, 3]);
+/// ```
+///
+/// If deserialization fails part way, the elements decoded so far stay in the vector.
+pub struct AppendSeed<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*>(
+    pub &'a mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+);
+
+impl<'a, 'de, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DeserializeSeed<'de>
+    for AppendSeed<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Visitor<'de>
+    for AppendSeed<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+where
+    $array_item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<B>(self, mut seq: B) -> Result<(), B::Error>
+    where
+        B: SeqAccess<'de>,
+    {
+        self.0
+            .reserve(cautious_capacity::<$array_item>(seq.size_hint()));
+
+        while let Some(value) = seq.next_element()? {
+            self.0.push(value);
+        }
+
+        Ok(())
+    }
+}
+
     }
 }
 
@@ -0,0 +1,42 @@
+use core::cmp::Ordering;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// Floating-point element types, which can be sorted with
+/// [`SmallVec::sort_unstable_by_total_cmp`][1] and [`SmallVec::sort_floats_unstable`][2].
+///
+/// This trait is sealed and implemented for `f32` and `f64`.
+///
+/// [1]: struct.SmallVec.html#method.sort_unstable_by_total_cmp
+/// [2]: struct.SmallVec.html#method.sort_floats_unstable
+pub trait SortableFloat: Copy + sealed::Sealed {
+    /// The IEEE 754 `totalOrder` of the two values.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    /// Returns `true` if the value is a NaN.
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_sortable_float {
+    ($($ty:ty),*) => {
+        $(
+            impl SortableFloat for $ty {
+                #[inline]
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    <$ty>::total_cmp(self, other)
+                }
+
+                #[inline]
+                fn is_nan(self) -> bool {
+                    <$ty>::is_nan(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_sortable_float!(f32, f64);
@@ -0,0 +1,40 @@
+use core::ptr;
+
+/// `Extend` with specialized implementations for iterators whose items can be written in bulk.
+pub trait SpecExtend<T, I> {
+    fn spec_extend(&mut self, iter: I);
+}
+
+/// Appending `n` clones of a value, without cloning one by one for `Copy` types.
+pub trait SpecExtendWith<T> {
+    fn spec_extend_with(&mut self, n: usize, value: T);
+}
+
+/// Writing `n` copies of a value, as a single `memset` for bytes.
+pub trait SpecFill: Copy {
+    /// `dst` must be valid for writing `n` values.
+    unsafe fn spec_fill(dst: *mut Self, n: usize, value: Self);
+}
+
+impl<T: Copy> SpecFill for T {
+    #[inline]
+    default unsafe fn spec_fill(dst: *mut T, n: usize, value: T) {
+        for i in 0..n {
+            ptr::write(dst.add(i), value);
+        }
+    }
+}
+
+impl SpecFill for u8 {
+    #[inline]
+    unsafe fn spec_fill(dst: *mut u8, n: usize, value: u8) {
+        ptr::write_bytes(dst, value, n);
+    }
+}
+
+impl SpecFill for i8 {
+    #[inline]
+    unsafe fn spec_fill(dst: *mut i8, n: usize, value: i8) {
+        ptr::write_bytes(dst, value as u8, n);
+    }
+}
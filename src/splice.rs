@@ -0,0 +1,141 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::vec::Vec;
+use core::ptr;
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*],
+    $array_item:ty
+) => {
+
+/// An iterator that removes a range of items from a `SmallVec`, yields them by value, and
+/// replaces them with the items of another iterator when dropped.
+///
+/// Returned from [`SmallVec::splice`][1].
+///
+/// [1]: struct.SmallVec.html#method.splice
+pub struct Splice<'a, I: Iterator<Item = $array_item>, $($impl_generics)*> {
+    // The length of the vector only covers the elements before the range while the iterator
+    // is alive.
+    pub(crate) vec: &'a mut SmallVec<$($decl_generics)*>,
+    pub(crate) replace_with: I,
+    // The removed elements that haven't been yielded yet are stored at `front..back`.
+    pub(crate) front: usize,
+    pub(crate) back: usize,
+    pub(crate) tail_start: usize,
+    pub(crate) tail_len: usize,
+}
+
+impl<'a, I: Iterator<Item = $array_item>, $($impl_generics)*> Iterator
+    for Splice<'a, I, $($decl_generics)*>
+{
+    type Item = $array_item;
+
+    #[inline]
+    fn next(&mut self) -> Option<$array_item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        unsafe { Some(ptr::read(self.vec.triple_mut().0.add(self.front - 1))) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, I: Iterator<Item = $array_item>, $($impl_generics)*> DoubleEndedIterator
+    for Splice<'a, I, $($decl_generics)*>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<$array_item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        unsafe { Some(ptr::read(self.vec.triple_mut().0.add(self.back))) }
+    }
+}
+
+impl<'a, I: Iterator<Item = $array_item>, $($impl_generics)*> ExactSizeIterator
+    for Splice<'a, I, $($decl_generics)*>
+{
+}
+
+impl<'a, I: Iterator<Item = $array_item>, $($impl_generics)*> Drop
+    for Splice<'a, I, $($decl_generics)*>
+{
+    fn drop(&mut self) {
+        // Moves the tail back next to the elements before it, including the replacement items
+        // written so far, even if dropping a removed element or the replacement iterator panics.
+        struct MoveTail<'r, 'a, I: Iterator<Item = $array_item>, $($impl_generics)*>(
+            &'r mut Splice<'a, I, $($decl_generics)*>,
+        );
+
+        impl<'r, 'a, I: Iterator<Item = $array_item>, $($impl_generics)*> Drop
+            for MoveTail<'r, 'a, I, $($decl_generics)*>
+        {
+            fn drop(&mut self) {
+                let splice = &mut *self.0;
+                unsafe {
+                    let (ptr, len_ptr, _) = splice.vec.triple_mut();
+                    let len = len_ptr.get();
+                    if splice.tail_len > 0 && splice.tail_start != len {
+                        ptr::copy(ptr.add(splice.tail_start), ptr.add(len), splice.tail_len);
+                    }
+                    len_ptr.set(len + splice.tail_len);
+                }
+            }
+        }
+
+        let guard = MoveTail(self);
+        guard.0.by_ref().for_each(drop);
+
+        // Fill the gap left by the removed elements.
+        unsafe {
+            loop {
+                let (ptr, len_ptr, _) = guard.0.vec.triple_mut();
+                if len_ptr.get() == guard.0.tail_start {
+                    break;
+                }
+                match guard.0.replace_with.next() {
+                    Some(item) => {
+                        ptr::write(ptr.add(len_ptr.get()), item);
+                        len_ptr.set(len_ptr.get() + 1);
+                    }
+                    None => return,
+                }
+            }
+        }
+
+        // Make room for the replacement items that didn't fit in the gap.
+        let mut rest: Vec<$array_item> = guard.0.replace_with.by_ref().collect();
+        drop(guard);
+        if rest.is_empty() {
+            return;
+        }
+        unsafe {
+            let index = self.vec.len() - self.tail_len;
+            self.vec.reserve(rest.len());
+            let (ptr, len_ptr, _) = self.vec.triple_mut();
+            ptr::copy(ptr.add(index), ptr.add(index + rest.len()), self.tail_len);
+            ptr::copy_nonoverlapping(rest.as_ptr(), ptr.add(index), rest.len());
+            len_ptr.set(len_ptr.get() + rest.len());
+            rest.set_len(0);
+        }
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([T, const N: usize], [T, { N }], T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array], [A], A::Item);
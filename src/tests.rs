@@ -1,5 +1,12 @@
 use crate::SmallVec;
-use alloc::{borrow::ToOwned, boxed::Box, rc::Rc, vec, vec::Vec};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::iter::FromIterator;
 
 macro_rules! create_smallvec {
@@ -15,8 +22,17 @@ macro_rules! create_smallvec {
         #[cfg(not(feature = "const_generics"))]
         let mut $var_name: SmallVec<[$data_ty; $data_value]> = $smallvec;
     };
+    (type $alias:ident = SmallVec($data_ty:ty, $data_value:expr)) => {
+        #[cfg(feature = "const_generics")]
+        type $alias = SmallVec<$data_ty, $data_value>;
+        #[cfg(not(feature = "const_generics"))]
+        type $alias = SmallVec<[$data_ty; $data_value]>;
+    };
 }
 
+mod aliasing;
+mod model;
+
 #[test]
 pub fn test_zero() {
     create_smallvec!(let mut v: SmallVec(_, 0) = SmallVec::new());
@@ -112,26 +128,157 @@ fn test_with_capacity() {
 fn drain() {
     create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
     v.push(3);
-    assert_eq!(v.drain().collect::<Vec<_>>(), &[3]);
+    assert_eq!(v.drain(..).collect::<Vec<_>>(), &[3]);
 
     // spilling the vec
     v.push(3);
     v.push(4);
     v.push(5);
-    assert_eq!(v.drain().collect::<Vec<_>>(), &[3, 4, 5]);
+    assert_eq!(v.drain(..).collect::<Vec<_>>(), &[3, 4, 5]);
 }
 
 #[test]
 fn drain_rev() {
     create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
     v.push(3);
-    assert_eq!(v.drain().rev().collect::<Vec<_>>(), &[3]);
+    assert_eq!(v.drain(..).rev().collect::<Vec<_>>(), &[3]);
 
     // spilling the vec
     v.push(3);
     v.push(4);
     v.push(5);
-    assert_eq!(v.drain().rev().collect::<Vec<_>>(), &[5, 4, 3]);
+    assert_eq!(v.drain(..).rev().collect::<Vec<_>>(), &[5, 4, 3]);
+}
+
+#[test]
+fn drain_range() {
+    for &inline in &[false, true] {
+        create_smallvec!(let mut v: SmallVec(String, 8) = SmallVec::new());
+        v.extend((0..6u8).map(|i| i.to_string()));
+        if !inline {
+            v.reserve(10);
+        }
+        assert_eq!(v.drain(1..3).collect::<Vec<_>>(), &["1", "2"]);
+        assert_eq!(&*v, &["0", "3", "4", "5"]);
+
+        let mut drain = v.drain(1..=2);
+        assert_eq!(drain.next_back().as_deref(), Some("4"));
+        drop(drain);
+        assert_eq!(&*v, &["0", "5"]);
+
+        assert_eq!(v.drain(2..).count(), 0);
+        assert_eq!(v.drain(..1).collect::<Vec<_>>(), &["0"]);
+        assert_eq!(&*v, &["5"]);
+        assert_eq!(v.spilled(), !inline);
+    }
+
+    // A leaked iterator leaves the elements before the range.
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2, 3, 4]));
+    core::mem::forget(v.drain(1..2));
+    assert_eq!(&*v, &[1]);
+}
+
+#[test]
+#[should_panic(expected = "range end (is 3) should be <= len (is 2)")]
+fn drain_range_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2]));
+    v.drain(1..3);
+}
+
+#[test]
+#[should_panic(expected = "range starts at 2 but ends at 1")]
+#[allow(clippy::reversed_empty_ranges)]
+fn drain_range_reversed() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2]));
+    v.drain(2..1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn drain_range_panicking_drop() {
+    struct PanicOnDrop(u8, Rc<Cell<usize>>);
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+            if self.0 == 2 {
+                panic!("drop");
+            }
+        }
+    }
+
+    use core::cell::Cell;
+    let dropped = Rc::new(Cell::new(0));
+    create_smallvec!(let mut v: SmallVec(PanicOnDrop, 2) = SmallVec::new());
+    v.extend((0..6).map(|i| PanicOnDrop(i, dropped.clone())));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.drain(1..4);
+    }));
+    assert!(result.is_err());
+    // The elements after the panicking one were still dropped, and the tail moved back.
+    assert_eq!(dropped.get(), 3);
+    assert_eq!(v.iter().map(|e| e.0).collect::<Vec<_>>(), &[0, 4, 5]);
+}
+
+#[test]
+fn splice() {
+    create_smallvec!(let mut v: SmallVec(String, 4) = SmallVec::new());
+    v.extend((0..4u8).map(|i| i.to_string()));
+    let new = || ["a", "b", "c"].iter().map(|s| s.to_string());
+
+    // More replacement items than removed elements, spilling the vector.
+    let removed: Vec<String> = v.splice(1..2, new()).collect();
+    assert_eq!(removed, &["1"]);
+    assert_eq!(&*v, &["0", "a", "b", "c", "2", "3"]);
+    assert!(v.spilled());
+
+    // Fewer, without consuming the iterator.
+    v.splice(1..5, new().take(1));
+    assert_eq!(&*v, &["0", "a", "3"]);
+
+    // As many, at the end.
+    let mut splice = v.splice(1.., new().take(2));
+    assert_eq!(splice.next_back().as_deref(), Some("3"));
+    drop(splice);
+    assert_eq!(&*v, &["0", "a", "b"]);
+
+    // Nothing removed.
+    assert_eq!(v.splice(..0, new()).count(), 0);
+    assert_eq!(&*v, &["a", "b", "c", "0", "a", "b"]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn splice_panicking_replacement() {
+    struct Panicking(u8);
+
+    impl Iterator for Panicking {
+        type Item = String;
+
+        fn next(&mut self) -> Option<String> {
+            self.0 += 1;
+            if self.0 == 3 {
+                panic!("replacement");
+            }
+            Some(self.0.to_string())
+        }
+    }
+
+    for &gap in &[1, 4] {
+        create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+        v.extend(["a", "b", "c", "d", "e"].iter().map(|s| s.to_string()));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.splice(0..gap, Panicking(0));
+        }));
+        assert!(result.is_err());
+        // The replacement items written before the panic are kept along with the tail.
+        let expected: &[&str] = if gap == 1 {
+            &["1", "b", "c", "d", "e"]
+        } else {
+            &["1", "2", "e"]
+        };
+        assert_eq!(&*v, expected);
+    }
 }
 
 #[test]
@@ -254,6 +401,7 @@ fn test_truncate() {
 }
 
 #[test]
+#[allow(clippy::map_clone)]
 fn test_insert_many() {
     create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::new());
     for x in 0..4 {
@@ -282,6 +430,7 @@ impl<T: Iterator> Iterator for MockHintIter<T> {
 }
 
 #[test]
+#[allow(clippy::map_clone)]
 fn test_insert_many_short_hint() {
     create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::new());
     for x in 0..4 {
@@ -302,6 +451,7 @@ fn test_insert_many_short_hint() {
 }
 
 #[test]
+#[allow(clippy::map_clone)]
 fn test_insert_many_long_hint() {
     create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::new());
     for x in 0..4 {
@@ -321,6 +471,47 @@ fn test_insert_many_long_hint() {
     );
 }
 
+#[test]
+fn test_insert_many_long_hint_spills() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[0, 1, 2, 3]));
+    v.insert_many(1, MockHintIter { x: 10..16, hint: 2 });
+    assert!(v.spilled());
+    assert_eq!(&*v, &[0, 10, 11, 12, 13, 14, 15, 1, 2, 3]);
+
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[0, 1]));
+    v.insert_many(1, 10..20);
+    assert_eq!(&*v, &[0, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 1]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_insert_many_panic_keeps_tail() {
+    struct PanicAfter(u8);
+    impl Iterator for PanicAfter {
+        type Item = Rc<u8>;
+        fn next(&mut self) -> Option<Rc<u8>> {
+            if self.0 == 0 {
+                panic!();
+            }
+            self.0 -= 1;
+            Some(Rc::new(self.0))
+        }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (4, Some(4))
+        }
+    }
+
+    let tail = Rc::new(9);
+    create_smallvec!(let mut v: SmallVec(Rc<u8>, 2) = SmallVec::from_elem(tail.clone(), 3));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.insert_many(1, PanicAfter(2));
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.iter().map(|x| **x).collect::<Vec<_>>(), [9, 1, 0, 9, 9]);
+    drop(v);
+    assert_eq!(Rc::strong_count(&tail), 1);
+}
+
 #[cfg(feature = "std")]
 #[test]
 // https://github.com/servo/rust-smallvec/issues/96
@@ -370,6 +561,7 @@ fn test_invalid_grow() {
 }
 
 #[test]
+#[allow(clippy::map_clone)]
 fn test_insert_from_slice() {
     create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::new());
     for x in 0..4 {
@@ -384,6 +576,7 @@ fn test_insert_from_slice() {
 }
 
 #[test]
+#[allow(clippy::map_clone)]
 fn test_extend_from_slice() {
     create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::new());
     for x in 0..4 {
@@ -455,6 +648,7 @@ fn test_ord() {
 
 #[cfg(feature = "std")]
 #[test]
+#[allow(clippy::unit_cmp)]
 fn test_hash() {
     use std::{collections::hash_map::DefaultHasher, hash::Hash};
 
@@ -572,7 +766,7 @@ fn test_from_slice() {
 fn test_exact_size_iterator() {
     create_smallvec!(let mut v: SmallVec(u32, 2) = SmallVec::from(&[1, 2, 3][..]));
     assert_eq!(v.clone().into_iter().len(), 3);
-    assert_eq!(v.drain().len(), 3);
+    assert_eq!(v.drain(..).len(), 3);
 }
 
 #[test]
@@ -584,6 +778,26 @@ fn shrink_to_fit_unspill() {
     assert!(!v.spilled(), "shrink_to_fit will un-spill if possible");
 }
 
+#[test]
+fn test_shrink_to() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::with_capacity(10));
+    v.extend(0..3);
+    v.shrink_to(20);
+    assert_eq!(v.capacity(), 10);
+    v.shrink_to(5);
+    assert_eq!(v.capacity(), 5);
+    v.shrink_to(0);
+    assert_eq!(v.capacity(), 3);
+    v.pop();
+    v.shrink_to(3);
+    assert_eq!(v.capacity(), 3);
+    v.shrink_to(1);
+    assert!(!v.spilled());
+    assert_eq!(&*v, &[0, 1]);
+    v.shrink_to(0);
+    assert_eq!(v.capacity(), 2);
+}
+
 #[test]
 fn test_into_vec() {
     create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::from_iter(0..2));
@@ -673,6 +887,89 @@ fn test_retain() {
     assert_eq!(Rc::strong_count(&one), 1);
 }
 
+#[test]
+fn test_extract_if() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..7u8).map(|i| i.to_string()));
+    let extracted: Vec<String> = v
+        .extract_if(|s| s.parse::<u8>().unwrap() % 3 == 0)
+        .collect();
+    assert_eq!(extracted, &["0", "3", "6"]);
+    assert_eq!(&*v, &["1", "2", "4", "5"]);
+
+    // Stopping early keeps the elements that weren't checked.
+    let mut iter = v.extract_if(|s| {
+        s.push('!');
+        true
+    });
+    assert_eq!(iter.next().as_deref(), Some("1!"));
+    drop(iter);
+    assert_eq!(&*v, &["2", "4", "5"]);
+
+    core::mem::forget(v.extract_if(|_| true));
+    assert!(v.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_extract_if_panicking_predicate() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..5u8).map(|i| i.to_string()));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.extract_if(|s| match &**s {
+            "3" => panic!("predicate"),
+            s => s == "1",
+        })
+        .count()
+    }));
+    assert!(result.is_err());
+    assert_eq!(&*v, &["0", "2", "3", "4"]);
+}
+
+#[test]
+fn test_retain_top_k() {
+    create_smallvec!(let mut v: SmallVec(u32, 4) = SmallVec::from_slice(&[7, 3, 9, 1, 8, 2]));
+    v.retain_top_k(3, |a, b| a.cmp(b));
+    v.sort_unstable();
+    assert_eq!(&*v, &[1, 2, 3]);
+
+    v.retain_top_k(5, |a, b| a.cmp(b));
+    assert_eq!(v.len(), 3);
+
+    let one = Rc::new(1);
+    create_smallvec!(let mut v: SmallVec(Rc<i32>, 2) = SmallVec::new());
+    v.push(Rc::new(2));
+    v.push(Rc::clone(&one));
+    v.push(Rc::new(3));
+    v.retain_top_k(1, |a, b| b.cmp(a));
+    assert_eq!(*v[0], 3);
+    assert_eq!(Rc::strong_count(&one), 1);
+    v.retain_top_k(0, |a, b| a.cmp(b));
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_push_unique() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    assert!(v.push_unique(1));
+    assert!(v.push_unique(2));
+    assert!(!v.push_unique(2));
+    assert!(v.push_unique(3));
+    assert!(!v.push_unique(1));
+    assert_eq!(&*v, &[1, 2, 3]);
+
+    create_smallvec!(let mut v: SmallVec((u8, &str), 2) = SmallVec::new());
+    let mut calls = 0;
+    assert!(v.push_unique_by_key((1, "a"), |&(k, _)| k));
+    assert!(!v.push_unique_by_key((1, "b"), |&(k, _)| {
+        calls += 1;
+        k
+    }));
+    // The key of the new element is computed only once.
+    assert_eq!(calls, 2);
+    assert_eq!(&*v, &[(1, "a")]);
+}
+
 #[test]
 fn test_dedup() {
     create_smallvec!(let mut dupes: SmallVec(i32, 5) = SmallVec::from_slice(&[1, 1, 2, 3, 3]));
@@ -692,6 +989,72 @@ fn test_dedup() {
     assert_eq!(no_dupes.len(), 5);
 }
 
+#[test]
+fn test_dedup_by() {
+    let words = ["a", "A", "b", "c", "C", "c"];
+    create_smallvec!(let mut v: SmallVec(String, 8) = words.iter().map(|s| s.to_string()).collect());
+    v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    assert_eq!(&*v, &["a", "b", "c"]);
+    assert!(!v.spilled());
+
+    // The element being checked is passed first, and the last one kept second.
+    create_smallvec!(let mut v: SmallVec(String, 2) = words.iter().map(|s| s.to_string()).collect());
+    v.dedup_by(|a, b| {
+        let same = a.eq_ignore_ascii_case(b);
+        if same {
+            b.push_str(a);
+        }
+        same
+    });
+    assert_eq!(&*v, &["aA", "b", "cC", "c"]);
+    assert!(v.spilled());
+
+    create_smallvec!(let mut v: SmallVec(u32, 2) = SmallVec::from_slice(&[1, 3, 2, 4, 4, 7]));
+    v.dedup_by_key(|x| *x % 2);
+    assert_eq!(&*v, &[1, 2, 7]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_dedup_by_panicking() {
+    let one = Rc::new(1);
+    create_smallvec!(let mut v: SmallVec(Rc<i32>, 2) = SmallVec::new());
+    v.extend((0..4).map(|_| Rc::clone(&one)));
+    let mut calls = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.dedup_by(|_, _| {
+            calls += 1;
+            if calls == 2 {
+                panic!("same_bucket");
+            }
+            true
+        })
+    }));
+    assert!(result.is_err());
+    // Nothing was dropped or leaked.
+    assert_eq!(v.len(), 4);
+    drop(v);
+    assert_eq!(Rc::strong_count(&one), 1);
+}
+
+#[test]
+fn test_resize_with() {
+    create_smallvec!(let mut v: SmallVec(Box<dyn Fn() -> u8>, 2) = SmallVec::new());
+    let mut n = 0;
+    v.resize_with(3, || {
+        n += 1;
+        let x = n;
+        Box::new(move || x)
+    });
+    assert!(v.spilled());
+    assert_eq!(v.iter().map(|f| f()).collect::<Vec<_>>(), &[1, 2, 3]);
+
+    v.resize_with(3, || unreachable!());
+    v.resize_with(1, || unreachable!());
+    assert_eq!(v.len(), 1);
+    assert_eq!(v[0](), 1);
+}
+
 #[test]
 fn test_resize() {
     create_smallvec!(let mut v: SmallVec(i32, 8) = SmallVec::new());
@@ -703,6 +1066,249 @@ fn test_resize() {
     assert_eq!(v[..], [1, 0][..]);
 }
 
+#[test]
+fn test_into_boxed_slice() {
+    create_smallvec!(let v: SmallVec(String, 4) = smallvec!["a".to_owned(), "b".to_owned()]);
+    let b = v.into_boxed_slice();
+    assert_eq!(&*b, ["a", "b"]);
+
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::with_capacity(16));
+    v.extend(0..5);
+    assert_eq!(&*v.into_boxed_slice(), [0, 1, 2, 3, 4]);
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::new());
+    assert!(v.into_boxed_slice().is_empty());
+}
+
+#[test]
+fn test_leak() {
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![1, 2]);
+    let leaked: &'static mut [u8] = v.leak();
+    leaked[1] = 3;
+    assert_eq!(leaked, [1, 3]);
+
+    create_smallvec!(let v: SmallVec(u8, 1) = smallvec![1, 2, 3]);
+    let (ptr, cap) = (v.as_ptr(), v.capacity());
+    let leaked = v.leak();
+    assert_eq!(leaked.as_ptr(), ptr);
+    // Give the buffer back so that leak checkers stay quiet.
+    unsafe { drop(Vec::from_raw_parts(leaked.as_mut_ptr(), 3, cap)) };
+}
+
+#[test]
+fn test_raw_parts() {
+    create_smallvec!(type V = SmallVec(String, 2));
+    create_smallvec!(type V2 = SmallVec((), 2));
+
+    let v: V = vec!["a".to_owned()].into_iter().collect();
+    let (ptr, len, cap) = v.into_raw_parts();
+    assert_eq!((len, cap), (1, 3));
+    let mut v = unsafe { V::from_raw_parts(ptr, len, cap) };
+    assert!(v.spilled());
+    v.push("b".to_owned());
+    assert_eq!(v[..], ["a", "b"]);
+
+    let v: V = (0..5).map(|i| i.to_string()).collect();
+    let cap = v.capacity();
+    let (ptr, len, cap2) = v.into_raw_parts();
+    assert_eq!((len, cap2), (5, cap));
+    let v = unsafe { Vec::from_raw_parts(ptr, len, cap) };
+    assert_eq!(v, ["0", "1", "2", "3", "4"]);
+
+    create_smallvec!(let v: SmallVec((), 2) = smallvec![(); 2]);
+    let (ptr, len, cap) = v.into_raw_parts();
+    let v = unsafe { V2::from_raw_parts(ptr, len, cap) };
+    assert_eq!(v.len(), 2);
+}
+
+#[test]
+fn test_as_mut_ptr_set_len() {
+    create_smallvec!(let mut v: SmallVec(u16, 4) = SmallVec::new());
+    unsafe {
+        for i in 0..4 {
+            v.as_mut_ptr().add(i).write(i as u16);
+        }
+        v.set_len(4);
+    }
+    assert_eq!(v[..], [0, 1, 2, 3]);
+    assert!(!v.spilled());
+
+    v.reserve(4);
+    unsafe {
+        let ptr = v.as_mut_ptr();
+        for i in 4..8 {
+            ptr.add(i).write(i as u16);
+        }
+        v.set_len(8);
+    }
+    assert_eq!(v[..], [0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(v.as_ptr(), v[..].as_ptr());
+}
+
+#[test]
+fn test_spare_capacity_mut() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = smallvec![1]);
+    assert_eq!(v.spare_capacity_mut().len(), 3);
+
+    let (init, spare) = v.split_at_spare_mut();
+    init[0] = 2;
+    for (i, slot) in spare.iter_mut().enumerate() {
+        slot.write(init[0] + i as u8 + 1);
+    }
+    unsafe { v.set_len(4) };
+    assert_eq!(v[..], [2, 3, 4, 5]);
+    assert!(v.spare_capacity_mut().is_empty());
+
+    v.reserve(4);
+    let cap = v.capacity();
+    let spare = v.spare_capacity_mut();
+    assert_eq!(spare.len(), cap - 4);
+    spare[0].write(6);
+    unsafe { v.set_len(5) };
+    assert_eq!(v[..], [2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_extend_from_within() {
+    // Back-references of an LZ77-style decoder, overlapping the end of the output.
+    create_smallvec!(let mut v: SmallVec(u8, 8) = SmallVec::from_slice(b"ab"));
+    for _ in 0..3 {
+        let len = v.len();
+        v.extend_from_within(len - 2..);
+    }
+    assert_eq!(v[..], b"abababab"[..]);
+    assert!(!v.spilled());
+    v.extend_from_within(..);
+    assert_eq!(v.len(), 16);
+    assert!(v.spilled());
+
+    create_smallvec!(let mut v: SmallVec(String, 2) = smallvec!["a".to_owned(), "b".to_owned()]);
+    v.extend_from_within(1..=1);
+    v.extend_from_within(0..0);
+    assert_eq!(v[..], ["a", "b", "b"]);
+}
+
+#[test]
+#[should_panic]
+fn test_extend_from_within_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = smallvec![1]);
+    v.extend_from_within(..2);
+}
+
+#[test]
+fn test_split_off() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = (0..5).map(|i| i.to_string()).collect());
+    let tail = v.split_off(3);
+    assert_eq!(v[..], ["0", "1", "2"]);
+    assert_eq!(tail[..], ["3", "4"]);
+    assert!(v.spilled());
+    assert!(!tail.spilled());
+
+    let mut tail = v.split_off(0);
+    assert!(v.is_empty());
+    assert_eq!(tail.capacity(), 3);
+    assert!(tail.split_off(3).is_empty());
+    assert_eq!(tail.len(), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_split_off_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = smallvec![1]);
+    v.split_off(2);
+}
+
+#[test]
+fn test_append() {
+    create_smallvec!(type V = SmallVec(String, 2));
+
+    let mut v: V = smallvec!["a".to_owned()];
+    let mut other: V = smallvec!["b".to_owned(), "c".to_owned()];
+    v.append(&mut other);
+    assert_eq!(v[..], ["a", "b", "c"]);
+    assert!(other.is_empty());
+    v.append(&mut other);
+    assert_eq!(v.len(), 3);
+
+    let mut empty = V::new();
+    let ptr = v.as_ptr();
+    empty.append(&mut v);
+    assert_eq!(empty.as_ptr(), ptr);
+    assert_eq!(empty[..], ["a", "b", "c"]);
+    assert!(v.is_empty());
+    assert!(!v.spilled());
+}
+
+#[test]
+fn test_smallvec_macro() {
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![]);
+    assert!(v.is_empty());
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![1, 2, 3,]);
+    assert_eq!(v[..], [1, 2, 3][..]);
+    assert!(!v.spilled());
+    create_smallvec!(let v: SmallVec(u8, 2) = smallvec![1, 2, 3]);
+    assert_eq!(v[..], [1, 2, 3][..]);
+    assert_eq!(v.capacity(), 3);
+
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![7; 0]);
+    assert!(v.is_empty());
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![7; 4]);
+    assert_eq!(v[..], [7; 4][..]);
+    assert!(!v.spilled());
+    create_smallvec!(let v: SmallVec(u8, 4) = smallvec![7; 5]);
+    assert_eq!(v[..], [7; 5][..]);
+    assert_eq!(v.capacity(), 5);
+}
+
+#[cfg(feature = "const_new")]
+#[test]
+fn test_const_new() {
+    static EMPTY: SmallVec<u8, 4> = SmallVec::new();
+    static DEFAULTS: SmallVec<u8, 4> = SmallVec::from_const([1, 2, 3, 4]);
+    static ZEROS: SmallVec<u8, 8> = smallvec_inline![0; 8];
+    const PRIMES: SmallVec<u8, 4> = smallvec_inline![2, 3, 5, 7,];
+
+    assert!(EMPTY.is_empty());
+    assert_eq!(DEFAULTS[..], [1, 2, 3, 4]);
+    assert_eq!(ZEROS[..], [0; 8]);
+    assert!(!ZEROS.spilled());
+
+    let mut v = PRIMES;
+    assert_eq!(v.capacity(), 4);
+    v.push(11);
+    assert!(v.spilled());
+    assert_eq!(v[..], [2, 3, 5, 7, 11]);
+    assert_eq!(PRIMES.len(), 4);
+}
+
+#[cfg(feature = "const_new")]
+#[test]
+fn test_smallvec_inline() {
+    const LIST: SmallVec<u8, 3> = smallvec_inline![1, 2, 3];
+    const REPEAT: SmallVec<u8, 2> = smallvec_inline![9; 2];
+    static STATIC_LIST: SmallVec<&str, 2> = smallvec_inline!["a", "b"];
+    static STATIC_REPEAT: SmallVec<u16, 5> = smallvec_inline![7; 5];
+
+    assert_eq!(LIST[..], [1, 2, 3]);
+    assert_eq!(REPEAT[..], [9, 9]);
+    assert_eq!(STATIC_LIST[..], ["a", "b"]);
+    assert_eq!(STATIC_REPEAT[..], [7; 5]);
+    assert!(!LIST.spilled() && !REPEAT.spilled());
+    assert!(!STATIC_LIST.spilled() && !STATIC_REPEAT.spilled());
+    assert_eq!(STATIC_REPEAT.capacity(), 5);
+}
+
+#[test]
+fn test_from_elem_moves_last_copy() {
+    let rc = Rc::new(());
+    create_smallvec!(let v: SmallVec(Rc<()>, 2) = smallvec![rc.clone(); 3]);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(v);
+    create_smallvec!(let v: SmallVec(Rc<()>, 2) = smallvec![rc.clone(); 0]);
+    assert!(v.is_empty());
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_write() {
@@ -718,6 +1324,15 @@ fn test_write() {
     create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
     v.write_all(&data[..]).unwrap();
     assert_eq!(v.as_ref(), data.as_ref());
+
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[0]));
+    let bufs = [
+        std::io::IoSlice::new(&data[..2]),
+        std::io::IoSlice::new(&[]),
+        std::io::IoSlice::new(&data[2..]),
+    ];
+    assert_eq!(v.write_vectored(&bufs).unwrap(), 5);
+    assert_eq!(&*v, &[0, 1, 2, 3, 4, 5]);
 }
 
 #[cfg(feature = "serde")]
@@ -725,6 +1340,7 @@ extern crate bincode;
 
 #[cfg(feature = "serde")]
 #[test]
+#[allow(deprecated)]
 fn test_serde() {
     use self::bincode::{config, deserialize};
     create_smallvec!(let mut v: SmallVec(i32, 2) = SmallVec::new());
@@ -743,6 +1359,84 @@ fn test_serde() {
     assert_eq!(v, decoded);
 }
 
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_append_seed() {
+    use self::bincode::{serialize, DefaultOptions, Deserializer, Options};
+    use crate::AppendSeed;
+    use serde::de::DeserializeSeed;
+
+    let mut bytes = serialize(&[1u16, 2, 3][..]).unwrap();
+    bytes.extend(serialize(&[4u16, 5][..]).unwrap());
+    let mut de = Deserializer::from_slice(
+        &bytes,
+        DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes(),
+    );
+
+    create_smallvec!(let mut v: SmallVec(u16, 4) = SmallVec::new());
+    AppendSeed(&mut v).deserialize(&mut de).unwrap();
+    assert_eq!(&*v, &[1, 2, 3]);
+    AppendSeed(&mut v).deserialize(&mut de).unwrap();
+    assert_eq!(&*v, &[1, 2, 3, 4, 5]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_deserialize_in_place() {
+    use self::bincode::{serialize, DefaultOptions, Deserializer, Options};
+    use serde::Deserialize;
+
+    create_smallvec!(let mut v: SmallVec(u32, 2) = (0..10).collect());
+    let capacity = v.capacity();
+    let bytes = serialize(&[7u32, 8, 9][..]).unwrap();
+    let mut de = Deserializer::from_slice(&bytes, DefaultOptions::new().with_fixint_encoding());
+    Deserialize::deserialize_in_place(&mut de, &mut v).unwrap();
+    assert_eq!(&*v, &[7, 8, 9]);
+    assert_eq!(v.capacity(), capacity);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_bytes() {
+    use self::bincode::{DefaultOptions, Deserializer, Serializer};
+    use crate::serde_bytes;
+    use serde::de::value::{BytesDeserializer, Error, SeqDeserializer, StrDeserializer};
+
+    create_smallvec!(type V = SmallVec(u8, 4));
+    let v: V = (0..10).collect();
+    let mut bytes = Vec::new();
+    serde_bytes::serialize(&v, &mut Serializer::new(&mut bytes, DefaultOptions::new())).unwrap();
+    assert_eq!(bytes.len(), 11);
+    let decoded: V =
+        serde_bytes::deserialize(&mut Deserializer::from_slice(&bytes, DefaultOptions::new()))
+            .unwrap();
+    assert_eq!(decoded, v);
+
+    let decoded: V = serde_bytes::deserialize(BytesDeserializer::<Error>::new(b"abc")).unwrap();
+    assert_eq!(&*decoded, b"abc");
+    assert!(!decoded.spilled());
+    let decoded: V = serde_bytes::deserialize(StrDeserializer::<Error>::new("hi")).unwrap();
+    assert_eq!(&*decoded, b"hi");
+    let decoded: V =
+        serde_bytes::deserialize(SeqDeserializer::<_, Error>::new(vec![1u8, 2].into_iter()))
+            .unwrap();
+    assert_eq!(&*decoded, &[1, 2]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_huge_length_prefix() {
+    use self::bincode::{deserialize, serialize};
+
+    // A sequence claiming far more elements than there are bytes left must fail to decode
+    // instead of trying to allocate them all up front.
+    let bytes = serialize(&(u64::MAX / 4)).unwrap();
+    create_smallvec!(type V = SmallVec(u64, 2));
+    assert!(deserialize::<V>(&bytes).is_err());
+}
+
 #[test]
 fn grow_to_shrink() {
     create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
@@ -785,3 +1479,2003 @@ fn grow_spilled_same_size() {
     assert_eq!(v.capacity(), 4);
     assert_eq!(v[..], [0, 1, 2]);
 }
+
+#[test]
+fn test_extend_from_array() {
+    create_smallvec!(let mut v: SmallVec(Box<u8>, 3) = SmallVec::new());
+    v.extend_from_array([Box::new(0), Box::new(1)]);
+    assert!(!v.spilled());
+    v.extend_from_array([Box::new(2), Box::new(3)]);
+    assert!(v.spilled());
+    v.extend_from_array([]);
+    assert_eq!(&v.iter().map(|v| **v).collect::<Vec<_>>(), &[0, 1, 2, 3]);
+}
+
+#[test]
+fn test_try_with_capacity() {
+    create_smallvec!(let v: SmallVec(u8, 3) = SmallVec::try_with_capacity(1).unwrap());
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 3);
+
+    create_smallvec!(let v: SmallVec(u8, 3) = SmallVec::try_with_capacity(10).unwrap());
+    assert!(v.spilled());
+    assert_eq!(v.capacity(), 10);
+
+    create_smallvec!(type V = SmallVec(u64, 3));
+    match V::try_with_capacity(usize::MAX) {
+        Err(crate::CollectionAllocErr::CapacityOverflow) => {}
+        _ => panic!("expected a capacity overflow"),
+    }
+}
+
+#[test]
+fn test_try_from_slice() {
+    create_smallvec!(let a: SmallVec(u32, 2) = SmallVec::try_from_slice(&[1]).unwrap());
+    create_smallvec!(let b: SmallVec(u32, 2) = SmallVec::try_from_slice(&[1, 2, 3]).unwrap());
+    assert!(!a.spilled());
+    assert!(b.spilled());
+    assert_eq!(&a[..], [1]);
+    assert_eq!(&b[..], [1, 2, 3]);
+}
+
+#[test]
+fn test_try_from_vec_compact() {
+    let mut vec = Vec::with_capacity(10);
+    vec.extend_from_slice(&[1, 2]);
+    create_smallvec!(let v: SmallVec(u8, 3) = SmallVec::try_from_vec_compact(vec).unwrap());
+    assert!(!v.spilled());
+    assert_eq!(&*v, &[1, 2]);
+
+    let mut vec = Vec::with_capacity(10);
+    vec.extend_from_slice(&[1, 2, 3, 4, 5]);
+    create_smallvec!(let v: SmallVec(u8, 3) = SmallVec::try_from_vec_compact(vec).unwrap());
+    assert!(v.spilled());
+    assert_eq!(v.capacity(), 5);
+    assert_eq!(&*v, &[1, 2, 3, 4, 5]);
+    assert_eq!(v.into_vec(), vec![1, 2, 3, 4, 5]);
+
+    let vec = vec![(); 5];
+    create_smallvec!(let v: SmallVec((), 3) = SmallVec::try_from_vec_compact(vec).unwrap());
+    assert_eq!(v.len(), 5);
+}
+
+#[test]
+fn test_truncate_while() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[0, 1, 2, 0, 0]));
+    assert_eq!(v.truncate_while(|&x| x == 0), 2);
+    assert_eq!(&*v, &[0, 1, 2]);
+    assert_eq!(v.truncate_while(|&x| x == 0), 0);
+    assert_eq!(v.truncate_while(|_| true), 3);
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_truncate_front_while() {
+    let one = Rc::new(1);
+    create_smallvec!(let mut v: SmallVec(Rc<i32>, 2) = SmallVec::new());
+    v.push(Rc::clone(&one));
+    v.push(Rc::clone(&one));
+    v.push(Rc::new(2));
+    assert_eq!(v.truncate_front_while(|x| **x == 1), 2);
+    assert_eq!(Rc::strong_count(&one), 1);
+    assert_eq!(v.len(), 1);
+    assert_eq!(*v[0], 2);
+    assert_eq!(v.truncate_front_while(|x| **x == 1), 0);
+    assert_eq!(v.len(), 1);
+}
+
+#[test]
+fn test_cursor_mut() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2, 3]));
+    {
+        let mut cursor = v.cursor_mut();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), None);
+        assert!(!cursor.move_prev());
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), 1);
+        assert_eq!(cursor.peek_prev(), Some(&mut 0));
+        assert!(cursor.move_next());
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.insert_after(4);
+        cursor.insert_after(5);
+        assert_eq!(cursor.len(), 5);
+        assert!(cursor.move_prev());
+        assert!(cursor.move_prev());
+        assert_eq!(cursor.current(), Some(&mut 0));
+        cursor.insert_after(9);
+        while cursor.move_next() {}
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_after(6);
+    }
+    assert!(v.spilled());
+    assert_eq!(&*v, &[0, 9, 1, 3, 5, 4, 6]);
+
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    v.cursor_mut().insert_before(1);
+    assert_eq!(&*v, &[1]);
+}
+
+#[test]
+fn test_cursor_mut_drop() {
+    let one = Rc::new(1);
+    create_smallvec!(let mut v: SmallVec(Rc<i32>, 2) = SmallVec::new());
+    v.push(Rc::clone(&one));
+    v.push(Rc::clone(&one));
+    {
+        let mut cursor = v.cursor_mut();
+        drop(cursor.remove_current());
+        cursor.insert_after(Rc::clone(&one));
+        cursor.insert_after(Rc::clone(&one));
+        cursor.move_next();
+    }
+    assert_eq!(Rc::strong_count(&one), 4);
+    assert_eq!(v.len(), 3);
+    core::mem::forget(v.cursor_mut());
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_reserve_in_place() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2]));
+    let ptr = v.as_ptr();
+    assert!(v.reserve_in_place(2));
+    assert_eq!(v.as_ptr(), ptr);
+    assert!(!v.reserve_in_place(3));
+    assert_eq!(v.as_ptr(), ptr);
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 4);
+
+    v.reserve_exact(4);
+    let ptr = v.as_ptr();
+    assert!(v.reserve_in_place(4));
+    assert_eq!(v.as_ptr(), ptr);
+    assert!(!v.reserve_in_place(100));
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v.capacity(), 6);
+    assert!(!v.reserve_in_place(usize::MAX));
+    assert_eq!(&*v, &[1, 2]);
+}
+
+#[test]
+fn test_small_slot_map() {
+    #[cfg(not(feature = "const_generics"))]
+    use crate::Slot;
+    use crate::SmallSlotMap;
+
+    #[cfg(feature = "const_generics")]
+    let mut map: SmallSlotMap<Rc<i32>, 2> = SmallSlotMap::new();
+    #[cfg(not(feature = "const_generics"))]
+    let mut map: SmallSlotMap<Rc<i32>, [Slot<Rc<i32>>; 2]> = SmallSlotMap::new();
+
+    let one = Rc::new(1);
+    let a = map.insert(Rc::clone(&one));
+    let b = map.insert(Rc::new(2));
+    assert!(!map.spilled());
+    assert_eq!(map.len(), 2);
+    assert_eq!(*map[a], 1);
+    assert_eq!(*map[b], 2);
+
+    assert_eq!(map.remove(a).map(|x| *x), Some(1));
+    assert_eq!(Rc::strong_count(&one), 1);
+    assert_eq!(map.remove(a), None);
+    assert!(!map.contains_key(a));
+
+    // The freed slot is reused with a new generation.
+    let c = map.insert(Rc::new(3));
+    assert!(!map.spilled());
+    assert_ne!(a, c);
+    assert_eq!(map.get(a), None);
+    *map.get_mut(c).unwrap() = Rc::new(30);
+
+    let d = map.insert(Rc::clone(&one));
+    assert!(map.spilled());
+    assert_eq!(
+        map.iter().map(|(k, v)| (k, **v)).collect::<Vec<_>>(),
+        vec![(c, 30), (b, 2), (d, 1)]
+    );
+    assert_eq!(map.iter_mut().len(), 3);
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(Rc::strong_count(&one), 1);
+    assert_eq!(map.get(d), None);
+    let e = map.insert(Rc::new(4));
+    assert_eq!(map.get(d), None);
+    assert_eq!(*map[e], 4);
+}
+
+#[test]
+fn test_small_arena() {
+    use crate::SmallArena;
+
+    #[cfg(feature = "const_generics")]
+    let mut arena: SmallArena<16> = SmallArena::new();
+    #[cfg(not(feature = "const_generics"))]
+    let mut arena: SmallArena<[u8; 16]> = SmallArena::new();
+
+    {
+        let a = arena.alloc(1u8);
+        let b = arena.alloc(2u64);
+        assert_eq!(b as *mut u64 as usize % core::mem::align_of::<u64>(), 0);
+        let c = arena.alloc_slice_copy(&[3u16, 4]);
+        *a += 10;
+        *b += 10;
+        c[1] += 10;
+        assert_eq!((*a, *b, &*c), (11, 12, &[3, 14][..]));
+        assert!(arena.spilled());
+
+        let big = arena.alloc_slice_fill_with(1000, |i| i as u32);
+        assert_eq!(big.len(), 1000);
+        assert_eq!(big[999], 999);
+        let zst = arena.alloc(());
+        assert_eq!(*zst, ());
+    }
+
+    arena.reset();
+    assert!(arena.spilled());
+    let d = arena.alloc(5u32);
+    assert_eq!(*d, 5);
+}
+
+#[test]
+fn test_small_vec1() {
+    use crate::SmallVec1;
+    use core::convert::TryFrom;
+
+    create_smallvec!(type V = SmallVec(u8, 2));
+    #[cfg(feature = "const_generics")]
+    type V1 = SmallVec1<u8, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type V1 = SmallVec1<[u8; 2]>;
+
+    let mut v = V1::new(1);
+    assert_eq!(v.pop(), None);
+    v.push(2);
+    v.insert(0, 0);
+    assert!(v.spilled());
+    assert_eq!(&*v, &[0, 1, 2]);
+    assert_eq!((*v.first(), *v.last()), (0, 2));
+    *v.last_mut() = 3;
+    assert_eq!(v.remove(0), Some(0));
+    v.truncate(0);
+    assert_eq!(&*v, &[1]);
+    assert_eq!(v.remove(0), None);
+
+    assert!(V1::try_from(V::new()).is_err());
+    let v = V1::try_from(V::from_slice(&[4, 5])).unwrap();
+    assert_eq!(V::from(v.clone()), V::from_slice(&[4, 5]));
+    assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![4, 5]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_ffi_conversions() {
+    use std::ffi::CStr;
+
+    create_smallvec!(type V = SmallVec(u8, 4));
+    let c = CStr::from_bytes_with_nul(b"/tmp/x\0").unwrap();
+    let v = V::from(c);
+    assert_eq!(&*v, b"/tmp/x");
+    assert_eq!(&*v.to_cstring().unwrap(), c);
+    assert!(V::from_slice(b"a\0b").to_cstring().is_err());
+
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        let os = OsStr::new("dir");
+        let v = V::from(os);
+        assert!(!v.spilled());
+        assert_eq!(v.as_os_str(), os);
+    }
+}
+
+#[test]
+fn test_from_iter_reserves_hint() {
+    create_smallvec!(type V = SmallVec(u16, 4));
+
+    let v: V = (0..1000).collect();
+    assert_eq!(v.capacity(), 1000);
+    assert_eq!(v.len(), 1000);
+
+    let v: V = (0..3).collect();
+    assert!(!v.spilled());
+
+    let v: V = (0..1000).filter(|x| x % 2 == 0).collect();
+    assert_eq!(&v[..3], &[0, 2, 4]);
+    assert_eq!(v.len(), 500);
+
+    // The remaining lower bound is reserved at once when the buffer fills up.
+    let mut v: V = V::new();
+    v.extend((0..4).filter(|_| true).chain(0..100));
+    assert_eq!(v.len(), 104);
+    assert_eq!(v.capacity(), 128);
+}
+
+#[test]
+fn test_get_mut_or_grow() {
+    create_smallvec!(let mut v: SmallVec(u32, 2) = SmallVec::new());
+    *v.get_mut_or_grow(1) += 5;
+    assert_eq!(&*v, &[0, 5]);
+    assert!(!v.spilled());
+    *v.get_mut_or_grow(0) += 1;
+    assert_eq!(&*v, &[1, 5]);
+    *v.get_mut_or_grow_with(3, || 7) += 1;
+    assert_eq!(&*v, &[1, 5, 7, 8]);
+    assert!(v.spilled());
+}
+
+#[test]
+fn test_as_vec_mut() {
+    create_smallvec!(let mut v: SmallVec(_, 2) = SmallVec::new());
+    v.push("a".to_owned());
+    {
+        let mut vec = v.as_vec_mut();
+        vec.push("b".to_owned());
+        vec.push("c".to_owned());
+        vec.remove(0);
+    }
+    assert_eq!(&*v, &["b", "c"]);
+    assert!(!v.spilled());
+
+    v.push("d".to_owned());
+    assert!(v.spilled());
+    let ptr = v.as_ptr();
+    {
+        let mut vec = v.as_vec_mut();
+        assert_eq!(vec.as_ptr(), ptr);
+        vec.truncate(1);
+    }
+    // A spilled vector keeps its heap buffer.
+    assert!(v.spilled());
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(&*v, &["b"]);
+
+    core::mem::forget(v.as_vec_mut());
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_swap_with_vec() {
+    create_smallvec!(let mut v: SmallVec(_, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    let mut vec = Vec::with_capacity(10);
+    vec.push(4);
+    let (v_ptr, vec_ptr) = (v.as_ptr(), vec.as_ptr());
+    v.swap_with_vec(&mut vec);
+    assert_eq!((&*v, &*vec), (&[4][..], &[1, 2, 3][..]));
+    // Both heap buffers changed owner.
+    assert_eq!((v.as_ptr(), vec.as_ptr()), (vec_ptr, v_ptr));
+
+    let mut vec = vec![5];
+    vec.shrink_to_fit();
+    v.truncate(0);
+    v.push(6);
+    v.swap_with_vec(&mut vec);
+    assert_eq!((&*v, &*vec), (&[5][..], &[6][..]));
+    assert!(!v.spilled());
+
+    v.swap_with_vec(&mut Vec::new());
+    assert!(v.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "`remove` index (is 3) should be < len (is 2)")]
+fn test_remove_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2]));
+    v.remove(3);
+}
+
+#[test]
+#[should_panic(expected = "`insert` index (is 3) should be <= len (is 2)")]
+fn test_insert_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2]));
+    v.insert(3, 0);
+}
+
+#[test]
+#[should_panic(expected = "`swap_remove` index (is 0) should be < len (is 0)")]
+fn test_swap_remove_empty() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::new());
+    v.swap_remove(0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_panic_location_is_caller() {
+    use std::{
+        panic,
+        string::{String, ToString},
+        sync::{Arc, Mutex},
+    };
+
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::new());
+    let captured: Arc<Mutex<Option<(String, u32)>>> = Arc::default();
+    let hook_captured = captured.clone();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // Other tests may panic concurrently, only record this one.
+        let message = info.to_string();
+        if message.contains("index (is 77)") {
+            let location = info.location().unwrap();
+            *hook_captured.lock().unwrap() = Some((location.file().to_owned(), location.line()));
+        }
+    }));
+    let line = line!() + 1;
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| v.remove(77)));
+    panic::set_hook(previous);
+
+    assert!(result.is_err());
+    assert_eq!(*captured.lock().unwrap(), Some((file!().to_owned(), line)));
+}
+
+#[test]
+fn test_try_extend() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    assert_eq!(
+        v.try_extend(vec![Ok(1), Ok(2), Err("three"), Ok(4)]),
+        Err("three")
+    );
+    assert_eq!(&*v, &[1, 2]);
+    assert_eq!(v.try_extend((3..6).map(Ok::<_, ()>)), Ok(()));
+    assert_eq!(&*v, &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_small_vec_pool() {
+    use crate::SmallVecPool;
+
+    #[cfg(feature = "const_generics")]
+    let pool: SmallVecPool<u8, 2> = SmallVecPool::new(1);
+    #[cfg(not(feature = "const_generics"))]
+    let pool: SmallVecPool<[u8; 2]> = SmallVecPool::new(1);
+
+    // Inline vectors are not worth keeping.
+    pool.get().push(1);
+    assert!(pool.is_empty());
+
+    let (mut a, mut b) = (pool.get(), pool.get());
+    a.extend_from_slice(&[1, 2, 3, 4]);
+    b.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    let ptr = a.as_ptr();
+    drop(a);
+    drop(b);
+    // Only one buffer is kept.
+    assert_eq!(pool.len(), 1);
+
+    let c = pool.get();
+    assert!(c.is_empty());
+    assert_eq!(c.as_ptr(), ptr);
+    let c = c.into_inner();
+    assert!(pool.is_empty());
+    pool.recycle(c);
+    assert_eq!(pool.len(), 1);
+    pool.clear();
+    assert!(pool.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_with_scratch() {
+    use crate::with_scratch;
+
+    create_smallvec!(type V = SmallVec(u8, 2));
+    let ptr = with_scratch(|buf: &mut V| {
+        buf.extend_from_slice(b"spilled");
+        let outer = buf.as_ptr();
+        // A nested call doesn't see the outer buffer.
+        with_scratch(|inner: &mut V| assert!(inner.is_empty()));
+        outer
+    });
+    with_scratch(|buf: &mut V| {
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_ptr(), ptr);
+    });
+}
+
+#[test]
+fn test_unzip() {
+    create_smallvec!(let pairs: SmallVec((u8, String), 2) = (0..3u8).map(|i| (i, i.to_string())).collect());
+    create_smallvec!(type Left = SmallVec(u8, 2));
+    create_smallvec!(type Right = SmallVec(String, 2));
+    let (left, right): (Left, Right) = pairs.unzip();
+    assert_eq!(&*left, &[0, 1, 2]);
+    assert_eq!(&*right, &["0", "1", "2"]);
+    assert_eq!(right.capacity(), 3);
+}
+
+#[test]
+fn test_assert_inline_bytes() {
+    create_smallvec!(type V = SmallVec(u64, 8));
+    assert_inline_bytes!(V, 96);
+    assert!(core::mem::size_of::<V>() > 64);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn test_reserve_overflow() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1]));
+    v.reserve(usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn test_reserve_next_power_of_two_overflow() {
+    // The rounded-up capacity doesn't fit in `usize` whatever its width.
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1]));
+    v.reserve(usize::MAX / 2 + 1);
+}
+
+#[test]
+fn test_try_reserve() {
+    use crate::CollectionAllocErr;
+
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1]));
+    v.try_reserve(1).unwrap();
+    assert!(!v.spilled());
+    v.try_reserve(2).unwrap();
+    assert_eq!(v.capacity(), 4);
+    v.try_reserve_exact(9).unwrap();
+    assert_eq!(v.capacity(), 10);
+
+    assert!(matches!(
+        v.try_reserve(usize::MAX),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    assert!(matches!(
+        v.try_reserve_exact(usize::MAX),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    // Fits in a `Layout` but not in memory.
+    #[cfg(not(feature = "u32_len"))]
+    assert!(matches!(
+        v.try_reserve_exact(isize::MAX as usize - 1),
+        Err(CollectionAllocErr::AllocErr { .. })
+    ));
+    #[cfg(feature = "u32_len")]
+    assert!(matches!(
+        v.try_reserve_exact(isize::MAX as usize - 1),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    assert_eq!((&*v, v.capacity()), (&[1][..], 10));
+}
+
+#[test]
+fn test_try_mutations() {
+    use crate::CollectionAllocErr;
+
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    v.try_push(1).unwrap();
+    v.try_insert(0, 0).unwrap();
+    v.try_extend_from_slice(&[3, 4]).unwrap();
+    v.try_insert_from_slice(2, &[2]).unwrap();
+    assert_eq!(&*v, &[0, 1, 2, 3, 4]);
+    v.try_resize(6, 5).unwrap();
+    assert_eq!(&*v, &[0, 1, 2, 3, 4, 5]);
+    v.try_resize(2, 0).unwrap();
+    assert_eq!(&*v, &[0, 1]);
+
+    // Failures leave the vector untouched.
+    let too_big = isize::MAX as usize / 2;
+    #[cfg(not(feature = "u32_len"))]
+    assert!(matches!(
+        v.try_resize(too_big, 0),
+        Err(CollectionAllocErr::AllocErr { .. })
+    ));
+    #[cfg(feature = "u32_len")]
+    assert!(matches!(
+        v.try_resize(too_big, 0),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    assert!(v.try_extend_from_slice(&[0; 64]).is_ok());
+    v.truncate(2);
+    assert!(v.try_insert_from_slice(1, &[0]).is_ok());
+    assert!(v.try_reserve(usize::MAX).is_err());
+    assert_eq!(&*v, &[0, 0, 1]);
+
+    create_smallvec!(let mut v: SmallVec(Box<u8>, 1) = SmallVec::new());
+    v.try_push(Box::new(1)).unwrap();
+    v.shrink_to_fit();
+    v.try_reserve_exact(1).unwrap();
+    v.try_push(Box::new(2)).unwrap();
+    assert_eq!(v.capacity(), 2);
+    v.try_insert(0, Box::new(0)).unwrap();
+    assert_eq!(v.iter().map(|b| **b).collect::<Vec<_>>(), &[0, 1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "`try_insert` index (is 3) should be <= len (is 2)")]
+fn test_try_insert_out_of_bounds() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2]));
+    let _ = v.try_insert(3, 0);
+}
+
+#[test]
+fn test_sort_floats() {
+    create_smallvec!(let mut v: SmallVec(f32, 4) = SmallVec::from_slice(&[0.0, f32::NAN, -0.0, -f32::NAN, 1.0, f32::NEG_INFINITY]));
+    v.sort_unstable_by_total_cmp();
+    assert!(v[0].is_nan() && v[0].is_sign_negative());
+    assert_eq!(v[1], f32::NEG_INFINITY);
+    assert!(v[2] == 0.0 && v[2].is_sign_negative());
+    assert!(v[3] == 0.0 && v[3].is_sign_positive());
+    assert_eq!(v[4], 1.0);
+    assert!(v[5].is_nan() && v[5].is_sign_positive());
+
+    v.sort_floats_unstable();
+    assert_eq!(v[0], f32::NEG_INFINITY);
+    assert!(v[1].is_sign_negative());
+    assert_eq!(&v[2..4], &[0.0, 1.0]);
+    assert!(v[4].is_nan() && v[5].is_nan());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_visitor_for_wrapper() {
+    use self::bincode::{deserialize, serialize};
+    use crate::SmallVecVisitor;
+    use serde::{Deserialize, Deserializer};
+
+    create_smallvec!(type V = SmallVec(u8, 4));
+    struct Wrapper(V);
+
+    impl<'de> Deserialize<'de> for Wrapper {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer
+                .deserialize_seq(SmallVecVisitor::new())
+                .map(Wrapper)
+        }
+    }
+
+    let encoded = serialize(&[1u8, 2, 3][..]).unwrap();
+    let Wrapper(v) = deserialize(&encoded).unwrap();
+    assert_eq!(&*v, &[1, 2, 3]);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_soa() {
+    use crate::SmallVecSoA;
+
+    #[derive(Debug, PartialEq, SmallVecSoA)]
+    #[soa(inline = 2)]
+    struct Particle {
+        position: f32,
+        name: String,
+    }
+
+    let mut particles = ParticleSoA::new();
+    assert!(particles.is_empty());
+    for (i, name) in ["a", "b", "c"].iter().enumerate() {
+        particles.push(Particle {
+            position: i as f32,
+            name: name.to_string(),
+        });
+    }
+    assert_eq!(particles.len(), 3);
+    assert_eq!(particles.position(), &[0.0, 1.0, 2.0]);
+    assert_eq!(particles.name()[1], "b");
+
+    let second = particles.get(1).unwrap();
+    assert_eq!((*second.position, &**second.name), (1.0, "b"));
+    assert!(particles.get(3).is_none());
+
+    *particles.get_mut(0).unwrap().position += 10.0;
+    particles.name_mut()[2].push('!');
+    let positions: Vec<f32> = particles.iter().map(|p| *p.position).collect();
+    assert_eq!(positions, [10.0, 1.0, 2.0]);
+
+    let removed = particles.swap_remove(0);
+    assert_eq!((removed.position, &*removed.name), (10.0, "a"));
+    assert_eq!(
+        particles.pop(),
+        Some(Particle {
+            position: 1.0,
+            name: "b".to_string(),
+        })
+    );
+    assert_eq!(particles.name(), &["c!"]);
+    particles.clear();
+    assert!(particles.pop().is_none());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_into_bytes() {
+    use bytes::{Bytes, BytesMut};
+
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(b"abcdef"));
+    let ptr = v.as_ptr();
+    let mut bytes = BytesMut::from(v);
+    assert_eq!(bytes.as_ptr(), ptr);
+    bytes.extend_from_slice(b"g");
+    assert_eq!(&bytes[..], b"abcdefg");
+
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(b"ab"));
+    assert_eq!(Bytes::from(v), Bytes::from_static(b"ab"));
+}
+
+#[cfg(feature = "nom")]
+#[test]
+fn test_nom_input() {
+    use nom::{
+        bytes::complete::{tag, take_while1},
+        character::complete::digit1,
+        sequence::preceded,
+        IResult,
+    };
+
+    create_smallvec!(type V = SmallVec(u8, 8));
+
+    fn key(input: V) -> IResult<V, V> {
+        preceded(tag("key="), take_while1(|b: u8| b.is_ascii_alphabetic()))(input)
+    }
+
+    let (rest, value) = key(V::from_slice(b"key=abc;")).unwrap();
+    assert_eq!((&*rest, &*value), (&b";"[..], &b"abc"[..]));
+    assert!(key(V::from_slice(b"nope")).is_err());
+
+    let (rest, number) = digit1::<_, ()>(V::from_slice(b"12345678901x")).unwrap();
+    assert_eq!(&*rest, b"x");
+    assert_eq!(nom::ParseTo::<u64>::parse_to(&number), Some(12345678901));
+}
+
+#[test]
+fn test_new_boxed() {
+    create_smallvec!(type V = SmallVec(Rc<u8>, 4096));
+    let one = Rc::new(1);
+    let mut v: Box<V> = V::new_boxed();
+    assert!(v.is_empty());
+    assert_eq!(v.capacity(), 4096);
+    v.extend((0..4097).map(|_| Rc::clone(&one)));
+    assert!(v.spilled());
+    assert_eq!(Rc::strong_count(&one), 4098);
+    drop(v);
+    assert_eq!(Rc::strong_count(&one), 1);
+}
+
+#[cfg(feature = "varint")]
+#[test]
+fn test_varint() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::new());
+    let values = [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX];
+    let lens: Vec<usize> = values.iter().map(|&n| v.push_varint_u64(n)).collect();
+    assert_eq!(lens, [1, 1, 1, 2, 2, 5, 10]);
+    let mut index = 0;
+    for &n in &values {
+        let (decoded, len) = v.read_varint_u64_at(index).unwrap();
+        assert_eq!(decoded, n);
+        index += len;
+    }
+    assert_eq!(index, v.len());
+    assert_eq!(v.read_varint_u64_at(index), None);
+    assert_eq!(v.read_varint_u64_at(index + 1), None);
+
+    v.clear();
+    for &n in &[0, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+        let len = v.push_varint_i64(n);
+        assert_eq!(v.read_varint_i64_at(v.len() - len), Some((n, len)));
+    }
+    assert_eq!(v[..4], [0, 1, 2, 127]);
+
+    // Truncated.
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(&[0x80, 0x80]));
+    assert_eq!(v.read_varint_u64_at(0), None);
+    // Overlong but in range.
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(&[0x81, 0x00]));
+    assert_eq!(v.read_varint_u64_at(0), Some((1, 2)));
+    // Overflows a u64.
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[0xff; 9]));
+    v.push(0x02);
+    assert_eq!(v.read_varint_u64_at(0), None);
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(&[0x80; 11]));
+    assert_eq!(v.read_varint_u64_at(0), None);
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn test_custom_allocator() {
+    use crate::allocator::{Allocator, Global};
+    #[cfg(feature = "allocator_api")]
+    use alloc::alloc::AllocError;
+    #[cfg(all(feature = "allocator-api2", not(feature = "allocator_api")))]
+    use allocator_api2::alloc::AllocError;
+    use core::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+    #[derive(Default)]
+    struct Counting {
+        live: Cell<isize>,
+        calls: Cell<usize>,
+    }
+
+    unsafe impl Allocator for &Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.live.set(self.live.get() + 1);
+            self.calls.set(self.calls.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let counting = Counting::default();
+    {
+        #[cfg(feature = "const_generics")]
+        let mut v: SmallVec<String, 2, &Counting> = SmallVec::new_in(&counting);
+        #[cfg(not(feature = "const_generics"))]
+        let mut v: SmallVec<[String; 2], &Counting> = SmallVec::new_in(&counting);
+        v.push("a".to_string());
+        v.push("b".to_string());
+        assert_eq!(counting.calls.get(), 0);
+        v.push("c".to_string());
+        v.extend((0..10).map(|i| i.to_string()));
+        assert!(v.spilled());
+        assert_eq!(counting.live.get(), 1);
+
+        let w = v.clone();
+        assert_eq!(v, w);
+        assert_eq!(counting.live.get(), 2);
+
+        v.truncate(2);
+        v.shrink_to_fit();
+        assert!(!v.spilled());
+        assert_eq!(counting.live.get(), 1);
+        assert_eq!(w.into_iter().next_back().as_deref(), Some("9"));
+        assert_eq!(counting.live.get(), 0);
+
+        #[cfg(feature = "const_generics")]
+        let v: SmallVec<u8, 2, &Counting> = SmallVec::with_capacity_in(5, &counting);
+        #[cfg(not(feature = "const_generics"))]
+        let v: SmallVec<[u8; 2], &Counting> = SmallVec::with_capacity_in(5, &counting);
+        assert_eq!(v.capacity(), 5);
+        assert_eq!(counting.live.get(), 1);
+    }
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn test_small_string() {
+    use crate::SmallString;
+    use core::fmt::Write;
+
+    #[cfg(feature = "const_generics")]
+    type S = SmallString<4>;
+    #[cfg(not(feature = "const_generics"))]
+    type S = SmallString<[u8; 4]>;
+
+    let mut s = S::new();
+    s.push('é');
+    s.push_str("a");
+    assert_eq!(s, "éa");
+    assert_eq!(s.len(), 3);
+    assert!(!s.spilled());
+    write!(s, "{}", 12).unwrap();
+    assert!(s.spilled());
+    assert_eq!(s.as_str(), "éa12");
+    assert_eq!(s.pop(), Some('2'));
+    s.truncate(10);
+    s.truncate(2);
+    assert_eq!(s, "é");
+    s.shrink_to_fit();
+    assert!(!s.spilled());
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s.pop(), None);
+
+    let s: S = "ab".chars().chain("cd".chars()).collect();
+    assert_eq!(s.to_uppercase(), "ABCD");
+    assert_eq!(s.clone().into_string(), String::from("abcd"));
+    let (lower, higher) = (S::from("abc"), S::from(String::from("abd")));
+    assert!(lower < higher);
+    assert_eq!(alloc::format!("{:?} {}", s, s), "\"abcd\" abcd");
+
+    let bytes = S::from("\u{7f}").into_bytes();
+    assert!(S::from_utf8(bytes).is_ok());
+    let invalid = S::from("\u{ff}").into_bytes();
+    assert!(S::from_utf8(invalid.iter().skip(1).cloned().collect()).is_err());
+}
+
+#[test]
+#[should_panic(expected = "`truncate` length (is 1) is not on a char boundary")]
+fn test_small_string_truncate_inside_char() {
+    use crate::SmallString;
+
+    #[cfg(feature = "const_generics")]
+    let mut s: SmallString<4> = SmallString::from("é");
+    #[cfg(not(feature = "const_generics"))]
+    let mut s: SmallString<[u8; 4]> = SmallString::from("é");
+    s.truncate(1);
+}
+
+#[test]
+fn test_small_binary_heap() {
+    #[cfg(feature = "const_generics")]
+    type H = crate::SmallBinaryHeap<u32, 4>;
+    #[cfg(not(feature = "const_generics"))]
+    type H = crate::SmallBinaryHeap<[u32; 4]>;
+
+    let mut heap = H::new();
+    assert_eq!(heap.pop(), None);
+    for &x in &[5, 1, 8, 3, 8, 2] {
+        heap.push(x);
+    }
+    assert!(heap.spilled());
+    assert_eq!(heap.peek(), Some(&8));
+    let mut popped = Vec::new();
+    while let Some(x) = heap.pop() {
+        popped.push(x);
+    }
+    assert_eq!(popped, [8, 8, 5, 3, 2, 1]);
+
+    let heap: H = (0..10).map(|i| (i * 7) % 10).collect();
+    assert_eq!(heap.len(), 10);
+    assert_eq!(heap.peek(), Some(&9));
+    assert_eq!(
+        heap.into_sorted_smallvec()[..],
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+    );
+
+    let mut heap = H::from(SmallVec::from_slice(&[2, 9, 4]));
+    heap.extend(vec![1, 10]);
+    assert_eq!(heap.pop(), Some(10));
+    assert_eq!(heap.into_iter().count(), 4);
+}
+
+#[test]
+fn test_small_map() {
+    use crate::{SmallMap, SmallMapEntry};
+
+    #[cfg(feature = "const_generics")]
+    type M = SmallMap<String, u32, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type M = SmallMap<String, u32, [(String, u32); 2]>;
+
+    let mut map = M::new();
+    assert_eq!(map.insert("a".to_owned(), 1), None);
+    assert_eq!(map.insert("b".to_owned(), 2), None);
+    assert_eq!(map.insert("a".to_owned(), 3), Some(1));
+    assert!(!map.spilled());
+    assert_eq!(map["a"], 3);
+    assert!(map.contains_key("b"));
+    assert_eq!(map.get("c"), None);
+
+    *map.entry("c".to_owned()).or_default() += 5;
+    map.entry("a".to_owned())
+        .and_modify(|v| *v *= 10)
+        .or_insert(0);
+    assert!(map.spilled());
+    assert_eq!(
+        map.iter()
+            .map(|(k, v)| (k.as_str(), *v))
+            .collect::<Vec<_>>(),
+        [("a", 30), ("b", 2), ("c", 5)]
+    );
+
+    match map.entry("b".to_owned()) {
+        SmallMapEntry::Occupied(entry) => assert_eq!(entry.remove_entry(), ("b".to_owned(), 2)),
+        SmallMapEntry::Vacant(_) => unreachable!(),
+    }
+    match map.entry("d".to_owned()) {
+        SmallMapEntry::Occupied(_) => unreachable!(),
+        SmallMapEntry::Vacant(entry) => assert_eq!(entry.into_key(), "d"),
+    }
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "c"]);
+
+    for v in map.values_mut() {
+        *v += 1;
+    }
+    map.retain(|_, v| *v > 10);
+    assert_eq!(map.remove("a"), Some(31));
+    assert!(map.is_empty());
+
+    let a: M = vec![("x".to_owned(), 1), ("y".to_owned(), 2)]
+        .into_iter()
+        .collect();
+    let b: M = vec![("y".to_owned(), 2), ("x".to_owned(), 1)]
+        .into_iter()
+        .collect();
+    assert_eq!(a, b);
+    assert_eq!(alloc::format!("{:?}", a), r#"{"x": 1, "y": 2}"#);
+    assert_eq!(a.into_iter().count(), 2);
+}
+
+#[test]
+fn test_small_set() {
+    #[cfg(feature = "const_generics")]
+    type S = crate::SmallSet<u8, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type S = crate::SmallSet<[u8; 2]>;
+
+    let mut set: S = vec![3, 1, 3, 2].into_iter().collect();
+    assert_eq!(set.as_slice(), [3, 1, 2]);
+    assert!(set.spilled());
+    assert!(!set.insert(1));
+    assert!(set.remove(&3));
+    assert!(!set.remove(&3));
+    assert_eq!(set.take(&1), Some(1));
+    assert_eq!(set.get(&2), Some(&2));
+
+    let a: S = vec![1, 2].into_iter().collect();
+    let b: S = vec![2, 1].into_iter().collect();
+    assert_eq!(a, b);
+    assert!(set.is_subset(&a));
+    assert!(!a.is_subset(&set));
+}
+
+#[test]
+fn test_small_ord_set() {
+    #[cfg(feature = "const_generics")]
+    type S = crate::SmallOrdSet<u8, 4>;
+    #[cfg(not(feature = "const_generics"))]
+    type S = crate::SmallOrdSet<[u8; 4]>;
+
+    let mut a: S = vec![7, 1, 5, 1, 3].into_iter().collect();
+    assert_eq!(a.as_slice(), [1, 3, 5, 7]);
+    assert!(!a.insert(5));
+    assert!(a.insert(4));
+    assert!(a.spilled());
+    assert_eq!((a.first(), a.last()), (Some(&1), Some(&7)));
+    assert!(a.remove(&7));
+    assert_eq!(a.as_slice(), [1, 3, 4, 5]);
+
+    let b: S = vec![0, 4, 5, 9].into_iter().collect();
+    assert_eq!(a.union(&b)[..], [0, 1, 3, 4, 5, 9]);
+    assert_eq!(a.intersection(&b)[..], [4, 5]);
+    assert_eq!(a.difference(&b)[..], [1, 3]);
+    assert_eq!(a.symmetric_difference(&b)[..], [0, 1, 3, 9]);
+    assert!(!a.is_disjoint(&b));
+    assert!(S::from_smallvec(a.difference(&b)).is_disjoint(&b));
+    assert!(S::from_smallvec(a.intersection(&b)).is_subset(&a));
+    assert!(S::new().union(&S::new()).is_empty());
+}
+
+#[test]
+fn test_small_ord_map() {
+    #[cfg(feature = "const_generics")]
+    type M = crate::SmallOrdMap<u32, String, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type M = crate::SmallOrdMap<u32, String, [(u32, String); 2]>;
+
+    let mut map: M = vec![
+        (3, "c".to_owned()),
+        (1, "a".to_owned()),
+        (3, "C".to_owned()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&3], "C");
+    assert_eq!(map.insert(2, "b".to_owned()), None);
+    assert_eq!(map.insert(2, "B".to_owned()).as_deref(), Some("b"));
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(map.last_key_value().map(|(k, _)| *k), Some(3));
+
+    map.get_or_insert_with(0, || "z".to_owned()).push('!');
+    map.get_or_insert_with(0, || unreachable!()).push('?');
+    assert_eq!(map.first_key_value(), Some((&0, &"z!?".to_owned())));
+    assert_eq!(map.remove_entry(&2), Some((2, "B".to_owned())));
+    assert!(!map.contains_key(&2));
+    map.retain(|k, _| k % 3 == 0);
+    assert_eq!(alloc::format!("{:?}", map), r#"{0: "z!?", 3: "C"}"#);
+    assert_eq!(map.into_iter().map(|(k, _)| k).collect::<Vec<_>>(), [0, 3]);
+}
+
+#[test]
+fn test_small_vec_deque() {
+    #[cfg(feature = "const_generics")]
+    type D<T> = crate::SmallVecDeque<T, 4>;
+    #[cfg(not(feature = "const_generics"))]
+    type D<T> = crate::SmallVecDeque<[T; 4]>;
+
+    let mut deque: D<String> = D::new();
+    for i in 0..3 {
+        deque.push_back(i.to_string());
+    }
+    assert_eq!(deque.pop_front().as_deref(), Some("0"));
+    deque.push_back("3".to_owned());
+    // Wraps around the end of the inline buffer.
+    deque.push_back("4".to_owned());
+    assert!(!deque.spilled());
+    assert_eq!(deque.as_slices().1.len(), 1);
+    assert_eq!(
+        deque.iter().rev().map(|s| &**s).collect::<Vec<_>>(),
+        ["4", "3", "2", "1"]
+    );
+
+    // Grows while wrapped.
+    deque.push_front("0".to_owned());
+    assert!(deque.spilled());
+    assert_eq!(deque.len(), 5);
+    assert_eq!(deque[0], "0");
+    assert_eq!(deque.back().map(|s| &**s), Some("4"));
+    deque.iter_mut().for_each(|s| s.push('!'));
+    assert_eq!(
+        alloc::format!("{:?}", deque),
+        r#"["0!", "1!", "2!", "3!", "4!"]"#
+    );
+    assert_eq!(deque.pop_back().as_deref(), Some("4!"));
+    assert_eq!(deque.clone(), deque);
+
+    let mut deque: D<u32> = (0..4).collect();
+    deque.pop_front();
+    deque.push_back(4);
+    assert_eq!(deque.make_contiguous(), [1, 2, 3, 4]);
+    assert_eq!(deque.as_slices(), (&[1, 2, 3, 4][..], &[][..]));
+    assert_eq!(deque.into_smallvec()[..], [1, 2, 3, 4]);
+
+    let mut deque: D<u32> = D::new();
+    deque.extend(0..3);
+    deque.push_front(9);
+    assert_eq!(deque.into_iter().rev().collect::<Vec<_>>(), [2, 1, 0, 9]);
+
+    let mut zst: D<()> = D::new();
+    for _ in 0..10 {
+        zst.push_front(());
+    }
+    zst.make_contiguous();
+    assert_eq!(zst.len(), 10);
+    assert_eq!(zst.iter().count(), 10);
+
+    #[cfg(feature = "const_generics")]
+    let mut empty = crate::SmallVecDeque::<u8, 0>::new();
+    #[cfg(not(feature = "const_generics"))]
+    let mut empty = crate::SmallVecDeque::<[u8; 0]>::new();
+    empty.push_front(1);
+    empty.push_front(0);
+    empty.push_back(2);
+    assert_eq!(empty.iter().copied().collect::<Vec<_>>(), [0, 1, 2]);
+}
+
+#[test]
+fn test_small_bit_vec() {
+    #[cfg(feature = "const_generics")]
+    type B = crate::SmallBitVec<1>;
+    #[cfg(not(feature = "const_generics"))]
+    type B = crate::SmallBitVec<[u64; 1]>;
+
+    let mut bits = B::from_elem(true, 62);
+    assert!(bits.all());
+    bits.push(false);
+    bits.push(true);
+    assert!(!bits.spilled());
+    bits.push(true);
+    assert!(bits.spilled());
+    assert_eq!(bits.len(), 65);
+    assert_eq!(bits.count_ones(), 64);
+    assert_eq!(bits.get(62), Some(false));
+    assert_eq!(bits.get(65), None);
+    assert_eq!(bits.pop(), Some(true));
+    assert_eq!(bits.as_words(), [!0 ^ 1 << 62]);
+
+    bits.truncate(3);
+    bits.set(1, false);
+    assert_eq!(alloc::format!("{:?}", bits), "[true, false, true]");
+    assert!(bits[0] && !bits[1]);
+    bits.resize(70, true);
+    assert_eq!(bits.count_ones(), 69);
+    assert_eq!(bits.iter().rev().nth(68), Some(false));
+    bits.resize(2, false);
+    assert_eq!(bits.as_words(), [1]);
+
+    let a: B = [true, false, true, false].iter().copied().collect();
+    let mut b = B::from_elem(false, 100);
+    b.set(2, true);
+    b.set(99, true);
+    assert_eq!((&a & &b).iter_ones().collect::<Vec<_>>(), [2]);
+    assert_eq!((&a & &b).len(), 100);
+    assert_eq!((&a | &b).iter_ones().collect::<Vec<_>>(), [0, 2, 99]);
+    assert_eq!((&a ^ &b).iter_ones().collect::<Vec<_>>(), [0, 99]);
+    assert_eq!((!a.clone()).iter_ones().collect::<Vec<_>>(), [1, 3]);
+    assert_eq!(!!a.clone(), a);
+    assert!(!B::new().any());
+    assert!(B::new().all());
+    assert_eq!(B::new().iter_ones().next(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_small_cursor() {
+    use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+
+    #[cfg(feature = "const_generics")]
+    type C = crate::SmallCursor<4>;
+    #[cfg(not(feature = "const_generics"))]
+    type C = crate::SmallCursor<[u8; 4]>;
+
+    let mut cursor = C::default();
+    cursor.write_all(b"abc").unwrap();
+    assert_eq!(cursor.seek(SeekFrom::Current(-2)).unwrap(), 1);
+    cursor.write_all(b"BCD").unwrap();
+    assert_eq!(cursor.get_ref()[..], *b"aBCD");
+    assert!(!cursor.get_ref().spilled());
+
+    // Writing past the end zero-fills the gap.
+    cursor.seek(SeekFrom::End(2)).unwrap();
+    cursor.write_all(b"!").unwrap();
+    assert_eq!(cursor.get_ref()[..], *b"aBCD\0\0!");
+    assert!(cursor.seek(SeekFrom::Current(-8)).is_err());
+    assert_eq!(cursor.position(), 7);
+
+    cursor.set_position(1);
+    let mut buf = [0; 3];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"BCD");
+    assert_eq!(cursor.fill_buf().unwrap(), b"\0\0!");
+    cursor.consume(2);
+    assert_eq!(cursor.read(&mut buf).unwrap(), 1);
+    assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+    cursor.set_position(100);
+    assert_eq!(cursor.remaining_slice(), b"");
+
+    let mut lines = C::new(crate::smallvec![b'a', b'\n', b'b']).lines();
+    assert_eq!(lines.next().unwrap().unwrap(), "a");
+    assert_eq!(lines.next().unwrap().unwrap(), "b");
+    assert!(lines.next().is_none());
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[cfg(feature = "const_generics")]
+    type V1 = crate::SmallVec1<u8, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type V1 = crate::SmallVec1<[u8; 2]>;
+    #[cfg(feature = "const_generics")]
+    type M = crate::SmallOrdMap<u8, u8, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type M = crate::SmallOrdMap<u8, u8, [(u8, u8); 2]>;
+    #[cfg(feature = "const_generics")]
+    type S = crate::SmallString<8>;
+    #[cfg(not(feature = "const_generics"))]
+    type S = crate::SmallString<[u8; 8]>;
+    create_smallvec!(type V = SmallVec(u8, 2));
+
+    // Each element is preceded by a byte saying whether to continue.
+    let data = [1, 10, 1, 20, 0];
+    let v = V::arbitrary(&mut Unstructured::new(&data)).unwrap();
+    assert_eq!(&*v, &[10, 20]);
+    let v = V::arbitrary_take_rest(Unstructured::new(&[1, 2, 1, 3])).unwrap();
+    assert_eq!(&*v, &[2, 3]);
+    assert!(V::arbitrary(&mut Unstructured::new(&[]))
+        .unwrap()
+        .is_empty());
+    assert_eq!(V::size_hint(0), (0, None));
+
+    let v = V1::arbitrary(&mut Unstructured::new(&[7, 1, 8, 0])).unwrap();
+    assert_eq!(&*v, &[7, 8]);
+    assert_eq!(V1::size_hint(0).0, 1);
+
+    let map = M::arbitrary_take_rest(Unstructured::new(&[1, 3, 30, 1, 1, 10, 1, 3, 31])).unwrap();
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&1, &10), (&3, &31)]);
+
+    let s = S::arbitrary_take_rest(Unstructured::new(b"hi")).unwrap();
+    assert_eq!(s, "hi");
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn test_proptest_strategy() {
+    use proptest::{
+        arbitrary::any,
+        strategy::{Strategy, ValueTree},
+        test_runner::{TestCaseError, TestError, TestRunner},
+    };
+
+    create_smallvec!(type V = SmallVec(u8, 8));
+    let strategy = crate::proptest::smallvec(any::<u8>(), 0..64);
+    let mut runner = TestRunner::deterministic();
+    let result = runner.run(&strategy, |v: V| {
+        if v.len() > 5 {
+            Err(TestCaseError::fail("too long"))
+        } else {
+            Ok(())
+        }
+    });
+    match result {
+        Err(TestError::Fail(_, v)) => {
+            assert_eq!(&*v, &[0; 6]);
+            assert!(!v.spilled());
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let v = any::<V>().new_tree(&mut runner).unwrap().current();
+    assert!(v.len() <= 100);
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn test_quickcheck_arbitrary() {
+    use quickcheck::{Arbitrary, Gen};
+
+    create_smallvec!(type V = SmallVec(u8, 2));
+    let v = V::arbitrary(&mut Gen::new(10));
+    assert!(v.len() <= 10);
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::from_slice(&[5, 6, 7]));
+    let shrunk: Vec<V> = v.shrink().collect();
+    assert!(shrunk.iter().any(|s| s.is_empty()));
+    assert!(shrunk.iter().all(|s| s.len() <= 3));
+    assert!(shrunk.iter().filter(|s| s.len() <= 2).all(|s| !s.spilled()));
+    assert!(shrunk.iter().any(|s| s[..] == [5, 6]));
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_rkyv() {
+    use rkyv::{rancor::Error, vec::ArchivedVec};
+
+    create_smallvec!(let v: SmallVec(u32, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    let bytes = rkyv::to_bytes::<Error>(&v).unwrap();
+    let archived = unsafe { rkyv::access_unchecked::<ArchivedVec<rkyv::Archived<u32>>>(&bytes) };
+    assert_eq!(archived.len(), 3);
+    assert!(*archived == v);
+
+    create_smallvec!(let decoded: SmallVec(u32, 4) = rkyv::deserialize::<_, Error>(archived).unwrap());
+    assert_eq!(&*decoded, &[1, 2, 3]);
+    assert!(!decoded.spilled());
+    // Same layout as a `Vec`.
+    let vec: Vec<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(vec, [1, 2, 3]);
+    assert_eq!(&bytes[..], &rkyv::to_bytes::<Error>(&vec).unwrap()[..]);
+}
+
+#[cfg(feature = "bincode2")]
+#[test]
+fn test_bincode2() {
+    use bincode2::{borrow_decode_from_slice, config, decode_from_slice, encode_to_vec};
+
+    create_smallvec!(let v: SmallVec(u16, 2) = SmallVec::from_slice(&[1, 2, 300]));
+    let bytes = encode_to_vec(&v, config::standard()).unwrap();
+    assert_eq!(
+        bytes,
+        encode_to_vec(v.to_vec(), config::standard()).unwrap()
+    );
+
+    create_smallvec!(type V = SmallVec(u16, 4));
+    let (decoded, read): (V, _) = decode_from_slice(&bytes, config::standard()).unwrap();
+    assert_eq!(read, bytes.len());
+    assert_eq!(&*decoded, &[1, 2, 300]);
+    assert!(!decoded.spilled());
+
+    #[cfg(feature = "const_generics")]
+    type S<'a> = SmallVec<&'a str, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type S<'a> = SmallVec<[&'a str; 2]>;
+    let bytes = encode_to_vec(&["a", "bc"][..], config::standard()).unwrap();
+    let (decoded, _): (S, _) = borrow_decode_from_slice(&bytes, config::standard()).unwrap();
+    assert_eq!(&*decoded, &["a", "bc"]);
+
+    let limited = config::standard().with_limit::<8>();
+    assert!(decode_from_slice::<V, _>(&[200, 0, 0, 0, 0, 0, 0, 0, 0], limited).is_err());
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck() {
+    use crate::{cast_smallvec, try_cast_smallvec};
+    use bytemuck::PodCastError;
+
+    create_smallvec!(let mut v: SmallVec(u32, 2) = SmallVec::from_slice(&[1, 2, 3, 4]));
+    assert_eq!(v.as_bytes().len(), 16);
+    v.as_bytes_mut()[0] = 9;
+    assert_eq!(v[0], u32::from_ne_bytes([9, 0, 0, 0]));
+
+    // Spilled, same alignment: the buffer is reused.
+    let ptr = v.as_ptr() as *const u8;
+    create_smallvec!(let halves: SmallVec([u16; 2], 2) = cast_smallvec(v.clone()));
+    assert_eq!(halves.len(), 4);
+    create_smallvec!(let bytes: SmallVec(u8, 2) = cast_smallvec(v.clone()));
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(&bytes[..], v.as_bytes());
+    create_smallvec!(let pairs: SmallVec([u32; 2], 1) = try_cast_smallvec(v).unwrap());
+    assert_eq!(pairs.as_ptr() as *const u8, ptr);
+    assert_eq!(pairs[1], [3, 4]);
+
+    // Fits inline after the cast, so it is copied.
+    create_smallvec!(let quad: SmallVec([u32; 4], 2) = cast_smallvec(pairs));
+    assert!(!quad.spilled());
+    assert_eq!(quad[0][1], 2);
+
+    create_smallvec!(let odd: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2, 3]));
+    create_smallvec!(type W = SmallVec(u16, 4));
+    let result: Result<W, _> = try_cast_smallvec(odd);
+    match result {
+        Err((PodCastError::OutputSliceWouldHaveSlop, odd)) => assert_eq!(&odd[..], &[1, 2, 3]),
+        _ => panic!(),
+    }
+    assert_eq!(
+        &*W::try_from_bytes(&[1, 0, 2, 0]).unwrap(),
+        &[1u16.to_le(), 2u16.to_le()]
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_rayon() {
+    use rayon::prelude::*;
+
+    create_smallvec!(type V = SmallVec(u32, 4));
+    let mut v: V = (0..100u32).into_par_iter().collect();
+    assert_eq!(v.len(), 100);
+    assert!(v.iter().copied().eq(0..100));
+    v.par_iter_mut().for_each(|x| *x *= 2);
+    assert_eq!(v.par_iter().sum::<u32>(), 9900);
+    assert_eq!(v.clone().into_par_iter().max(), Some(198));
+
+    let mut small: V = (0..2u32).into_par_iter().collect();
+    assert!(!small.spilled());
+    small.par_extend(vec![7, 8]);
+    assert_eq!(&*small, &[0, 1, 7, 8]);
+    let mut empty = V::new();
+    empty.par_extend(0..10u32);
+    assert!(empty.iter().copied().eq(0..10));
+}
+
+#[test]
+fn test_inline_vec() {
+    use crate::{CollectionAllocErr, InlineVec};
+    use core::convert::TryFrom;
+
+    create_smallvec!(type V = SmallVec(u8, 3));
+    #[cfg(feature = "const_generics")]
+    type I = InlineVec<u8, 3>;
+    #[cfg(not(feature = "const_generics"))]
+    type I = InlineVec<[u8; 3]>;
+
+    let mut v = I::new();
+    assert_eq!(v.capacity(), 3);
+    assert_eq!(v.push(1), Ok(()));
+    assert_eq!(v.insert(0, 0), Ok(()));
+    assert_eq!(v.remaining_capacity(), 1);
+    assert!(matches!(
+        v.try_extend_from_slice(&[2, 3]),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    assert_eq!(&*v, &[0, 1]);
+    v.try_extend_from_slice(&[2]).unwrap();
+    assert!(v.is_full());
+    assert_eq!(v.push(3), Err(3));
+    assert_eq!(v.insert(1, 4), Err(4));
+    assert!(!v.as_smallvec().spilled());
+    assert_eq!(v.swap_remove(0), 0);
+    v.retain(|x| *x != 1);
+    assert_eq!(&*v, &[2]);
+
+    let mut big = V::from_slice(&[1, 2, 3, 4]);
+    big = I::try_from(big).unwrap_err();
+    big.pop();
+    let v = I::try_from(big).unwrap();
+    assert!(!v.as_smallvec().spilled());
+    assert_eq!(V::from(v.clone()), V::from_slice(&[1, 2, 3]));
+    assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    assert_eq!(&*I::try_from(&[1, 2][..]).unwrap(), &[1, 2]);
+    assert!(matches!(
+        I::try_from(&[1, 2, 3, 4][..]),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    let mut vec = I::try_from(vec![1, 2, 3, 4]).unwrap_err();
+    vec.pop();
+    let v = I::try_from(vec).unwrap();
+    assert!(!v.as_smallvec().spilled());
+    assert_eq!(&*v, &[1, 2, 3]);
+}
+
+#[test]
+fn test_compact_size() {
+    use core::mem::size_of;
+
+    // The inline buffer overlaps the heap pointer and length, leaving only the capacity beside.
+    create_smallvec!(type Bytes = SmallVec(u8, 8));
+    create_smallvec!(type Words = SmallVec(usize, 2));
+    #[cfg(not(feature = "u32_len"))]
+    assert_eq!(size_of::<Bytes>(), size_of::<usize>() * 3);
+    assert_eq!(size_of::<Words>(), size_of::<usize>() * 3);
+
+    let mut v = Bytes::from_slice(b"abcdefgh");
+    assert!(!v.spilled());
+    v.push(b'i');
+    assert!(v.spilled());
+    v.truncate(2);
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    assert_eq!(&v[..], b"ab");
+}
+
+#[test]
+fn test_option_niche() {
+    use core::mem::size_of;
+
+    create_smallvec!(type Small = SmallVec(u32, 2));
+    create_smallvec!(type Large = SmallVec(u64, 4));
+    assert_eq!(size_of::<Option<Small>>(), size_of::<Small>());
+    assert_eq!(size_of::<Option<Large>>(), size_of::<Large>());
+
+    let mut v: Option<Small> = Some(Small::new());
+    v.as_mut().unwrap().extend(0..5);
+    assert!(v.as_ref().unwrap().spilled());
+    assert_eq!(v.take().unwrap().into_iter().sum::<u32>(), 10);
+    assert!(v.is_none());
+
+    // Zero-sized elements can't reach the capacity that would collide with the niche.
+    let max = crate::small_vec_data::NonMaxUsize::MAX;
+    create_smallvec!(type Units = SmallVec((), 2));
+    assert!(Units::try_with_capacity(max + 1).is_err());
+    assert_eq!(Units::try_with_capacity(max).unwrap().capacity(), max);
+}
+
+#[test]
+fn test_extend_exact_size_sources() {
+    create_smallvec!(let mut v: SmallVec(u32, 4) = SmallVec::new());
+    v.extend([1, 2, 3].iter().copied());
+    assert!(!v.spilled());
+    v.extend([4, 5, 6].iter().copied());
+    assert!(v.spilled());
+    assert_eq!(&*v, &[1, 2, 3, 4, 5, 6]);
+
+    let mut source = vec![7, 8, 9, 10].into_iter();
+    source.next();
+    v.extend(source);
+    create_smallvec!(let mut other: SmallVec(u32, 4) = SmallVec::from_slice(&[11, 12, 13]));
+    other.extend(0..0);
+    let mut source = other.into_iter();
+    source.next_back();
+    v.extend(source);
+    assert_eq!(&*v, &[1, 2, 3, 4, 5, 6, 8, 9, 10, 11, 12]);
+
+    // Moved items are dropped once, by their new owner.
+    let item = Rc::new(());
+    create_smallvec!(type R = SmallVec(Rc<()>, 2));
+    let mut items = R::new();
+    let mut source = vec![item.clone(); 3].into_iter();
+    source.next();
+    items.extend(source);
+    let mut source = R::from_elem(item.clone(), 3).into_iter();
+    source.next();
+    items.extend(source);
+    assert_eq!(Rc::strong_count(&item), 5);
+    drop(items);
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn test_fill_copy_elements() {
+    create_smallvec!(let mut bytes: SmallVec(i8, 4) = SmallVec::from_elem(-1, 3));
+    assert_eq!(&*bytes, &[-1; 3]);
+    bytes.resize(6, 5);
+    assert!(bytes.spilled());
+    assert_eq!(&*bytes, &[-1, -1, -1, 5, 5, 5]);
+
+    create_smallvec!(let mut words: SmallVec(u16, 2) = SmallVec::from_elem(0x102, 2));
+    words.resize(5, 7);
+    assert_eq!(&*words, &[0x102, 0x102, 7, 7, 7]);
+    words.resize(1, 9);
+    assert_eq!(&*words, &[0x102]);
+
+    create_smallvec!(let filled: SmallVec(u8, 8) = smallvec![0xAA; 100]);
+    assert_eq!(filled.len(), 100);
+    assert!(filled.iter().all(|&b| b == 0xAA));
+}
+
+#[test]
+fn test_fmt_write() {
+    use core::fmt::Write;
+
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::new());
+    write!(v, "{}-{:02x}", 12, 0xab).unwrap();
+    v.write_char('é').unwrap();
+    assert_eq!(&*v, "12-abé".as_bytes());
+}
+
+#[test]
+fn test_small_cow() {
+    use crate::SmallCow;
+
+    #[cfg(feature = "const_generics")]
+    type C<'a> = SmallCow<'a, u8, 4>;
+    #[cfg(not(feature = "const_generics"))]
+    type C<'a> = SmallCow<'a, [u8; 4]>;
+
+    let defaults = [1, 2, 3];
+    let borrowed = C::from(&defaults[..]);
+    assert!(borrowed.is_borrowed());
+    assert_eq!(&*borrowed, &defaults);
+    assert_eq!(C::default(), C::from(&[][..]));
+
+    let mut tweaked = borrowed.clone();
+    tweaked.to_mut().push(4);
+    assert!(tweaked.is_owned());
+    assert_ne!(tweaked, borrowed);
+    assert_eq!(&*tweaked, &[1, 2, 3, 4]);
+    tweaked.to_mut().push(5);
+    assert!(tweaked.as_ref().len() == 5 && tweaked.clone().into_owned().spilled());
+
+    let owned = borrowed.into_owned();
+    assert!(!owned.spilled());
+    assert_eq!(C::from(owned.clone()), C::from(&defaults[..]));
+    assert_eq!(owned.into_iter().sum::<u8>(), 6);
+}
+
+#[test]
+fn test_push_within_capacity() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    assert_eq!(v.push_within_capacity(1), Ok(()));
+    assert_eq!(v.push_within_capacity(2), Ok(()));
+    assert_eq!(v.push_within_capacity(3), Err(3));
+    assert!(!v.spilled());
+
+    v.reserve_exact(2);
+    let cap = v.capacity();
+    while v.push_within_capacity(0).is_ok() {}
+    assert_eq!(v.len(), cap);
+    assert_eq!(v.capacity(), cap);
+}
+
+#[test]
+fn test_pop_if() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    assert_eq!(v.pop_if(|x| *x == 2), None);
+    assert_eq!(
+        v.pop_if(|x| {
+            *x += 1;
+            *x == 4
+        }),
+        Some(4)
+    );
+    assert_eq!(v.pop_if(|x| *x == 2), Some(2));
+    assert_eq!(v.pop_if(|_| true), Some(1));
+    assert_eq!(v.pop_if(|_| true), None);
+}
+
+#[test]
+fn test_extend_from_refs() {
+    let data = [1, 2, 3, 4, 5];
+    create_smallvec!(let mut v: SmallVec(u32, 4) = data[..2].iter().collect());
+    assert!(!v.spilled());
+    v.extend(data[2..].iter());
+    v.extend(&[6]);
+    assert_eq!(&*v, &[1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_boxed_slice_conversions() {
+    let boxed: Box<[u32]> = vec![1, 2, 3, 4, 5].into_boxed_slice();
+    let ptr = boxed.as_ptr();
+    create_smallvec!(let mut v: SmallVec(u32, 4) = SmallVec::from(boxed));
+    assert!(v.spilled());
+    assert_eq!(v.as_ptr(), ptr);
+    v.push(6);
+    assert_eq!(&*v, &[1, 2, 3, 4, 5, 6]);
+
+    let boxed: Box<[u32]> = v.into();
+    assert_eq!(&*boxed, &[1, 2, 3, 4, 5, 6]);
+
+    create_smallvec!(let small: SmallVec(u32, 4) = SmallVec::from(Box::from(&[7, 8][..])));
+    assert!(!small.spilled());
+    assert_eq!(Box::<[u32]>::from(small), Box::from(&[7, 8][..]));
+}
+
+#[test]
+fn test_cow_conversions() {
+    use alloc::borrow::Cow;
+
+    let data = [1, 2, 3];
+    create_smallvec!(let v: SmallVec(u8, 2) = Cow::Borrowed(&data[..]).into());
+    assert_eq!(&*v, &data);
+
+    let owned = vec![4, 5, 6];
+    let ptr = owned.as_ptr();
+    create_smallvec!(let v: SmallVec(u8, 2) = Cow::<[u8]>::Owned(owned).into());
+    assert_eq!(v.as_ptr(), ptr);
+
+    let cow: Cow<[u8]> = v.into();
+    assert!(matches!(cow, Cow::Owned(ref vec) if vec.as_ptr() == ptr));
+}
+
+#[test]
+fn test_drain_keep_rest() {
+    create_smallvec!(let mut v: SmallVec(Box<u8>, 2) = (0..6).map(Box::new).collect());
+    let mut drain = v.drain(1..5);
+    assert_eq!(drain.as_slice().len(), 4);
+    assert_eq!(drain.next().map(|x| *x), Some(1));
+    assert_eq!(drain.next_back().map(|x| *x), Some(4));
+    assert_eq!(drain.as_slice(), &[Box::new(2), Box::new(3)]);
+    drain.keep_rest();
+    assert_eq!(v.iter().map(|x| **x).collect::<Vec<_>>(), [0, 2, 3, 5]);
+
+    v.drain(..).keep_rest();
+    assert_eq!(v.len(), 4);
+    let mut drain = v.drain(2..);
+    drain.by_ref().for_each(drop);
+    drain.keep_rest();
+    assert_eq!(v.iter().map(|x| **x).collect::<Vec<_>>(), [0, 2]);
+
+    create_smallvec!(let mut units: SmallVec((), 2) = SmallVec::from_elem((), 5));
+    let mut drain = units.drain(1..4);
+    drain.next();
+    drain.keep_rest();
+    assert_eq!(units.len(), 4);
+}
+
+#[test]
+fn test_into_iter_slices_clone_debug_default() {
+    create_smallvec!(let v: SmallVec(String, 2) = ["a", "b", "c"].iter().map(|s| s.to_string()).collect());
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next().as_deref(), Some("a"));
+    iter.as_mut_slice()[1].push('!');
+    assert_eq!(iter.as_slice(), &["b".to_string(), "c!".to_string()]);
+    assert_eq!(alloc::format!("{:?}", iter), r#"IntoIter(["b", "c!"])"#);
+
+    let copy = iter.clone();
+    assert_eq!(iter.next_back().as_deref(), Some("c!"));
+    assert_eq!(copy.collect::<Vec<_>>(), ["b", "c!"]);
+    assert_eq!(iter.as_slice().len(), 1);
+
+    #[cfg(feature = "const_generics")]
+    type I = crate::IntoIter<u8, 2>;
+    #[cfg(not(feature = "const_generics"))]
+    type I = crate::IntoIter<[u8; 2]>;
+    assert_eq!(I::default().len(), 0);
+}
+
+#[test]
+fn test_cross_type_eq() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    create_smallvec!(let other: SmallVec(u8, 8) = SmallVec::from_slice(&[1, 2, 3]));
+    assert!(v == other);
+    assert!(v == [1, 2, 3]);
+    assert!(v != [1, 2]);
+    assert!(v == [1u8, 2, 3][..]);
+    assert_eq!(v, &[1, 2, 3][..]);
+    assert_eq!(v, &mut [1, 2, 3][..]);
+    assert!(v == vec![1, 2, 3]);
+
+    v.pop();
+    assert!(v != other);
+    assert!(v != vec![1, 2, 3]);
+    assert!(v == [1, 2]);
+}
+
+#[test]
+fn test_cross_type_ord() {
+    use core::cmp::Ordering;
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    create_smallvec!(let other: SmallVec(u8, 8) = SmallVec::from_slice(&[1, 3]));
+    assert!(v < other);
+    assert!(v > [1, 2]);
+    assert!(v <= [1, 2, 3]);
+    assert!(v < [1u8, 2, 4][..]);
+    assert!(v > vec![0, 9]);
+    assert_eq!(v.partial_cmp(&&[1, 2, 3][..]), Some(Ordering::Equal));
+    assert_eq!(v.partial_cmp(&&mut [2][..]), Some(Ordering::Less));
+
+    create_smallvec!(let nan: SmallVec(f32, 2) = SmallVec::from_slice(&[f32::NAN]));
+    assert_eq!(nan.partial_cmp(&[f32::NAN]), None);
+}
+
+#[cfg(feature = "embedded-io")]
+#[test]
+fn test_embedded_io() {
+    use embedded_io::{BufRead, Read, Write};
+
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::new());
+    v.write_all(b"abc").unwrap();
+    write!(v, "{}", 12).unwrap();
+    v.flush().unwrap();
+    assert_eq!(&v[..], b"abc12");
+
+    let mut reader = v.into_iter();
+    assert_eq!(reader.fill_buf().unwrap(), b"abc12");
+    reader.consume(1);
+    let mut buf = [0; 3];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"bc1");
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    reader.consume(1);
+    assert_eq!(reader.next(), None);
+}
+
+// Actually formatting needs a `defmt` logger and linker script, so only the impls are checked.
+#[cfg(feature = "defmt")]
+#[test]
+fn test_defmt_format() {
+    fn assert_format<T: defmt::Format + ?Sized>() {}
+
+    create_smallvec!(type Bytes = SmallVec(u8, 4));
+    create_smallvec!(type Nested = SmallVec(Bytes, 2));
+    assert_format::<Bytes>();
+    assert_format::<Nested>();
+}
+
+#[cfg(feature = "may_dangle")]
+#[test]
+fn test_may_dangle() {
+    create_smallvec!(let mut v: SmallVec(&i32, 2) = SmallVec::new());
+    let x = 1;
+    v.push(&x);
+    v.push(&x);
+    v.push(&x);
+    assert_eq!(v.iter().map(|x| **x).sum::<i32>(), 3);
+}
+
+#[test]
+fn test_try_into_array() {
+    use core::convert::TryFrom;
+
+    create_smallvec!(let v: SmallVec(String, 2) = ["a", "b", "c"].iter().map(|s| s.to_string()).collect());
+    assert!(v.spilled());
+    let v = <[String; 2]>::try_from(v).unwrap_err();
+    let v = <[String; 4]>::try_from(v).unwrap_err();
+    assert_eq!(<[String; 3]>::try_from(v).unwrap(), ["a", "b", "c"]);
+
+    create_smallvec!(let inline: SmallVec(String, 4) = SmallVec::from_elem(String::from("x"), 1));
+    assert_eq!(<[String; 1]>::try_from(inline).unwrap(), ["x"]);
+
+    create_smallvec!(let empty: SmallVec(u8, 2) = SmallVec::new());
+    assert_eq!(<[u8; 0]>::try_from(empty).unwrap(), []);
+
+    create_smallvec!(let units: SmallVec((), 1) = SmallVec::from_elem((), 5));
+    assert_eq!(<[(); 5]>::try_from(units).unwrap(), [(); 5]);
+}
+
+#[cfg(feature = "u32_len")]
+#[test]
+fn test_u32_len() {
+    use crate::CollectionAllocErr;
+    use core::mem::size_of;
+
+    create_smallvec!(type Bytes = SmallVec(u8, 8));
+    create_smallvec!(type Words = SmallVec(u32, 3));
+    if size_of::<usize>() == 8 {
+        assert_eq!(size_of::<Bytes>(), 16);
+        assert_eq!(size_of::<Words>(), 16);
+        assert_eq!(size_of::<Option<Bytes>>(), 16);
+    }
+
+    let mut v = Bytes::new();
+    v.extend(0..20);
+    assert!(v.spilled());
+    assert_eq!(v.len(), 20);
+    v.truncate(3);
+    v.shrink_to_fit();
+    assert_eq!(&v[..], &[0, 1, 2]);
+
+    create_smallvec!(let mut units: SmallVec((), 2) = SmallVec::new());
+    assert!(units.try_reserve(u32::MAX as usize - 1).is_ok());
+    assert_eq!(units.capacity(), u32::MAX as usize - 1);
+    assert!(matches!(
+        units.try_reserve(u32::MAX as usize),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+
+    let mut big = Vec::<()>::new();
+    #[allow(clippy::uninit_vec)]
+    unsafe {
+        big.set_len(u32::MAX as usize)
+    };
+    create_smallvec!(type Units = SmallVec((), 2));
+    assert!(matches!(
+        Units::try_from_vec_compact(big),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+}
+
+#[test]
+fn test_insert_sorted() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    for &x in &[5, 1, 4, 1, 9, 2] {
+        v.insert_sorted(x);
+    }
+    assert_eq!(&*v, &[1, 1, 2, 4, 5, 9]);
+
+    create_smallvec!(let mut v: SmallVec((u8, char), 2) = SmallVec::new());
+    assert_eq!(v.insert_sorted_by_key((2, 'a'), |&(k, _)| k), 0);
+    assert_eq!(v.insert_sorted_by_key((1, 'b'), |&(k, _)| k), 0);
+    // Equal keys keep their insertion order.
+    assert_eq!(v.insert_sorted_by_key((2, 'c'), |&(k, _)| k), 2);
+    assert_eq!(v.insert_sorted_by_key((1, 'd'), |&(k, _)| k), 1);
+    assert_eq!(&*v, &[(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[9, 5, 1]));
+    assert_eq!(v.insert_sorted_by(3, |a, b| b.cmp(a)), 2);
+    assert_eq!(&*v, &[9, 5, 3, 1]);
+}
+
+#[cfg(feature = "extend_one")]
+#[test]
+fn test_extend_one() {
+    create_smallvec!(let mut v: SmallVec(u8, 2) = SmallVec::new());
+    Extend::<u8>::extend_reserve(&mut v, 10);
+    assert!(v.capacity() >= 10);
+    v.extend_one(1);
+    Extend::<&u8>::extend_one(&mut v, &2);
+    Extend::<&u8>::extend_reserve(&mut v, 20);
+    assert!(v.capacity() >= 22);
+    assert_eq!(&*v, &[1, 2]);
+
+    create_smallvec!(type Evens = SmallVec(u32, 2));
+    create_smallvec!(type Odds = SmallVec(u32, 4));
+    let (evens, odds): (Evens, Evens) = (0..10).partition(|x| x % 2 == 0);
+    assert_eq!(&*evens, &[0, 2, 4, 6, 8]);
+    assert_eq!(&*odds, &[1, 3, 5, 7, 9]);
+    let (small, large): (Evens, Odds) = (0..3).map(|x| (x, x * 10)).unzip();
+    assert_eq!(&*small, &[0, 1, 2]);
+    assert_eq!(&*large, &[0, 10, 20]);
+}
+
+#[test]
+fn test_into_shared_slice() {
+    use alloc::sync::Arc;
+
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2, 3]));
+    let rc: Rc<[u8]> = v.clone().into();
+    let arc: Arc<[u8]> = v.into();
+    assert_eq!(&*rc, &[1, 2, 3]);
+    assert_eq!(&*arc, &[1, 2, 3]);
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    assert!(v.spilled());
+    let rc: Rc<[u8]> = v.clone().into();
+    let arc: Arc<[u8]> = v.into();
+    assert_eq!(&*rc, &[1, 2, 3]);
+    assert_eq!(&*arc, &[1, 2, 3]);
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::new());
+    let rc: Rc<[u8]> = v.into();
+    assert!(rc.is_empty());
+
+    // The elements are moved, not cloned and dropped.
+    let elem = Rc::new(0);
+    create_smallvec!(let v: SmallVec(Rc<i32>, 4) = smallvec![elem.clone(), elem.clone()]);
+    let rc: Rc<[Rc<i32>]> = v.into();
+    assert_eq!(Rc::strong_count(&elem), 3);
+    drop(rc);
+    assert_eq!(Rc::strong_count(&elem), 1);
+}
+
+#[test]
+fn test_to_smallvec() {
+    use crate::ToSmallVec;
+
+    let words = ["a".to_owned(), "b".to_owned(), "c".to_owned()];
+    create_smallvec!(let v: SmallVec(String, 4) = words[1..].to_smallvec());
+    assert!(!v.spilled());
+    assert_eq!(&*v, &["b", "c"]);
+
+    create_smallvec!(let v: SmallVec(String, 2) = words.to_smallvec());
+    assert!(v.spilled());
+    assert_eq!(&*v, &words[..]);
+
+    #[cfg(feature = "const_generics")]
+    let v = [1, 2, 3][..].to_smallvec::<3>();
+    #[cfg(not(feature = "const_generics"))]
+    let v = [1, 2, 3][..].to_smallvec::<[i32; 3]>();
+    assert_eq!(v.inline_size(), 3);
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+fn test_wasm_round_trip() {
+    use js_sys::Uint8Array;
+    use wasm_bindgen::{JsCast, JsValue};
+
+    create_smallvec!(let v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2, 3]));
+    let array = Uint8Array::from(&v);
+    assert_eq!(array.to_vec(), [1, 2, 3]);
+    create_smallvec!(let back: SmallVec(u8, 4) = SmallVec::from(&array));
+    assert!(!back.spilled());
+    assert_eq!(back, v);
+
+    create_smallvec!(let v: SmallVec(u8, 2) = SmallVec::from_slice(&[4, 5, 6, 7]));
+    let value = JsValue::from(v.clone());
+    create_smallvec!(let back: SmallVec(u8, 2) = value.dyn_into::<Uint8Array>().unwrap().into());
+    assert!(back.spilled());
+    assert_eq!(back, v);
+}
@@ -0,0 +1,149 @@
+//! Aliasing patterns that are easy to get wrong in the unsafe code. They pass under a plain
+//! `cargo test` too, but are meant to be run under Miri (see `scripts/test-miri.sh`).
+
+use crate::SmallVec;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[test]
+fn as_mut_ptr_interleaved_with_reads() {
+    create_smallvec!(let mut inline: SmallVec(u32, 4) = SmallVec::from_slice(&[1, 2, 3]));
+    create_smallvec!(let mut spilled: SmallVec(u32, 2) = SmallVec::from_slice(&[1, 2, 3]));
+    assert!(!inline.spilled());
+    assert!(spilled.spilled());
+
+    unsafe {
+        let p = inline.as_mut_ptr();
+        *p = 10;
+        assert_eq!(inline.len(), 3);
+        *p.add(2) = 30;
+        assert_eq!(*inline.as_ptr().add(2), 30);
+        *p.add(1) = 20;
+
+        let p = spilled.as_mut_ptr();
+        *p = 10;
+        assert_eq!(spilled.len(), 3);
+        *p.add(2) = 30;
+        assert_eq!(*spilled.as_ptr().add(2), 30);
+        *p.add(1) = 20;
+    }
+    assert_eq!(&*inline, &[10, 20, 30]);
+    assert_eq!(&*spilled, &[10, 20, 30]);
+}
+
+#[test]
+fn set_len_keeps_element_pointers() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..3).map(|i: u8| i.to_string()));
+
+    unsafe {
+        let p = v.as_mut_ptr();
+        let len = v.len();
+        // Hide the elements, move one out and put it back, like `Drain` and `retain` do.
+        v.set_len(0);
+        let s = p.add(1).read();
+        assert!(v.is_empty());
+        p.add(1).write(s + "!");
+        v.set_len(len);
+    }
+    assert_eq!(&*v, &["0", "1!", "2"]);
+
+    unsafe {
+        let p = v.as_mut_ptr();
+        v.set_len(1);
+        drop(p.add(1).read());
+        drop(p.add(2).read());
+    }
+    assert_eq!(&*v, &["0"]);
+}
+
+#[test]
+fn drain_then_reborrow() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..5).map(|i: u8| i.to_string()));
+    let first: *const String = &v[0];
+    assert_eq!(unsafe { &*first }, "0");
+
+    {
+        let mut drain = v.drain(..);
+        assert_eq!(drain.next().as_deref(), Some("0"));
+        assert_eq!(drain.next_back().as_deref(), Some("4"));
+        // The rest is dropped with the iterator.
+    }
+    assert!(v.is_empty());
+
+    v.push(String::from("a"));
+    let r = &mut v[0];
+    r.push('b');
+    assert_eq!(&*v, &["ab"]);
+    assert_eq!(v.drain(..).collect::<Vec<_>>(), &["ab"]);
+}
+
+#[test]
+fn unspill_after_heap_references() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..4).map(|i: u8| i.to_string()));
+    assert!(v.spilled());
+    {
+        let heap_ref = &mut v[3];
+        heap_ref.push('!');
+        let all: &[String] = &v;
+        assert_eq!(all[3], "3!");
+    }
+
+    v.truncate(2);
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    v[1].push('?');
+    assert_eq!(&*v, &["0", "1?"]);
+
+    // And back to the heap.
+    v.push(String::from("2"));
+    assert!(v.spilled());
+    let p = v.as_mut_ptr();
+    unsafe { (*p.add(2)).push('#') };
+    assert_eq!(&*v, &["0", "1?", "2#"]);
+}
+
+#[test]
+fn grow_and_shrink_through_reserve() {
+    create_smallvec!(let mut v: SmallVec(u8, 4) = SmallVec::from_slice(&[1, 2]));
+    let before = v.as_ptr();
+    assert!(v.reserve_in_place(2));
+    assert_eq!(v.as_ptr(), before);
+    assert!(!v.reserve_in_place(16));
+    v.extend_from_slice(&[3, 4]);
+    v.grow(4);
+    assert!(!v.spilled());
+    assert_eq!(&*v, &[1, 2, 3, 4]);
+}
+
+#[test]
+fn cursor_edits_inline_and_spilled() {
+    create_smallvec!(let mut v: SmallVec(String, 3) = SmallVec::new());
+    v.extend((0..3).map(|i: u8| i.to_string()));
+    {
+        let mut cursor = v.cursor_mut();
+        cursor.move_next();
+        cursor.insert_before(String::from("a"));
+        cursor.current().unwrap().push('!');
+        cursor.insert_after(String::from("b"));
+        drop(cursor.remove_current());
+        cursor.move_prev();
+        cursor.peek_prev().unwrap().push('?');
+    }
+    assert_eq!(&*v, &["0?", "a", "b", "2"]);
+}
+
+#[test]
+fn into_iter_partial_drop() {
+    create_smallvec!(let mut v: SmallVec(String, 2) = SmallVec::new());
+    v.extend((0..3).map(|i: u8| i.to_string()));
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next().as_deref(), Some("0"));
+    assert_eq!(iter.next_back().as_deref(), Some("2"));
+    assert_eq!(iter.len(), 1);
+    drop(iter);
+}
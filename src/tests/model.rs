@@ -0,0 +1,207 @@
+//! Random operation sequences applied in lockstep to a `SmallVec` and a `Vec` oracle.
+//!
+//! The elements count how many of them are alive, so leaks and double drops show up as well as
+//! differences in the contents.
+
+use crate::SmallVec;
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::Cell;
+use quickcheck::{Arbitrary, Gen, QuickCheck};
+
+#[derive(Debug)]
+struct Counted {
+    value: u8,
+    alive: Rc<Cell<usize>>,
+}
+
+impl Counted {
+    fn new(value: u8, alive: &Rc<Cell<usize>>) -> Self {
+        alive.set(alive.get() + 1);
+        Counted {
+            value,
+            alive: alive.clone(),
+        }
+    }
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        Counted::new(self.value, &self.alive)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.alive.set(self.alive.get() - 1);
+    }
+}
+
+impl PartialEq for Counted {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Push(u8),
+    Pop,
+    Insert(usize, u8),
+    Remove(usize),
+    SwapRemove(usize),
+    Truncate(usize),
+    Clear,
+    Reserve(usize),
+    ReserveExact(usize),
+    ShrinkToFit,
+    Extend(Vec<u8>),
+    Retain(u8),
+    Dedup,
+    Drain(usize, usize),
+    Resize(usize, u8),
+    Splice(usize, usize, Vec<u8>),
+}
+
+impl Arbitrary for Op {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let small = |g: &mut Gen| usize::from(u8::arbitrary(g) % 24);
+        match u8::arbitrary(g) % 16 {
+            0 => Op::Push(u8::arbitrary(g)),
+            1 => Op::Pop,
+            2 => Op::Insert(small(g), u8::arbitrary(g)),
+            3 => Op::Remove(small(g)),
+            4 => Op::SwapRemove(small(g)),
+            5 => Op::Truncate(small(g)),
+            6 => Op::Clear,
+            7 => Op::Reserve(small(g)),
+            8 => Op::ReserveExact(small(g)),
+            9 => Op::ShrinkToFit,
+            10 => Op::Extend(Vec::arbitrary(g)),
+            11 => Op::Retain(u8::arbitrary(g) % 4 + 1),
+            12 => Op::Dedup,
+            13 => Op::Drain(small(g), small(g)),
+            14 => Op::Resize(small(g), u8::arbitrary(g)),
+            _ => Op::Splice(small(g), small(g), Vec::arbitrary(g)),
+        }
+    }
+}
+
+fn apply(ops: Vec<Op>) -> bool {
+    create_smallvec!(type V = SmallVec(Counted, 4));
+
+    let alive = Rc::new(Cell::new(0));
+    let mut small = V::new();
+    let mut oracle = Vec::new();
+
+    for op in ops {
+        match op {
+            Op::Push(x) => {
+                small.push(Counted::new(x, &alive));
+                oracle.push(Counted::new(x, &alive));
+            }
+            Op::Pop => {
+                if small.pop() != oracle.pop() {
+                    return false;
+                }
+            }
+            Op::Insert(index, x) => {
+                let index = index % (oracle.len() + 1);
+                small.insert(index, Counted::new(x, &alive));
+                oracle.insert(index, Counted::new(x, &alive));
+            }
+            Op::Remove(index) if !oracle.is_empty() => {
+                let index = index % oracle.len();
+                if small.remove(index) != oracle.remove(index) {
+                    return false;
+                }
+            }
+            Op::SwapRemove(index) if !oracle.is_empty() => {
+                let index = index % oracle.len();
+                if small.swap_remove(index) != oracle.swap_remove(index) {
+                    return false;
+                }
+            }
+            Op::Remove(_) | Op::SwapRemove(_) => {}
+            Op::Truncate(len) => {
+                small.truncate(len);
+                oracle.truncate(len);
+            }
+            Op::Clear => {
+                small.clear();
+                oracle.clear();
+            }
+            Op::Reserve(additional) => {
+                small.reserve(additional);
+                if small.capacity() < small.len() + additional {
+                    return false;
+                }
+            }
+            Op::ReserveExact(additional) => {
+                small.reserve_exact(additional);
+                if small.capacity() < small.len() + additional {
+                    return false;
+                }
+            }
+            Op::ShrinkToFit => {
+                small.shrink_to_fit();
+                if small.spilled() != (small.len() > small.inline_size()) {
+                    return false;
+                }
+            }
+            Op::Extend(values) => {
+                small.extend(values.iter().map(|&x| Counted::new(x, &alive)));
+                oracle.extend(values.iter().map(|&x| Counted::new(x, &alive)));
+            }
+            Op::Retain(modulo) => {
+                small.retain(|x| x.value % modulo == 0);
+                oracle.retain(|x| x.value % modulo == 0);
+            }
+            Op::Dedup => {
+                small.dedup();
+                oracle.dedup();
+            }
+            Op::Drain(a, b) => {
+                let a = a % (oracle.len() + 1);
+                let b = b % (oracle.len() + 1);
+                let range = a.min(b)..a.max(b);
+                if !small.drain(range.clone()).eq(oracle.drain(range)) {
+                    return false;
+                }
+            }
+            Op::Resize(len, x) => {
+                small.resize(len, Counted::new(x, &alive));
+                oracle.resize(len, Counted::new(x, &alive));
+            }
+            Op::Splice(a, b, values) => {
+                let a = a % (oracle.len() + 1);
+                let b = b % (oracle.len() + 1);
+                let range = a.min(b)..a.max(b);
+                let new = || values.iter().map(|&x| Counted::new(x, &alive));
+                if !small
+                    .splice(range.clone(), new())
+                    .eq(oracle.splice(range, new()))
+                {
+                    return false;
+                }
+            }
+        }
+
+        if small[..] != oracle[..]
+            || small.capacity() < small.len()
+            || alive.get() != small.len() + oracle.len()
+        {
+            return false;
+        }
+    }
+
+    drop(small);
+    drop(oracle);
+    alive.get() == 0
+}
+
+#[test]
+fn model_against_vec() {
+    QuickCheck::new()
+        .tests(500)
+        .quickcheck(apply as fn(Vec<Op>) -> bool);
+}
@@ -0,0 +1,58 @@
+//! Without the `alloc` feature vectors never spill: growing past the inline capacity fails like
+//! an allocation failure.
+
+use crate::{CollectionAllocErr, SmallVec};
+
+#[cfg(feature = "const_generics")]
+type V = SmallVec<u32, 2>;
+#[cfg(not(feature = "const_generics"))]
+type V = SmallVec<[u32; 2]>;
+
+#[test]
+fn test_try_push_past_inline_capacity() {
+    let mut v = V::new();
+    assert!(v.try_push(1).is_ok());
+    assert!(v.try_push(2).is_ok());
+    assert!(matches!(
+        v.try_push(3),
+        Err(CollectionAllocErr::AllocErr { .. })
+    ));
+    assert!(!v.spilled());
+    assert_eq!(&*v, &[1, 2]);
+}
+
+#[test]
+fn test_try_reserve_past_inline_capacity() {
+    let mut v = V::new();
+    v.push(1);
+    assert!(v.try_reserve(1).is_ok());
+    assert!(matches!(
+        v.try_reserve(2),
+        Err(CollectionAllocErr::AllocErr { .. })
+    ));
+    assert!(matches!(
+        v.try_reserve_exact(2),
+        Err(CollectionAllocErr::AllocErr { .. })
+    ));
+    assert!(matches!(
+        v.try_reserve(usize::MAX),
+        Err(CollectionAllocErr::CapacityOverflow)
+    ));
+    assert_eq!(v.capacity(), 2);
+    assert_eq!(&*v, &[1]);
+}
+
+#[test]
+#[should_panic(expected = "no heap without the `alloc` feature")]
+fn test_push_past_inline_capacity() {
+    let mut v = V::new();
+    v.extend([1, 2].iter().copied());
+    v.push(3);
+}
+
+#[test]
+#[should_panic(expected = "no heap without the `alloc` feature")]
+fn test_reserve_past_inline_capacity() {
+    let mut v = V::new();
+    v.reserve(3);
+}
@@ -0,0 +1,43 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+
+/// Trait to clone a slice into a `SmallVec`, like `to_vec` does for `Vec`
+///
+/// The inline capacity is usually inferred from the context, but can also be given explicitly.
+///
+/// ## Example
+///
+/// ```rust
+/// use smallvec::{SmallVec, ToSmallVec};
+///
+/// let words = ["a", "b", "c"];
+/// let v: SmallVec<[&str; 4]> = words[1..].to_smallvec();
+/// assert_eq!(&*v, &["b", "c"]);
+///
+/// let v = words.to_smallvec::<[&str; 2]>();
+/// assert!(v.spilled());
+/// ```
+pub trait ToSmallVec<T> {
+    /// Clones the elements of the slice into a new `SmallVec`
+    #[cfg(feature = "const_generics")]
+    fn to_smallvec<const N: usize>(&self) -> SmallVec<T, N>;
+
+    /// Clones the elements of the slice into a new `SmallVec`
+    #[cfg(not(feature = "const_generics"))]
+    fn to_smallvec<A: Array<Item = T>>(&self) -> SmallVec<A>;
+}
+
+impl<T: Clone> ToSmallVec<T> for [T] {
+    #[cfg(feature = "const_generics")]
+    #[inline]
+    fn to_smallvec<const N: usize>(&self) -> SmallVec<T, N> {
+        SmallVec::from(self)
+    }
+
+    #[cfg(not(feature = "const_generics"))]
+    #[inline]
+    fn to_smallvec<A: Array<Item = T>>(&self) -> SmallVec<A> {
+        SmallVec::from(self)
+    }
+}
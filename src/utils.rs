@@ -1,6 +1,191 @@
-use alloc::vec::Vec;
+use crate::{small_vec_data::NonMaxUsize, CollectionAllocErr};
+#[cfg(feature = "alloc")]
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc};
+use core::{
+    alloc::Layout,
+    cmp::Ordering,
+    mem,
+    ops::{Bound, Range, RangeBounds},
+    ptr::NonNull,
+};
 
-pub unsafe fn deallocate<T>(ptr: *mut T, capacity: usize) {
-    let _vec: Vec<T> = Vec::from_raw_parts(ptr, 0, capacity);
-    // Let it drop.
+/// Layout of a heap buffer holding `capacity` elements, matching the one `Vec` would use.
+///
+/// A capacity above `NonMaxUsize::MAX` is rejected even for zero-sized elements, since vectors
+/// store their capacity as a `NonMaxUsize`.
+pub fn layout_array<T>(capacity: usize) -> Result<Layout, CollectionAllocErr> {
+    if capacity > NonMaxUsize::MAX {
+        return Err(CollectionAllocErr::CapacityOverflow);
+    }
+    let size = mem::size_of::<T>()
+        .checked_mul(capacity)
+        .ok_or(CollectionAllocErr::CapacityOverflow)?;
+    if size > isize::MAX as usize {
+        return Err(CollectionAllocErr::CapacityOverflow);
+    }
+    Layout::from_size_align(size, mem::align_of::<T>())
+        .map_err(|_| CollectionAllocErr::CapacityOverflow)
+}
+
+/// Allocates a buffer for `capacity` elements that can later be handed to `Vec::from_raw_parts`.
+#[cfg(feature = "alloc")]
+pub fn allocate<T>(capacity: usize) -> Result<*mut T, CollectionAllocErr> {
+    let layout = layout_array::<T>(capacity)?;
+    if layout.size() == 0 {
+        return Ok(NonNull::dangling().as_ptr());
+    }
+    let ptr = unsafe { alloc(layout) };
+    if ptr.is_null() {
+        Err(CollectionAllocErr::AllocErr { layout })
+    } else {
+        Ok(ptr as *mut T)
+    }
+}
+
+/// Resizes a buffer previously obtained from `allocate` (or `Vec`), possibly moving it.
+/// On failure the original buffer is left untouched.
+#[cfg(feature = "alloc")]
+pub unsafe fn reallocate<T>(
+    ptr: *mut T,
+    capacity: usize,
+    new_capacity: usize,
+) -> Result<*mut T, CollectionAllocErr> {
+    let old_layout = layout_array::<T>(capacity)?;
+    let new_layout = layout_array::<T>(new_capacity)?;
+    if old_layout.size() == 0 {
+        return allocate(new_capacity);
+    }
+    if new_layout.size() == 0 {
+        dealloc(ptr as *mut u8, old_layout);
+        return Ok(NonNull::dangling().as_ptr());
+    }
+    let new_ptr = realloc(ptr as *mut u8, old_layout, new_layout.size());
+    if new_ptr.is_null() {
+        Err(CollectionAllocErr::AllocErr { layout: new_layout })
+    } else {
+        Ok(new_ptr as *mut T)
+    }
+}
+
+/// Without a heap, only empty buffers can be allocated.
+#[cfg(not(feature = "alloc"))]
+pub fn allocate<T>(capacity: usize) -> Result<*mut T, CollectionAllocErr> {
+    let layout = layout_array::<T>(capacity)?;
+    if layout.size() == 0 {
+        Ok(NonNull::dangling().as_ptr())
+    } else {
+        Err(CollectionAllocErr::AllocErr { layout })
+    }
+}
+
+/// Unwraps the result of a fallible allocation, panicking or aborting like `Vec` does on failure.
+#[inline]
+#[track_caller]
+pub fn infallible<T>(result: Result<T, CollectionAllocErr>) -> T {
+    match result {
+        Ok(x) => x,
+        Err(CollectionAllocErr::CapacityOverflow) => capacity_overflow(),
+        #[cfg(feature = "alloc")]
+        Err(CollectionAllocErr::AllocErr { layout }) => handle_alloc_error(layout),
+        #[cfg(not(feature = "alloc"))]
+        Err(CollectionAllocErr::AllocErr { layout }) => alloc_failed(layout),
+    }
+}
+
+/// Sorts `slice` with `compare`, keeping equal elements in order.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn stable_sort_by<T, F: FnMut(&T, &T) -> Ordering>(slice: &mut [T], compare: F) {
+    slice.sort_by(compare);
+}
+
+/// Without a heap there is no buffer for `slice::sort_by`, so an insertion sort is used.
+#[cfg(not(feature = "alloc"))]
+pub fn stable_sort_by<T, F: FnMut(&T, &T) -> Ordering>(slice: &mut [T], mut compare: F) {
+    for i in 1..slice.len() {
+        let mut j = i;
+        while j > 0 && compare(&slice[j - 1], &slice[j]) == Ordering::Greater {
+            slice.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+/// Resolves `range` against a slice of length `len`, panicking like slice indexing does if it is
+/// out of bounds.
+#[inline]
+#[track_caller]
+pub fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).unwrap_or_else(|| range_overflow()),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).unwrap_or_else(|| range_overflow()),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    if start > end || end > len {
+        range_out_of_bounds(start, end, len);
+    }
+    start..end
+}
+
+// The panics are kept out of line so the checks in the (inlined) callers stay small.
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn capacity_overflow() -> ! {
+    panic!("capacity overflow")
+}
+
+#[cfg(not(feature = "alloc"))]
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn alloc_failed(layout: Layout) -> ! {
+    panic!(
+        "memory allocation of {} bytes failed: no heap without the `alloc` feature",
+        layout.size()
+    )
+}
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn index_out_of_bounds(method: &str, index: usize, len: usize) -> ! {
+    panic!(
+        "`{}` index (is {}) should be < len (is {})",
+        method, index, len
+    )
+}
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn insertion_index_out_of_bounds(method: &str, index: usize, len: usize) -> ! {
+    panic!(
+        "`{}` index (is {}) should be <= len (is {})",
+        method, index, len
+    )
+}
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn range_out_of_bounds(start: usize, end: usize, len: usize) -> ! {
+    if start > end {
+        panic!("range starts at {} but ends at {}", start, end)
+    } else {
+        panic!("range end (is {}) should be <= len (is {})", end, len)
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn range_overflow() -> ! {
+    panic!("range bound overflows usize")
 }
@@ -0,0 +1,107 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+
+/// The length of the longest LEB128 encoding of a `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+#[inline]
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[inline]
+fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<$($impl_generics)*> SmallVec<$($decl_generics)*> {
+    /// Append `value` encoded as an unsigned LEB128 varint, and return the number of bytes
+    /// written.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::new();
+    /// assert_eq!(v.push_varint_u64(300), 2);
+    /// assert_eq!(&*v, &[0xac, 0x02]);
+    /// assert_eq!(v.read_varint_u64_at(0), Some((300, 2)));
+    /// ```
+    pub fn push_varint_u64(&mut self, mut value: u64) -> usize {
+        let mut buf = [0; MAX_VARINT_LEN];
+        let mut len = 0;
+        while value >= 0x80 {
+            buf[len] = value as u8 | 0x80;
+            value >>= 7;
+            len += 1;
+        }
+        buf[len] = value as u8;
+        len += 1;
+        self.extend_from_slice(&buf[..len]);
+        len
+    }
+
+    /// Append `value` zigzag-encoded as an unsigned LEB128 varint, so that numbers close to
+    /// zero take few bytes whatever their sign, and return the number of bytes written.
+    ///
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// let mut v: SmallVec<[u8; 8]> = SmallVec::new();
+    /// v.push_varint_i64(-1);
+    /// assert_eq!(&*v, &[0x01]);
+    /// assert_eq!(v.read_varint_i64_at(0), Some((-1, 1)));
+    /// ```
+    #[inline]
+    pub fn push_varint_i64(&mut self, value: i64) -> usize {
+        self.push_varint_u64(zigzag_encode(value))
+    }
+
+    /// Decode the unsigned LEB128 varint starting at byte `index`, and return it with the
+    /// number of bytes it takes.
+    ///
+    /// Returns `None` if the varint is truncated, if it doesn't fit in a `u64`, or if `index`
+    /// is out of bounds. Overlong encodings (with redundant trailing zero groups) are accepted
+    /// as long as they fit in ten bytes.
+    pub fn read_varint_u64_at(&self, index: usize) -> Option<(u64, usize)> {
+        let bytes = self.get(index..)?;
+        let mut value = 0;
+        for (i, &byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+            // The tenth byte only has room for the most significant bit of a `u64`.
+            if i == MAX_VARINT_LEN - 1 && byte > 1 {
+                return None;
+            }
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Some((value, i + 1));
+            }
+        }
+        None
+    }
+
+    /// Decode the zigzag-encoded LEB128 varint starting at byte `index`, and return it with the
+    /// number of bytes it takes.
+    ///
+    /// Fails in the same cases as [`read_varint_u64_at`][1].
+    ///
+    /// [1]: #method.read_varint_u64_at
+    #[inline]
+    pub fn read_varint_i64_at(&self, index: usize) -> Option<(i64, usize)> {
+        self.read_varint_u64_at(index)
+            .map(|(value, len)| (zigzag_decode(value), len))
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);
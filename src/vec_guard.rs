@@ -0,0 +1,76 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use alloc::vec::Vec;
+use core::{
+    mem,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+macro_rules! create_with_parts {
+(
+    <$($({$s_impl_ty_prefix:ident})? $s_impl_ty:ident$(: $s_impl_ty_bound:ident)?),*>,
+    <$s_decl_ty:ident$(, {$s_decl_const_ty:ident})?>,
+    $array_item:ty
+) => {
+
+/// A guard that presents the contents of a `SmallVec` as a `Vec` and moves them back when
+/// dropped.
+///
+/// A spilled vector hands its heap buffer over to the `Vec` without copying. An inline vector is
+/// copied into a new `Vec`, and copied back inline when the guard is dropped if it still fits.
+///
+/// Returned from [`SmallVec::as_vec_mut`][1].
+///
+/// [1]: struct.SmallVec.html#method.as_vec_mut
+pub struct VecGuard<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> {
+    pub(crate) vec: &'a mut SmallVec<$s_decl_ty$(, {$s_decl_const_ty})?>,
+    pub(crate) inner: Vec<$array_item>,
+    pub(crate) was_inline: bool,
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Deref
+    for VecGuard<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    type Target = Vec<$array_item>;
+    #[inline]
+    fn deref(&self) -> &Vec<$array_item> {
+        &self.inner
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> DerefMut
+    for VecGuard<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Vec<$array_item> {
+        &mut self.inner
+    }
+}
+
+impl<'a, $($($s_impl_ty_prefix)? $s_impl_ty$(: $s_impl_ty_bound)?),*> Drop
+    for VecGuard<'a, $s_decl_ty$(, {$s_decl_const_ty})?>
+{
+    fn drop(&mut self) {
+        let mut inner = mem::take(&mut self.inner);
+        if self.was_inline && inner.len() <= self.vec.inline_size() {
+            unsafe {
+                let len = inner.len();
+                inner.set_len(0);
+                ptr::copy_nonoverlapping(inner.as_ptr(), self.vec.triple_mut().0, len);
+                self.vec.set_len(len);
+            }
+        } else {
+            *self.vec = SmallVec::from_vec(inner);
+        }
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!(<T, {const} N: usize>, <T, {N}>, T);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!(<A: Array>, <A>, A::Item);
@@ -0,0 +1,64 @@
+#[cfg(not(feature = "const_generics"))]
+use crate::Array;
+use crate::SmallVec;
+use js_sys::Uint8Array;
+use wasm_bindgen::JsValue;
+
+macro_rules! create_with_parts {
+(
+    [$($impl_generics:tt)*],
+    [$($decl_generics:tt)*]
+) => {
+
+impl<'a, $($impl_generics)*> From<&'a SmallVec<$($decl_generics)*>> for Uint8Array {
+    /// Copy the bytes into a new JS `Uint8Array`.
+    #[inline]
+    fn from(vec: &'a SmallVec<$($decl_generics)*>) -> Self {
+        Uint8Array::from(&vec[..])
+    }
+}
+
+impl<$($impl_generics)*> From<SmallVec<$($decl_generics)*>> for Uint8Array {
+    /// Copy the bytes into a new JS `Uint8Array`.
+    #[inline]
+    fn from(vec: SmallVec<$($decl_generics)*>) -> Self {
+        Uint8Array::from(&vec)
+    }
+}
+
+impl<$($impl_generics)*> From<SmallVec<$($decl_generics)*>> for JsValue {
+    /// Copy the bytes into a new JS `Uint8Array`.
+    #[inline]
+    fn from(vec: SmallVec<$($decl_generics)*>) -> Self {
+        Uint8Array::from(&vec).into()
+    }
+}
+
+impl<'a, $($impl_generics)*> From<&'a Uint8Array> for SmallVec<$($decl_generics)*> {
+    /// Copy the contents of a JS `Uint8Array` straight into the vector's buffer.
+    fn from(array: &'a Uint8Array) -> Self {
+        let len = array.length() as usize;
+        let mut vec = SmallVec::with_capacity(len);
+        unsafe {
+            array.raw_copy_to_ptr(vec.as_mut_ptr());
+            vec.set_len(len);
+        }
+        vec
+    }
+}
+
+impl<$($impl_generics)*> From<Uint8Array> for SmallVec<$($decl_generics)*> {
+    /// Copy the contents of a JS `Uint8Array` straight into the vector's buffer.
+    #[inline]
+    fn from(array: Uint8Array) -> Self {
+        SmallVec::from(&array)
+    }
+}
+
+    }
+}
+
+#[cfg(feature = "const_generics")]
+create_with_parts!([const N: usize], [u8, { N }]);
+#[cfg(not(feature = "const_generics"))]
+create_with_parts!([A: Array<Item = u8>], [A]);